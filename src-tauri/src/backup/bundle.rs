@@ -0,0 +1,245 @@
+// src-tauri/src/backup/bundle.rs
+
+//! Backups as a single `.zip` that carries its images along.
+//!
+//! ```text
+//! manifest.json     format, version, createdAt, SHA-256 of every other file
+//! data.json         the backup document (see the module docs)
+//! images/<name>     each image an item refers to, decrypted
+//! thumbs/<name>     its preview (PNG)
+//! ```
+//!
+//! On import every checksum is verified before anything is written. Images
+//! go through `ImageStore::put`, so they may come back under a different
+//! (content-addressed) name; items are pointed at the new names. Bundles
+//! can replace the store or be merged into it.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::io::{Read, Seek, Write};
+
+use serde::{Deserialize, Serialize};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use super::validate::invalid;
+use super::merge::{merge, MergeReport};
+use super::{export, parse, restore, Backup, FieldError};
+use crate::error::{Error, Result};
+use crate::images::{hex_digest, ImageStore, ThumbnailCache};
+use crate::storage::{Database, ImportCounts};
+
+pub const BUNDLE_FORMAT: &str = "clipboard-manager-bundle";
+const BUNDLE_VERSION: u32 = 1;
+
+const MANIFEST: &str = "manifest.json";
+const DATA: &str = "data.json";
+const IMAGES_DIR: &str = "images/";
+const THUMBS_DIR: &str = "thumbs/";
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Manifest {
+    format: String,
+    version: u32,
+    #[serde(default)]
+    created_at: Option<String>,
+    /// Archive path → SHA-256 (hex) of every file but the manifest.
+    files: BTreeMap<String, String>,
+    /// Images items refer to that were already gone when exporting.
+    #[serde(default)]
+    missing_images: Vec<String>,
+}
+
+/// A bundle whose checksums all matched.
+struct Bundle {
+    backup: Backup,
+    images: BTreeMap<String, Vec<u8>>,
+    thumbs: BTreeMap<String, Vec<u8>>,
+}
+
+/// Writes the backup and every image it refers to into `out`.
+pub fn export_bundle<W: Write + Seek>(
+    db: &Database,
+    images: &ImageStore,
+    thumbs: &ThumbnailCache,
+    include_secrets: bool,
+    out: W,
+) -> Result<()> {
+    let backup = export(db, include_secrets)?;
+    let mut files = BTreeMap::new();
+    let mut missing_images = Vec::new();
+
+    for file_name in referenced_images(&backup) {
+        let bytes = match images.get(&file_name) {
+            Ok(bytes) => bytes,
+            Err(Error::Io(err)) if err.kind() == std::io::ErrorKind::NotFound => {
+                missing_images.push(file_name);
+                continue;
+            }
+            Err(err) => return Err(err),
+        };
+        // Previews are a cache; one that can't be rendered is just left out.
+        if let Ok(thumb) = thumbs.get(&file_name) {
+            files.insert(format!("{THUMBS_DIR}{file_name}"), thumb);
+        }
+        files.insert(format!("{IMAGES_DIR}{file_name}"), bytes);
+    }
+    files.insert(DATA.to_string(), backup.to_json()?.into_bytes());
+
+    let manifest = Manifest {
+        format: BUNDLE_FORMAT.into(),
+        version: BUNDLE_VERSION,
+        created_at: backup.created_at.clone(),
+        files: files.iter().map(|(path, bytes)| (path.clone(), hex_digest(bytes))).collect(),
+        missing_images,
+    };
+
+    let mut zip = ZipWriter::new(out);
+    let deflated = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    // Images are compressed already.
+    let stored = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+    zip.start_file(MANIFEST, deflated)?;
+    zip.write_all(&serde_json::to_vec_pretty(&manifest)?)?;
+    for (path, bytes) in &files {
+        zip.start_file(path.as_str(), if path == DATA { deflated } else { stored })?;
+        zip.write_all(bytes)?;
+    }
+    zip.finish()?;
+    Ok(())
+}
+
+/// Replaces the store with the contents of a bundle, images included.
+pub fn restore_bundle<R: Read + Seek>(
+    db: &Database,
+    images: &ImageStore,
+    thumbs: &ThumbnailCache,
+    input: R,
+) -> Result<ImportCounts> {
+    let mut bundle = read_bundle(input)?;
+    store_images(images, thumbs, &bundle)?;
+    point_at_stored_names(&mut bundle)?;
+    restore(db, &bundle.backup)
+}
+
+/// Merges a bundle into the store (see `merge`). A dry run writes no
+/// images either.
+pub fn merge_bundle<R: Read + Seek>(
+    db: &Database,
+    images: &ImageStore,
+    thumbs: &ThumbnailCache,
+    input: R,
+    dry_run: bool,
+) -> Result<MergeReport> {
+    let mut bundle = read_bundle(input)?;
+    if !dry_run {
+        store_images(images, thumbs, &bundle)?;
+    }
+    // Before merging, so duplicate images are recognised by name.
+    point_at_stored_names(&mut bundle)?;
+    merge(db, &bundle.backup, dry_run)
+}
+
+/// Reads and verifies everything without writing anything.
+fn read_bundle<R: Read + Seek>(input: R) -> Result<Bundle> {
+    let mut zip = ZipArchive::new(input)?;
+    let manifest: Manifest = match read_entry(&mut zip, MANIFEST)? {
+        Some(bytes) => serde_json::from_slice(&bytes).map_err(|err| invalid(MANIFEST, &err.to_string()))?,
+        None => return Err(invalid(MANIFEST, "is missing; not a backup bundle")),
+    };
+    if manifest.format != BUNDLE_FORMAT {
+        return Err(invalid(MANIFEST, &format!("expected format \"{BUNDLE_FORMAT}\"")));
+    }
+    if manifest.version > BUNDLE_VERSION {
+        return Err(invalid(MANIFEST, &format!("bundle version {} is newer than this app supports", manifest.version)));
+    }
+
+    let mut problems = Vec::new();
+    let mut contents = BTreeMap::new();
+    for (path, expected) in &manifest.files {
+        match read_entry(&mut zip, path)? {
+            Some(bytes) if hex_digest(&bytes) == *expected => {
+                contents.insert(path.clone(), bytes);
+            }
+            Some(_) => problems.push(FieldError { path: path.clone(), message: "checksum mismatch".into() }),
+            None => problems.push(FieldError { path: path.clone(), message: "listed in the manifest but missing".into() }),
+        }
+    }
+    for path in zip.file_names() {
+        if path != MANIFEST && !manifest.files.contains_key(path) {
+            problems.push(FieldError { path: path.to_string(), message: "not listed in the manifest".into() });
+        }
+    }
+    if !problems.is_empty() {
+        return Err(Error::InvalidBackup(problems));
+    }
+
+    let Some(data) = contents.remove(DATA) else {
+        return Err(invalid(DATA, "is missing"));
+    };
+    let data = String::from_utf8(data).map_err(|_| invalid(DATA, "is not UTF-8"))?;
+    let backup = parse(&data)?;
+
+    let mut bundle = Bundle { backup, images: BTreeMap::new(), thumbs: BTreeMap::new() };
+    for (path, bytes) in contents {
+        if let Some(file_name) = path.strip_prefix(IMAGES_DIR) {
+            bundle.images.insert(file_name.to_string(), bytes);
+        } else if let Some(file_name) = path.strip_prefix(THUMBS_DIR) {
+            bundle.thumbs.insert(file_name.to_string(), bytes);
+        }
+    }
+    Ok(bundle)
+}
+
+fn store_images(images: &ImageStore, thumbs: &ThumbnailCache, bundle: &Bundle) -> Result<()> {
+    for (file_name, bytes) in &bundle.images {
+        let stored = images.put(bytes)?;
+        if let Some(thumb) = bundle.thumbs.get(file_name) {
+            thumbs.insert(&stored.sha256, thumb)?;
+        }
+    }
+    Ok(())
+}
+
+/// Images may come back under a different (content-addressed) name;
+/// renames the references accordingly.
+fn point_at_stored_names(bundle: &mut Bundle) -> Result<()> {
+    let renamed = bundle
+        .images
+        .iter()
+        .map(|(file_name, bytes)| Ok((file_name.as_str(), ImageStore::name_for(bytes)?)))
+        .collect::<Result<HashMap<_, _>>>()?;
+
+    let backup = &mut bundle.backup;
+    let notes = backup.projects.iter_mut().flat_map(|p| &mut p.folders).flat_map(|f| &mut f.notes);
+    let image_data = backup.history.iter_mut().map(|h| &mut h.image_data).chain(notes.map(|n| &mut n.image_data));
+    for image_data in image_data.flatten() {
+        if let Some(new_name) = renamed.get(image_data.as_str()) {
+            image_data.clone_from(new_name);
+        }
+    }
+    Ok(())
+}
+
+/// Image file names in `backup`, without legacy inline `data:` URLs.
+fn referenced_images(backup: &Backup) -> BTreeSet<String> {
+    let notes = backup.projects.iter().flat_map(|p| &p.folders).flat_map(|f| &f.notes);
+    backup
+        .history
+        .iter()
+        .filter_map(|h| h.image_data.as_ref())
+        .chain(notes.filter_map(|n| n.image_data.as_ref()))
+        .filter(|name| !name.starts_with("data:"))
+        .cloned()
+        .collect()
+}
+
+fn read_entry<R: Read + Seek>(zip: &mut ZipArchive<R>, path: &str) -> Result<Option<Vec<u8>>> {
+    let mut entry = match zip.by_name(path) {
+        Ok(entry) => entry,
+        Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+        Err(err) => return Err(err.into()),
+    };
+    let mut bytes = Vec::new();
+    entry.read_to_end(&mut bytes)?;
+    Ok(Some(bytes))
+}
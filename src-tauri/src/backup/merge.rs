@@ -0,0 +1,285 @@
+// src-tauri/src/backup/merge.rs
+
+//! Combining a backup with what is already in the store.
+//!
+//! Projects are matched by id, then by name; folders the same way within
+//! their project. Notes are duplicates when their folder already holds the
+//! same content, history items when any item does (content type, text and
+//! image). Tags are unioned. Nothing already stored is ever changed.
+//!
+//! A record whose id is taken by something different is a conflict: it is
+//! added under a fresh id and reported.
+//!
+//! Merged history keeps its copy times, so it sorts among the local items
+//! by age. Like imported history, it is exempt from retention age limits.
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::Utc;
+use rusqlite::Transaction;
+use serde::Serialize;
+
+use super::Backup;
+use crate::classify::language_of;
+use crate::error::Result;
+use crate::ids;
+use crate::images::hex_digest;
+use crate::models::{ContentType, Folder, HistoryItem, NoteItem, Project};
+use crate::storage::{insert_history_item, insert_note, mark_imported, Database, ImportCounts};
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeConflict {
+    /// Location in the backup, e.g. `projects/0/folders/2/notes/5`.
+    pub path: String,
+    pub id: String,
+    /// The id the record was added under instead.
+    pub new_id: String,
+}
+
+#[derive(Debug, Default, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeReport {
+    /// `true` when this is a preview and nothing was written.
+    pub dry_run: bool,
+    pub added: ImportCounts,
+    /// Records already present: duplicates, and projects or folders that
+    /// were merged into an existing one.
+    pub skipped: ImportCounts,
+    pub conflicts: Vec<MergeConflict>,
+}
+
+/// Adds whatever `backup` has that the store doesn't. With `dry_run` the
+/// same work is done and rolled back, so the report is exactly what a real
+/// merge would do.
+pub fn merge(db: &Database, backup: &Backup, dry_run: bool) -> Result<MergeReport> {
+    let run = |tx: &Transaction| {
+        let mut merger = Merger {
+            tx,
+            known: Known::load(tx)?,
+            report: MergeReport { dry_run, ..Default::default() },
+            merged_at: Utc::now().timestamp_millis(),
+        };
+        for (index, project) in backup.projects.iter().enumerate() {
+            merger.project(project, &format!("projects/{index}"))?;
+        }
+        // By copy time, oldest first, so `seq` breaks ties the same way.
+        let mut history: Vec<_> = backup.history.iter().enumerate().rev().collect();
+        history.sort_by_key(|(_, item)| item.created_at);
+        for (index, item) in history {
+            merger.history_item(item, &format!("history/{index}"))?;
+        }
+        for tag in &backup.global_tags {
+            merger.tag(tag)?;
+        }
+        Ok(merger.report)
+    };
+    if dry_run {
+        db.rehearse(run)
+    } else {
+        db.write(run)
+    }
+}
+
+/// What the store holds, kept up to date as records are added so the
+/// backup's own duplicates are caught too.
+#[derive(Default)]
+struct Known {
+    projects: HashSet<String>,
+    project_names: HashMap<String, String>,
+    /// Folder id → project id.
+    folders: HashMap<String, String>,
+    /// (project id, folder name) → folder id.
+    folder_names: HashMap<(String, String), String>,
+    /// (folder id, content hash).
+    note_contents: HashSet<(String, String)>,
+    history_contents: HashSet<String>,
+    /// Every id in use, of any kind.
+    ids: HashSet<String>,
+}
+
+impl Known {
+    fn load(tx: &Transaction) -> Result<Self> {
+        let mut known = Known::default();
+
+        let mut stmt = tx.prepare("SELECT id FROM entity_ids")?;
+        known.ids = stmt.query_map([], |row| row.get(0))?.collect::<rusqlite::Result<_>>()?;
+
+        let mut stmt = tx.prepare("SELECT id, name FROM projects")?;
+        let mut rows = stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            known.add_project(row.get(0)?, &row.get::<_, String>(1)?);
+        }
+
+        let mut stmt = tx.prepare("SELECT id, project_id, name FROM folders")?;
+        let mut rows = stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            known.add_folder(row.get(0)?, row.get(1)?, &row.get::<_, String>(2)?);
+        }
+
+        let mut stmt = tx.prepare("SELECT folder_id, content_type, unseal(text), image_data FROM notes")?;
+        let mut rows = stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            let hash = content_hash(
+                ContentType::parse(&row.get::<_, String>(1)?),
+                &row.get::<_, String>(2)?,
+                row.get::<_, Option<String>>(3)?.as_deref(),
+            );
+            known.note_contents.insert((row.get(0)?, hash));
+        }
+
+        let mut stmt = tx.prepare("SELECT content_type, unseal(text), image_data FROM history")?;
+        let mut rows = stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            let hash = content_hash(
+                ContentType::parse(&row.get::<_, String>(0)?),
+                &row.get::<_, String>(1)?,
+                row.get::<_, Option<String>>(2)?.as_deref(),
+            );
+            known.history_contents.insert(hash);
+        }
+
+        Ok(known)
+    }
+
+    fn add_project(&mut self, id: String, name: &str) {
+        self.project_names.entry(normalize(name)).or_insert_with(|| id.clone());
+        self.ids.insert(id.clone());
+        self.projects.insert(id);
+    }
+
+    fn add_folder(&mut self, id: String, project_id: String, name: &str) {
+        self.folder_names.entry((project_id.clone(), normalize(name))).or_insert_with(|| id.clone());
+        self.ids.insert(id.clone());
+        self.folders.insert(id, project_id);
+    }
+}
+
+struct Merger<'a> {
+    tx: &'a Transaction<'a>,
+    known: Known,
+    report: MergeReport,
+    /// Unix ms; merged history counts as imported from this moment.
+    merged_at: i64,
+}
+
+impl Merger<'_> {
+    fn project(&mut self, project: &Project, path: &str) -> Result<()> {
+        let by_name = self.known.project_names.get(&normalize(&project.name)).cloned();
+        let project_id = if self.known.projects.contains(&project.id) {
+            self.report.skipped.projects += 1;
+            project.id.clone()
+        } else if let Some(id) = by_name {
+            self.report.skipped.projects += 1;
+            id
+        } else {
+            let id = self.free_id(&project.id, path);
+            self.tx.execute(
+                "INSERT INTO projects (id, name, position)
+                 VALUES (?1, ?2, (SELECT COALESCE(MAX(position) + 1, 0) FROM projects))",
+                (&id, &project.name),
+            )?;
+            self.known.add_project(id.clone(), &project.name);
+            self.report.added.projects += 1;
+            id
+        };
+
+        for (index, folder) in project.folders.iter().enumerate() {
+            self.folder(folder, &project_id, &format!("{path}/folders/{index}"))?;
+        }
+        Ok(())
+    }
+
+    fn folder(&mut self, folder: &Folder, project_id: &str, path: &str) -> Result<()> {
+        let owner = self.known.folders.get(&folder.id).cloned();
+        let by_name = self.known.folder_names.get(&(project_id.to_string(), normalize(&folder.name))).cloned();
+        let folder_id = match (owner, by_name) {
+            (Some(owner), _) if owner == project_id => {
+                self.report.skipped.folders += 1;
+                folder.id.clone()
+            }
+            (_, Some(id)) => {
+                self.report.skipped.folders += 1;
+                id
+            }
+            (_, None) => {
+                let id = self.free_id(&folder.id, path);
+                self.tx.execute(
+                    "INSERT INTO folders (id, project_id, name, position)
+                     VALUES (?1, ?2, ?3, (SELECT COALESCE(MAX(position) + 1, 0) FROM folders WHERE project_id = ?2))",
+                    (&id, project_id, &folder.name),
+                )?;
+                self.known.add_folder(id.clone(), project_id.to_string(), &folder.name);
+                self.report.added.folders += 1;
+                id
+            }
+        };
+
+        for (index, note) in folder.notes.iter().enumerate() {
+            self.note(note, &folder_id, &format!("{path}/notes/{index}"))?;
+        }
+        Ok(())
+    }
+
+    fn note(&mut self, note: &NoteItem, folder_id: &str, path: &str) -> Result<()> {
+        let hash = content_hash(note.content_type, &note.text, note.image_data.as_deref());
+        if !self.known.note_contents.insert((folder_id.to_string(), hash)) {
+            self.report.skipped.notes += 1;
+            return Ok(());
+        }
+
+        let mut note = note.clone();
+        note.id = self.free_id(&note.id, path);
+        note.language = note.language.or_else(|| language_of(note.content_type, &note.text));
+        insert_note(self.tx, folder_id, &note)?;
+        self.known.ids.insert(note.id);
+        self.report.added.notes += 1;
+        Ok(())
+    }
+
+    fn history_item(&mut self, item: &HistoryItem, path: &str) -> Result<()> {
+        let hash = content_hash(item.content_type, &item.text, item.image_data.as_deref());
+        if !self.known.history_contents.insert(hash) {
+            self.report.skipped.history += 1;
+            return Ok(());
+        }
+
+        let mut item = item.clone();
+        item.id = self.free_id(&item.id, path);
+        item.language = item.language.or_else(|| language_of(item.content_type, &item.text));
+        insert_history_item(self.tx, &item)?;
+        mark_imported(self.tx, &item.id, self.merged_at)?;
+        self.known.ids.insert(item.id);
+        self.report.added.history += 1;
+        Ok(())
+    }
+
+    fn tag(&mut self, tag: &str) -> Result<()> {
+        if self.tx.execute("INSERT OR IGNORE INTO tags (name) VALUES (?1)", [tag])? > 0 {
+            self.report.added.tags += 1;
+        } else {
+            self.report.skipped.tags += 1;
+        }
+        Ok(())
+    }
+
+    /// `id` if no record of any kind has it yet. Otherwise a fresh id, and
+    /// the conflict is recorded.
+    fn free_id(&mut self, id: &str, path: &str) -> String {
+        if !self.known.ids.contains(id) {
+            return id.to_string();
+        }
+        let new_id = ids::new_id();
+        self.report.conflicts.push(MergeConflict { path: path.to_string(), id: id.to_string(), new_id: new_id.clone() });
+        new_id
+    }
+}
+
+fn content_hash(content_type: ContentType, text: &str, image_data: Option<&str>) -> String {
+    let key = format!("{}\0{text}\0{}", content_type.as_str(), image_data.unwrap_or_default());
+    hex_digest(key.as_bytes())
+}
+
+fn normalize(name: &str) -> String {
+    name.trim().to_lowercase()
+}
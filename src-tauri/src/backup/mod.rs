@@ -0,0 +1,181 @@
+// src-tauri/src/backup/mod.rs
+
+//! Backup and restore as a single JSON document.
+//!
+//! The current format (version 5):
+//!
+//! ```text
+//! {
+//!   "format": "clipboard-manager-backup",
+//!   "version": 5,
+//!   "createdAt": "2026-01-31T18:04:05Z",     RFC 3339, UTC
+//!   "history": [HistoryItem, ...],           newest first
+//!   "projects": [Project, ...],              with folders and notes, in display order
+//!   "globalTags": ["work", ...]
+//! }
+//! ```
+//!
+//! Items, projects, folders and notes have the same fields as in
+//! `src/types.ts`; their `createdAt` and `updatedAt` are Unix milliseconds.
+//! Ids are unique across all four kinds. Older files (`version` 1 to 4)
+//! are upgraded on read; see `upgrade`.
+//!
+//! A restore first validates the whole file and collects every problem it
+//! finds, each with the path of the offending field. Only a file without
+//! problems replaces the live data, in one transaction.
+//!
+//! Instead of replacing everything, a backup can also be merged into the
+//! store; see `merge`. `bundle` wraps the same document in a `.zip`
+//! together with the images it refers to.
+
+mod bundle;
+mod merge;
+mod upgrade;
+mod validate;
+
+#[cfg(test)]
+mod tests;
+
+use std::collections::HashSet;
+
+use chrono::{SecondsFormat, Utc};
+use rusqlite::Transaction;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::classify::language_of;
+use crate::error::Result;
+use crate::models::{HistoryItem, Project};
+use crate::storage::{insert_history_item, insert_note, Database, ImportCounts};
+
+pub use bundle::{export_bundle, merge_bundle, restore_bundle};
+pub use merge::{merge, MergeReport};
+pub use validate::FieldError;
+
+pub const FORMAT: &str = "clipboard-manager-backup";
+pub const VERSION: u32 = 5;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Backup {
+    pub format: String,
+    pub version: u32,
+    /// Missing from some very early exports.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<String>,
+    pub history: Vec<HistoryItem>,
+    pub projects: Vec<Project>,
+    pub global_tags: Vec<String>,
+}
+
+impl Backup {
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn counts(&self) -> ImportCounts {
+        let folders = self.projects.iter().flat_map(|p| &p.folders);
+        ImportCounts {
+            projects: self.projects.len(),
+            folders: folders.clone().count(),
+            notes: folders.map(|f| f.notes.len()).sum(),
+            history: self.history.len(),
+            tags: self.global_tags.len(),
+            trash_purged: 0,
+        }
+    }
+}
+
+/// Snapshot of the store. Items containing credentials are left out unless
+/// `include_secrets` is set.
+pub fn export(db: &Database, include_secrets: bool) -> Result<Backup> {
+    let mut data = db.load()?;
+    if !include_secrets {
+        data.history.retain(|item| !item.is_secret);
+        for folder in data.projects.iter_mut().flat_map(|p| &mut p.folders) {
+            folder.notes.retain(|note| !note.is_secret);
+        }
+    }
+    Ok(Backup {
+        format: FORMAT.into(),
+        version: VERSION,
+        created_at: Some(Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true)),
+        history: data.history,
+        projects: data.projects,
+        global_tags: data.global_tags,
+    })
+}
+
+/// Reads a backup of any known version. Fails with `Error::InvalidBackup`
+/// listing every problem, or `Error::Json` if it isn't JSON at all.
+pub fn parse(json: &str) -> Result<Backup> {
+    let mut value: Value = serde_json::from_str(json)?;
+    upgrade::to_current(&mut value)?;
+    validate::backup(&value)
+}
+
+/// Replaces all projects, history and tags with the contents of `backup`.
+/// The trash is kept, except for entries holding an id the backup brings
+/// back: those are dropped (their images go with the next GC) and counted
+/// in `trash_purged`. Settings and the encryption state are kept.
+pub fn restore(db: &Database, backup: &Backup) -> Result<ImportCounts> {
+    db.write(|tx| {
+        tx.execute_batch("DELETE FROM history; DELETE FROM projects; DELETE FROM tags;")?;
+        let mut counts = backup.counts();
+        counts.trash_purged = release_trashed_ids(tx, backup)?;
+        insert_projects(tx, &backup.projects)?;
+        // Oldest first, so `seq` keeps the order.
+        for item in backup.history.iter().rev() {
+            let mut item = item.clone();
+            item.language = item.language.or_else(|| language_of(item.content_type, &item.text));
+            insert_history_item(tx, &item)?;
+        }
+        for tag in &backup.global_tags {
+            tx.execute("INSERT OR IGNORE INTO tags (name) VALUES (?1)", [tag])?;
+        }
+        Ok(counts)
+    })
+}
+
+/// Deletes the trash entries that reserve any id in `backup`, since putting
+/// them back later would collide. Returns how many were deleted.
+fn release_trashed_ids(tx: &Transaction, backup: &Backup) -> Result<usize> {
+    let folders = backup.projects.iter().flat_map(|p| &p.folders);
+    let mut ids: Vec<&str> = backup.projects.iter().map(|p| p.id.as_str()).collect();
+    ids.extend(folders.clone().map(|f| f.id.as_str()));
+    ids.extend(folders.flat_map(|f| &f.notes).map(|n| n.id.as_str()));
+    ids.extend(backup.history.iter().map(|h| h.id.as_str()));
+
+    let mut stmt = tx.prepare("SELECT trash_id FROM trash_ids WHERE id = ?1 UNION SELECT id FROM trash WHERE id = ?1")?;
+    let mut entries = HashSet::new();
+    for id in ids {
+        for entry in stmt.query_map([id], |row| row.get::<_, String>(0))? {
+            entries.insert(entry?);
+        }
+    }
+    for entry in &entries {
+        tx.execute("DELETE FROM trash WHERE id = ?1", [entry])?;
+    }
+    Ok(entries.len())
+}
+
+fn insert_projects(tx: &Transaction, projects: &[Project]) -> Result<()> {
+    for (position, project) in projects.iter().enumerate() {
+        tx.execute(
+            "INSERT INTO projects (id, name, position) VALUES (?1, ?2, ?3)",
+            (&project.id, &project.name, position as i64),
+        )?;
+        for (position, folder) in project.folders.iter().enumerate() {
+            tx.execute(
+                "INSERT INTO folders (id, project_id, name, position) VALUES (?1, ?2, ?3, ?4)",
+                (&folder.id, &project.id, &folder.name, position as i64),
+            )?;
+            for note in &folder.notes {
+                let mut note = note.clone();
+                note.language = note.language.or_else(|| language_of(note.content_type, &note.text));
+                insert_note(tx, &folder.id, &note)?;
+            }
+        }
+    }
+    Ok(())
+}
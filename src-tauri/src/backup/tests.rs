@@ -0,0 +1,363 @@
+// src-tauri/src/backup/tests.rs
+
+use std::fs;
+use std::io::{Cursor, Read, Write};
+
+use image::{ImageFormat, RgbaImage};
+use serde_json::json;
+
+use super::*;
+use crate::error::Error;
+use crate::images::{ImageStore, ThumbnailCache};
+use crate::models::{ContentType, NoteItem};
+use crate::retention::{self, RetentionPolicy};
+use crate::trash::{self, TrashKind};
+
+fn item(id: &str, text: &str) -> HistoryItem {
+    HistoryItem {
+        id: id.into(),
+        text: text.into(),
+        created_at: 1_700_000_000_000,
+        updated_at: 1_700_000_000_000,
+        utc_offset: 0,
+        content_type: ContentType::Text,
+        image_data: None,
+        language: None,
+        is_favorite: false,
+        is_secret: false,
+        expires_at: None,
+    }
+}
+
+fn note(id: &str, text: &str) -> NoteItem {
+    NoteItem {
+        id: id.into(),
+        text: text.into(),
+        created_at: 1_700_000_000_000,
+        updated_at: 1_700_000_000_000,
+        utc_offset: 0,
+        content_type: ContentType::Text,
+        tags: vec!["work".into()],
+        image_data: None,
+        language: None,
+        is_favorite: false,
+        is_secret: false,
+    }
+}
+
+fn populated() -> Database {
+    let db = Database::open_in_memory().unwrap();
+    db.push_history_item(&item("1", "first"), None).unwrap();
+    db.push_history_item(&HistoryItem { is_favorite: true, ..item("2", "second") }, None).unwrap();
+    db.add_project("p2", "Work").unwrap();
+    db.add_folder("p2", "f2", "Snippets").unwrap();
+    db.add_note("f2", &note("n1", "fn main() {}")).unwrap();
+    db.add_global_tag("work").unwrap();
+    db
+}
+
+fn problems(result: Result<Backup>) -> Vec<String> {
+    match result {
+        Err(Error::InvalidBackup(errors)) => errors.iter().map(ToString::to_string).collect(),
+        other => panic!("expected InvalidBackup, got {other:?}"),
+    }
+}
+
+#[test]
+fn round_trip_restores_everything() {
+    let source = populated();
+    let json = export(&source, false).unwrap().to_json().unwrap();
+
+    let target = Database::open_in_memory().unwrap();
+    target.push_history_item(&item("old", "replaced"), None).unwrap();
+    let counts = restore(&target, &parse(&json).unwrap()).unwrap();
+
+    assert_eq!((counts.projects, counts.folders, counts.notes, counts.history), (2, 2, 1, 2));
+    assert_eq!(target.load().unwrap(), source.load().unwrap());
+}
+
+#[test]
+fn restoring_only_drops_trash_entries_the_backup_collides_with() {
+    let json = export(&populated(), false).unwrap().to_json().unwrap();
+    let target = populated();
+    target.add_note("f2", &note("n9", "not in the backup")).unwrap();
+    trash::delete(&target, TrashKind::Note, "n9").unwrap();
+    trash::delete(&target, TrashKind::Project, "p2").unwrap();
+
+    let counts = restore(&target, &parse(&json).unwrap()).unwrap();
+
+    assert_eq!(counts.trash_purged, 1);
+    let kept = trash::list(&target).unwrap();
+    assert_eq!(kept.iter().map(|entry| entry.id.as_str()).collect::<Vec<_>>(), ["n9"]);
+    assert_eq!(target.load().unwrap().projects[1].folders[0].notes[0].id, "n1");
+    // The kept entry still holds its id.
+    let reserved: Vec<String> = target
+        .read(|conn| {
+            let mut stmt = conn.prepare("SELECT id FROM trash_ids ORDER BY id")?;
+            let rows = stmt.query_map([], |row| row.get(0))?;
+            Ok(rows.collect::<rusqlite::Result<_>>()?)
+        })
+        .unwrap();
+    assert_eq!(reserved, ["n9"]);
+}
+
+#[test]
+fn webview_exports_are_upgraded() {
+    let v1 = json!({
+        "version": 1,
+        "exportDate": "2025-03-01T10:00:00.000Z",
+        // 2023-11-14T22:13:20Z, copied at UTC+3.
+        "history": [{ "id": "1700000000000", "text": "hello", "date": "01:13", "contentType": "text" }],
+        "projects": [],
+    });
+    let backup = parse(&v1.to_string()).unwrap();
+    assert_eq!(backup.version, VERSION);
+    assert_eq!(backup.created_at.as_deref(), Some("2025-03-01T10:00:00.000Z"));
+    let hello = &backup.history[0];
+    assert_eq!((hello.created_at, hello.updated_at, hello.utc_offset), (1_700_000_000_000, 1_700_000_000_000, 180));
+    assert!(backup.global_tags.is_empty());
+
+    let v2 = json!({
+        "history": [],
+        "projects": [{ "id": "p1", "name": "Личное", "folders": [{ "id": "f1", "name": "Входящие", "notes": [] }] }],
+        "globalTags": ["work"],
+        "version": 2,
+        "date": "2025-03-02T10:00:00.000Z",
+    });
+    let backup = parse(&v2.to_string()).unwrap();
+    assert_eq!(backup.created_at.as_deref(), Some("2025-03-02T10:00:00.000Z"));
+    assert_eq!(backup.projects[0].folders[0].name, "Входящие");
+}
+
+#[test]
+fn every_problem_is_reported_with_its_path() {
+    let json = json!({
+        "version": 2,
+        "history": [
+            { "id": "1", "date": "10:00", "contentType": "text" },
+            { "id": "1", "text": "again", "date": "10:00", "contentType": "hologram" },
+        ],
+        "projects": [{ "id": "p1", "name": " ", "folders": [{ "id": "f1", "name": "Inbox", "notes": [
+            { "id": "n1", "text": "x", "date": "10:00", "contentType": "text", "imageData": "../../etc/passwd", "tags": [3] },
+        ]}]}],
+        "globalTags": "work",
+    });
+
+    let problems = problems(parse(&json.to_string()));
+    let paths: Vec<_> = problems.iter().map(|p| p.split(':').next().unwrap()).collect();
+    assert_eq!(paths, [
+        "history/0/text",
+        "history/1/id",
+        "history/1/contentType",
+        "projects/0/name",
+        "projects/0/folders/0/notes/0/tags/0",
+        "projects/0/folders/0/notes/0/imageData",
+        "globalTags",
+    ]);
+    assert!(problems[1].contains("duplicate history item id '1', first used at history/0"));
+}
+
+#[test]
+fn ids_shared_across_kinds_are_separated_in_old_files_only() {
+    let shared = |version| {
+        json!({
+            "format": FORMAT,
+            "version": version,
+            "history": [{ "id": "1700000000000", "text": "copied", "createdAt": 1, "contentType": "text" }],
+            "projects": [{ "id": "p1", "name": "Личное", "folders": [{ "id": "1700000000000", "name": "Inbox", "notes": [] }] }],
+        })
+        .to_string()
+    };
+
+    let backup = parse(&shared(4)).unwrap();
+    assert_eq!(backup.projects[0].folders[0].id, "1700000000000");
+    assert_ne!(backup.history[0].id, "1700000000000");
+
+    let problems = problems(parse(&shared(VERSION)));
+    assert_eq!(problems, ["projects/0/folders/0/id: duplicate folder id '1700000000000', first used at history/0"]);
+}
+
+#[test]
+fn invalid_files_leave_live_data_alone() {
+    let db = populated();
+    let before = db.load().unwrap();
+
+    assert!(matches!(parse("{ not json"), Err(Error::Json(_))));
+    let newer = json!({ "format": FORMAT, "version": VERSION + 1, "history": [], "projects": [], "globalTags": [] });
+    assert_eq!(problems(parse(&newer.to_string())), [format!(
+        "version: version {} is newer than this app supports ({VERSION})",
+        VERSION + 1
+    )]);
+    let foreign = json!({ "format": "something-else", "version": 3 });
+    assert!(matches!(parse(&foreign.to_string()), Err(Error::InvalidBackup(_))));
+
+    assert_eq!(db.load().unwrap(), before);
+}
+
+#[test]
+fn secrets_are_left_out_unless_asked_for() {
+    let db = populated();
+    db.push_history_item(&HistoryItem { is_secret: true, ..item("3", "hunter2") }, None).unwrap();
+
+    assert!(export(&db, false).unwrap().history.iter().all(|h| h.id != "3"));
+    assert_eq!(export(&db, true).unwrap().history[0].id, "3");
+}
+
+struct Store {
+    db: Database,
+    images: ImageStore,
+    thumbs: ThumbnailCache,
+    dir: tempfile::TempDir,
+}
+
+impl Store {
+    fn new() -> Self {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::open_in_memory().unwrap();
+        let images = ImageStore::new(db.clone(), dir.path().join("images"));
+        let thumbs = ThumbnailCache::new(images.clone(), dir.path().join("thumbs"));
+        Self { db, images, thumbs, dir }
+    }
+
+    fn bundle(&self) -> Vec<u8> {
+        let mut zip = Cursor::new(Vec::new());
+        export_bundle(&self.db, &self.images, &self.thumbs, false, &mut zip).unwrap();
+        zip.into_inner()
+    }
+}
+
+fn png() -> Vec<u8> {
+    let mut out = Cursor::new(Vec::new());
+    RgbaImage::from_pixel(4, 4, image::Rgba([10, 200, 10, 255])).write_to(&mut out, ImageFormat::Png).unwrap();
+    out.into_inner()
+}
+
+fn image_item(id: &str, file_name: &str) -> HistoryItem {
+    HistoryItem { content_type: ContentType::Image, image_data: Some(file_name.into()), ..item(id, "Image") }
+}
+
+#[test]
+fn bundles_carry_their_images() {
+    let source = Store::new();
+    let stored = source.images.put(&png()).unwrap();
+    source.db.push_history_item(&image_item("1", &stored.file_name), None).unwrap();
+    // Named by the old webview, before images were content-addressed.
+    fs::create_dir_all(source.images.dir()).unwrap();
+    fs::write(source.images.dir().join("img_1700000000000_abc.png"), png()).unwrap();
+    source.db.push_history_item(&image_item("2", "img_1700000000000_abc.png"), None).unwrap();
+    source.db.push_history_item(&image_item("3", "gone.png"), None).unwrap();
+
+    let target = Store::new();
+    let counts = restore_bundle(&target.db, &target.images, &target.thumbs, Cursor::new(source.bundle())).unwrap();
+    assert_eq!(counts.history, 3);
+
+    let history = target.db.load().unwrap().history;
+    assert_eq!(history[1].image_data, Some(stored.file_name.clone()), "legacy name not pointed at the stored blob");
+    assert_eq!(history[2].image_data, Some(stored.file_name.clone()));
+    assert_eq!(history[0].image_data.as_deref(), Some("gone.png"));
+    assert_eq!(target.images.get(&stored.file_name).unwrap(), png());
+    assert!(fs::read_dir(target.dir.path().join("thumbs")).unwrap().next().is_some(), "preview not restored");
+}
+
+#[test]
+fn tampered_bundles_are_rejected_before_anything_changes() {
+    let source = Store::new();
+    source.db.push_history_item(&item("1", "original"), None).unwrap();
+    let bundle = source.bundle();
+
+    // Rewrite data.json without updating the manifest.
+    let mut original = zip::ZipArchive::new(Cursor::new(bundle)).unwrap();
+    let mut tampered = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    for i in 0..original.len() {
+        let mut entry = original.by_index(i).unwrap();
+        let mut bytes = Vec::new();
+        entry.read_to_end(&mut bytes).unwrap();
+        if entry.name() == "data.json" {
+            bytes = String::from_utf8(bytes).unwrap().replace("original", "tampered").into_bytes();
+        }
+        tampered.start_file(entry.name(), zip::write::SimpleFileOptions::default()).unwrap();
+        tampered.write_all(&bytes).unwrap();
+    }
+    let tampered = tampered.finish().unwrap().into_inner();
+
+    let target = Store::new();
+    target.db.push_history_item(&item("live", "keep me"), None).unwrap();
+    let result = restore_bundle(&target.db, &target.images, &target.thumbs, Cursor::new(tampered));
+    match result {
+        Err(Error::InvalidBackup(errors)) => assert_eq!(errors[0].to_string(), "data.json: checksum mismatch"),
+        other => panic!("expected InvalidBackup, got {other:?}"),
+    }
+    assert_eq!(target.db.load().unwrap().history[0].id, "live");
+
+    let not_a_zip = restore_bundle(&target.db, &target.images, &target.thumbs, Cursor::new(b"{}".to_vec()));
+    assert!(matches!(not_a_zip, Err(Error::Archive(_))));
+}
+
+#[test]
+fn merging_unions_by_id_then_name_and_dedupes_content() {
+    let laptop = populated();
+    laptop.push_history_item(&item("laptop-1", "only on the laptop"), None).unwrap();
+    // Same id as a desktop item, different text.
+    laptop.push_history_item(&item("9", "laptop nine"), None).unwrap();
+    laptop.add_project("p-laptop", " work ").unwrap();
+    laptop.add_folder("p-laptop", "f-laptop", "Drafts").unwrap();
+    laptop.add_note("f-laptop", &note("n2", "draft")).unwrap();
+    laptop.add_global_tag("home").unwrap();
+    let backup = export(&laptop, false).unwrap();
+
+    let desktop = populated();
+    desktop.push_history_item(&item("9", "desktop nine"), None).unwrap();
+    let before = desktop.load().unwrap();
+
+    let preview = merge(&desktop, &backup, true).unwrap();
+    assert_eq!(desktop.load().unwrap(), before, "preview wrote something");
+
+    let report = merge(&desktop, &backup, false).unwrap();
+    assert!(!report.dry_run);
+    assert_eq!((report.added.projects, report.added.folders, report.added.notes), (0, 1, 1));
+    assert_eq!((report.skipped.projects, report.skipped.folders, report.skipped.notes), (3, 2, 1));
+    assert_eq!((report.added.history, report.skipped.history), (2, 2));
+    assert_eq!((report.added.tags, report.skipped.tags), (1, 1));
+    assert_eq!(report.conflicts.len(), 1);
+    let renamed = report.conflicts[0].new_id.clone();
+    assert_eq!(report.conflicts[0].id, "9");
+    assert!(uuid::Uuid::parse_str(&renamed).is_ok(), "{renamed} is not a fresh id");
+    assert_eq!(preview.added.history, report.added.history);
+    assert_eq!(preview.conflicts.len(), report.conflicts.len());
+
+    let data = desktop.load().unwrap();
+    // "work" matched the existing "Work" project by name.
+    assert_eq!(data.projects.len(), 2);
+    let work = &data.projects[1];
+    assert_eq!(work.folders.iter().map(|f| f.name.as_str()).collect::<Vec<_>>(), ["Snippets", "Drafts"]);
+    assert_eq!(work.folders[1].notes[0].text, "draft");
+    assert_eq!(data.history.iter().find(|h| h.id == renamed).unwrap().text, "laptop nine");
+    assert_eq!(data.history.iter().find(|h| h.id == "9").unwrap().text, "desktop nine");
+    assert_eq!(data.global_tags, ["work", "home"]);
+
+    // Merging again adds nothing.
+    let again = merge(&desktop, &backup, false).unwrap();
+    assert_eq!((again.added.history, again.added.notes, again.added.folders), (0, 0, 0));
+    assert!(again.conflicts.is_empty());
+}
+
+#[test]
+fn merged_history_sorts_by_copy_time_and_is_trimmed_first() {
+    let now = chrono::Utc::now().timestamp_millis();
+    let laptop = Database::open_in_memory().unwrap();
+    laptop.push_history_item(&item("old-1", "laptop, last year"), None).unwrap();
+    laptop.push_history_item(&HistoryItem { created_at: 1_700_000_500_000, ..item("old-2", "laptop, later") }, None).unwrap();
+    let backup = export(&laptop, false).unwrap();
+
+    let desktop = Database::open_in_memory().unwrap();
+    desktop.push_history_item(&HistoryItem { created_at: now - 1_000, ..item("new-1", "desktop, today") }, None).unwrap();
+    desktop.push_history_item(&HistoryItem { created_at: now, ..item("new-2", "desktop, now") }, None).unwrap();
+    merge(&desktop, &backup, false).unwrap();
+
+    let texts = |db: &Database| db.load().unwrap().history.into_iter().map(|h| h.text).collect::<Vec<_>>();
+    assert_eq!(texts(&desktop), ["desktop, now", "desktop, today", "laptop, later", "laptop, last year"]);
+
+    let policy = RetentionPolicy { max_items: Some(3), ..Default::default() };
+    retention::prune(&desktop, &policy, now).unwrap();
+    assert_eq!(texts(&desktop), ["desktop, now", "desktop, today", "laptop, later"]);
+}
@@ -0,0 +1,125 @@
+// src-tauri/src/backup/upgrade.rs
+
+//! Brings older backups up to the current layout before validation.
+//!
+//! - v1, from the settings menu and command palette: `exportDate` instead of
+//!   `createdAt`. Very early exports have no `version` at all.
+//! - v2, from `useImportExport.ts`: `date` instead of `createdAt`.
+//! - v3: items and notes have a `date` ("14:05") instead of `createdAt`,
+//!   `updatedAt` and `utcOffset`; see `dates::upgrade_record`.
+//! - v4: a project, folder, note or history item may share its `Date.now()`
+//!   id with a record of another kind. The first keeps it, the others get
+//!   a new id; repeats within a kind are left for validation to report.
+//!
+//! v1 and v2 have no `format`.
+
+use std::collections::HashMap;
+
+use chrono::Local;
+use serde_json::{Map, Value};
+
+use super::validate::invalid;
+use super::{FORMAT, VERSION};
+use crate::dates::upgrade_record;
+use crate::error::Result;
+use crate::ids;
+
+pub(super) fn to_current(value: &mut Value) -> Result<()> {
+    let Some(root) = value.as_object_mut() else {
+        return Err(invalid("", "expected an object"));
+    };
+
+    let version = match root.get("version") {
+        None | Some(Value::Null) => 1,
+        Some(version) => match version.as_u64() {
+            Some(version) if version >= 1 => version,
+            _ => return Err(invalid("version", "expected a positive integer")),
+        },
+    };
+    if version > u64::from(VERSION) {
+        return Err(invalid("version", &format!("version {version} is newer than this app supports ({VERSION})")));
+    }
+
+    if version < 3 {
+        let date_field = if version == 1 { "exportDate" } else { "date" };
+        if let Some(created_at) = root.remove(date_field) {
+            root.insert("createdAt".into(), created_at);
+        }
+        root.insert("format".into(), FORMAT.into());
+        default_to_empty(root, "globalTags");
+    }
+    if version < 4 {
+        let now = Local::now();
+        for record in records(root) {
+            upgrade_record(record, now);
+        }
+    }
+    if version < 5 {
+        separate_ids(root);
+    }
+    root.insert("version".into(), VERSION.into());
+
+    match root.get("format").and_then(Value::as_str) {
+        Some(FORMAT) => Ok(()),
+        _ => Err(invalid("format", &format!("expected \"{FORMAT}\""))),
+    }
+}
+
+/// History items and notes, wherever they sit. Malformed parts are skipped
+/// here and reported by validation.
+fn records(root: &mut Map<String, Value>) -> Vec<&mut Value> {
+    let mut records: Vec<&mut Value> = Vec::new();
+    for (key, value) in root.iter_mut() {
+        let Value::Array(items) = value else { continue };
+        match key.as_str() {
+            "history" => records.extend(items.iter_mut()),
+            "projects" => {
+                let folders = items.iter_mut().filter_map(|p| p.get_mut("folders")?.as_array_mut()).flatten();
+                records.extend(folders.filter_map(|f| f.get_mut("notes")?.as_array_mut()).flatten());
+            }
+            _ => {}
+        }
+    }
+    records
+}
+
+fn separate_ids(root: &mut Map<String, Value>) {
+    let mut first_kind: HashMap<String, &str> = HashMap::new();
+    let mut claim = |record: &mut Value, kind: &'static str| {
+        let Some(id) = record.get("id").and_then(Value::as_str) else { return };
+        match first_kind.get(id) {
+            Some(first) if *first != kind => record["id"] = ids::new_id().into(),
+            Some(_) => {}
+            None => {
+                first_kind.insert(id.to_string(), kind);
+            }
+        }
+    };
+
+    if let Some(Value::Array(projects)) = root.get_mut("projects") {
+        for project in projects {
+            claim(project, "project");
+            for folder in children(project, "folders") {
+                claim(folder, "folder");
+                for note in children(folder, "notes") {
+                    claim(note, "note");
+                }
+            }
+        }
+    }
+    if let Some(Value::Array(history)) = root.get_mut("history") {
+        for item in history {
+            claim(item, "history");
+        }
+    }
+}
+
+fn children<'a>(record: &'a mut Value, key: &str) -> impl Iterator<Item = &'a mut Value> {
+    record.get_mut(key).and_then(Value::as_array_mut).into_iter().flatten()
+}
+
+fn default_to_empty(root: &mut Map<String, Value>, key: &str) {
+    if root.get(key).is_none_or(Value::is_null) {
+        root.insert(key.into(), Value::Array(Vec::new()));
+    }
+}
@@ -0,0 +1,261 @@
+// src-tauri/src/backup/validate.rs
+
+//! Field-by-field validation, so a bad file is reported in full rather than
+//! one serde error at a time.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use super::Backup;
+use crate::error::{Error, Result};
+use crate::models::{Folder, HistoryItem, NoteItem, Project};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FieldError {
+    /// JSON-pointer-like location, e.g. `projects/0/folders/2/notes/5/text`.
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.path.as_str() {
+            "" => f.write_str(&self.message),
+            path => write!(f, "{path}: {}", self.message),
+        }
+    }
+}
+
+/// A file with a single problem.
+pub(super) fn invalid(path: &str, message: &str) -> Error {
+    Error::InvalidBackup(vec![FieldError { path: path.into(), message: message.into() }])
+}
+
+/// Expects `value` to have gone through `upgrade::to_current`.
+pub(super) fn backup(value: &Value) -> Result<Backup> {
+    let mut checker = Checker::default();
+    let root = value.as_object().expect("upgrade checked the root");
+
+    let created_at = checker.optional(root, "createdAt", "");
+    let history: Vec<_> = checker
+        .array(root, "history", "")
+        .iter()
+        .enumerate()
+        .filter_map(|(i, raw)| checker.history_item(raw, &format!("history/{i}")))
+        .collect();
+    let projects: Vec<_> = checker
+        .array(root, "projects", "")
+        .iter()
+        .enumerate()
+        .filter_map(|(i, raw)| checker.project(raw, &format!("projects/{i}")))
+        .collect();
+    let global_tags = checker.tags(root, "globalTags", "");
+
+    if !checker.errors.is_empty() {
+        return Err(Error::InvalidBackup(checker.errors));
+    }
+    Ok(Backup {
+        format: super::FORMAT.into(),
+        version: super::VERSION,
+        created_at,
+        history,
+        projects,
+        global_tags,
+    })
+}
+
+#[derive(Default)]
+struct Checker {
+    errors: Vec<FieldError>,
+    /// Path each id was first seen at; ids are unique across kinds.
+    seen: HashMap<String, String>,
+}
+
+impl Checker {
+    fn history_item(&mut self, raw: &Value, path: &str) -> Option<HistoryItem> {
+        let record = self.object(raw, path)?;
+        let id = self.id(record, "history item", path);
+        let text = self.required(record, "text", path);
+        let created_at = self.required(record, "createdAt", path);
+        let updated_at = self.optional(record, "updatedAt", path);
+        let utc_offset = self.optional(record, "utcOffset", path);
+        let content_type = self.required(record, "contentType", path);
+        let image_data = self.image_data(record, path);
+        let language = self.optional(record, "language", path);
+        let is_favorite = self.optional(record, "isFavorite", path);
+        let is_secret = self.optional(record, "isSecret", path);
+        let expires_at = self.optional(record, "expiresAt", path);
+        Some(HistoryItem {
+            id: id?,
+            text: text?,
+            created_at: created_at?,
+            updated_at: updated_at.or(created_at)?,
+            utc_offset: utc_offset.unwrap_or_default(),
+            content_type: content_type?,
+            image_data,
+            language,
+            is_favorite: is_favorite.unwrap_or_default(),
+            is_secret: is_secret.unwrap_or_default(),
+            expires_at,
+        })
+    }
+
+    fn project(&mut self, raw: &Value, path: &str) -> Option<Project> {
+        let record = self.object(raw, path)?;
+        let id = self.id(record, "project", path);
+        let name = self.name(record, path);
+        let folders: Vec<_> = self
+            .array(record, "folders", path)
+            .iter()
+            .enumerate()
+            .filter_map(|(i, raw)| self.folder(raw, &format!("{path}/folders/{i}")))
+            .collect();
+        Some(Project { id: id?, name: name?, folders })
+    }
+
+    fn folder(&mut self, raw: &Value, path: &str) -> Option<Folder> {
+        let record = self.object(raw, path)?;
+        let id = self.id(record, "folder", path);
+        let name = self.name(record, path);
+        let notes: Vec<_> = self
+            .array(record, "notes", path)
+            .iter()
+            .enumerate()
+            .filter_map(|(i, raw)| self.note(raw, &format!("{path}/notes/{i}")))
+            .collect();
+        Some(Folder { id: id?, name: name?, notes })
+    }
+
+    fn note(&mut self, raw: &Value, path: &str) -> Option<NoteItem> {
+        let record = self.object(raw, path)?;
+        let id = self.id(record, "note", path);
+        let text = self.required(record, "text", path);
+        let created_at = self.required(record, "createdAt", path);
+        let updated_at = self.optional(record, "updatedAt", path);
+        let utc_offset = self.optional(record, "utcOffset", path);
+        let content_type = self.required(record, "contentType", path);
+        let tags = self.tags(record, "tags", path);
+        let image_data = self.image_data(record, path);
+        let language = self.optional(record, "language", path);
+        let is_favorite = self.optional(record, "isFavorite", path);
+        let is_secret = self.optional(record, "isSecret", path);
+        Some(NoteItem {
+            id: id?,
+            text: text?,
+            created_at: created_at?,
+            updated_at: updated_at.or(created_at)?,
+            utc_offset: utc_offset.unwrap_or_default(),
+            content_type: content_type?,
+            tags,
+            image_data,
+            language,
+            is_favorite: is_favorite.unwrap_or_default(),
+            is_secret: is_secret.unwrap_or_default(),
+        })
+    }
+
+    /// A non-empty id not used by another record.
+    fn id(&mut self, record: &Map<String, Value>, kind: &'static str, path: &str) -> Option<String> {
+        let id: String = self.required(record, "id", path)?;
+        if id.is_empty() {
+            self.fail(&join(path, "id"), "must not be empty");
+            return None;
+        }
+        if let Some(first) = self.seen.get(&id) {
+            let message = format!("duplicate {kind} id '{id}', first used at {first}");
+            self.fail(&join(path, "id"), &message);
+            return None;
+        }
+        self.seen.insert(id.clone(), path.to_string());
+        Some(id)
+    }
+
+    fn name(&mut self, record: &Map<String, Value>, path: &str) -> Option<String> {
+        let name: String = self.required(record, "name", path)?;
+        if name.trim().is_empty() {
+            self.fail(&join(path, "name"), "must not be empty");
+            return None;
+        }
+        Some(name)
+    }
+
+    /// A file name in the image store, or a legacy `data:image/...` URL.
+    fn image_data(&mut self, record: &Map<String, Value>, path: &str) -> Option<String> {
+        let image_data: String = self.optional(record, "imageData", path)?;
+        let is_plain =
+            !image_data.is_empty() && !image_data.starts_with('.') && !image_data.contains(['/', '\\']);
+        if !is_plain && !image_data.starts_with("data:image/") {
+            self.fail(&join(path, "imageData"), "expected an image file name");
+            return None;
+        }
+        Some(image_data)
+    }
+
+    fn tags(&mut self, record: &Map<String, Value>, key: &str, path: &str) -> Vec<String> {
+        let raw = self.array(record, key, path);
+        let mut tags = Vec::with_capacity(raw.len());
+        for (i, tag) in raw.iter().enumerate() {
+            match tag.as_str().map(str::trim) {
+                Some(tag) if !tag.is_empty() => tags.push(tag.to_string()),
+                _ => self.fail(&format!("{}/{i}", join(path, key)), "expected a non-empty string"),
+            }
+        }
+        tags
+    }
+
+    fn object<'a>(&mut self, raw: &'a Value, path: &str) -> Option<&'a Map<String, Value>> {
+        let record = raw.as_object();
+        if record.is_none() {
+            self.fail(path, "expected an object");
+        }
+        record
+    }
+
+    /// An array field; missing or `null` counts as empty.
+    fn array<'a>(&mut self, record: &'a Map<String, Value>, key: &str, path: &str) -> &'a [Value] {
+        match record.get(key) {
+            None | Some(Value::Null) => &[],
+            Some(Value::Array(items)) => items,
+            Some(_) => {
+                self.fail(&join(path, key), "expected an array");
+                &[]
+            }
+        }
+    }
+
+    fn required<T: DeserializeOwned>(&mut self, record: &Map<String, Value>, key: &str, path: &str) -> Option<T> {
+        if record.get(key).is_none_or(Value::is_null) {
+            self.fail(&join(path, key), "is required");
+            return None;
+        }
+        self.optional(record, key, path)
+    }
+
+    /// `None` when missing, `null` or invalid; only the last is an error.
+    fn optional<T: DeserializeOwned>(&mut self, record: &Map<String, Value>, key: &str, path: &str) -> Option<T> {
+        let raw = record.get(key).filter(|raw| !raw.is_null())?;
+        match T::deserialize(raw) {
+            Ok(value) => Some(value),
+            Err(err) => {
+                self.fail(&join(path, key), &err.to_string());
+                None
+            }
+        }
+    }
+
+    fn fail(&mut self, path: &str, message: &str) {
+        self.errors.push(FieldError { path: path.to_string(), message: message.to_string() });
+    }
+}
+
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{path}/{key}")
+    }
+}
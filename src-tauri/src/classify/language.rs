@@ -0,0 +1,174 @@
+// src-tauri/src/classify/language.rs
+
+//! Guesses the programming language of a code clip.
+//!
+//! Every language has a set of telltale patterns with weights. A pattern
+//! counts once no matter how often it matches; the language with the highest
+//! total wins, earlier languages winning ties. Shared syntax (`=>`, `::`,
+//! `{ … }`) is left out or weighted low, since it separates nothing.
+
+use std::sync::LazyLock;
+
+use regex::RegexSet;
+
+use crate::models::{ContentType, Language};
+
+/// Below this a guess is more likely wrong than right.
+const MIN_SCORE: u32 = 3;
+
+const SIGNALS: &[(Language, &[(&str, u32)])] = &[
+    (
+        Language::Rust,
+        &[
+            (r"(?m)^\s*(?:pub(?:\([\w:]+\))?\s+)?(?:async\s+)?(?:unsafe\s+)?fn\s+\w+", 3),
+            (r"\blet\s+mut\s", 3),
+            (r"(?m)^\s*(?:pub\s+)?(?:struct|enum|trait|mod)\s+\w+|^\s*impl\b", 2),
+            (r"\b(?:println|eprintln|format|vec|panic|assert_eq|macro_rules)!", 3),
+            (r"#!?\[(?:derive|cfg|allow|test|tokio::main)", 3),
+            (r"&(?:mut\s+|'\w+\s+)?(?:self|str)\b|\b(?:Option|Result|Vec|Box|Arc)<", 2),
+            (r"(?m)^\s*use\s+(?:std|crate|super|self)?::?[\w:]*", 2),
+            (r"\.unwrap\(\)|\?;|\.into_iter\(\)", 2),
+            (r"\bmatch\s+[^{]+\{", 1),
+        ],
+    ),
+    (
+        Language::TypeScript,
+        &[
+            (r"(?m)^\s*(?:export\s+)?(?:const|let|var)\s+(?:[\w$]+|\[[^\]]*\]|\{[^}]*\})\s*(?::[^=]+)?=", 2),
+            (r"(?m)^\s*(?:export\s+)?(?:default\s+)?(?:async\s+)?function\b", 3),
+            (r#"(?m)^\s*import\s+.+\s+from\s+['"]|^\s*import\s+['"]|\brequire\(['"]"#, 3),
+            (r"(?m)^\s*export\s+(?:default|const|function|class|interface|type|\{)", 3),
+            (r"(?m)^\s*(?:export\s+)?(?:interface|type)\s+\w+(?:<[^>]*>)?\s*(?:=|\{|extends)", 3),
+            (r":\s*(?:string|number|boolean|any|unknown|void)\b|\bas\s+const\b", 2),
+            (r"===|!==", 2),
+            (r"\.length\b|\b(?:null|undefined)\b", 1),
+            (r"\bconsole\.\w+\(|\b(?:document|window)\.\w+|\bJSON\.(?:parse|stringify)\(", 3),
+            (r"\buse(?:State|Effect|Memo|Callback|Ref)\b|\bawait\s+\w+", 2),
+            (r"\)\s*=>|\w\s*=>\s*[{(\w]", 1),
+        ],
+    ),
+    (
+        Language::Python,
+        &[
+            (r"(?m)^\s*(?:async\s+)?def\s+\w+\s*\(.*\)\s*(?:->\s*[^:]+)?:\s*$", 4),
+            (r"(?m)^\s*from\s+[\w.]+\s+import\s+|^\s*import\s+[\w.]+(?:\s+as\s+\w+)?\s*$", 3),
+            (r"(?m)^\s*class\s+\w+(?:\(.*\))?:\s*$", 3),
+            (r"(?m)^\s*(?:if|elif|else|for|while|with|try|except|finally)\b[^{;]*:\s*$", 2),
+            (r"\bself\.\w+|\b__\w+__\b", 2),
+            (r"\b(?:None|True|False)\b|\belif\b|\bnot\s+in\b|\bprint\(", 1),
+            (r#"f["'][^"']*\{"#, 2),
+        ],
+    ),
+    (
+        Language::Go,
+        &[
+            (r"(?m)^package\s+\w+\s*$", 4),
+            (r"(?m)^\s*func\s+(?:\([^)]*\)\s*)?\w+\s*\(", 4),
+            (r":=", 2),
+            (r"\bfmt\.\w+\(|\bif\s+err\s*!=\s*nil\b", 3),
+            (r"(?m)^import\s+\(|\bchan\s+\w+|\bgo\s+func\b|\bdefer\s", 3),
+        ],
+    ),
+    (
+        Language::Sql,
+        &[
+            (r"(?i)\bselect\b[\s\S]+?\bfrom\b", 3),
+            (r"(?i)\binsert\s+into\b|\bupdate\s+\w+\s+set\b|\bdelete\s+from\b", 4),
+            (r"(?i)\b(?:create|alter|drop)\s+(?:table|index|view|trigger)\b", 4),
+            (r"(?i)\b(?:where|group\s+by|order\s+by|inner\s+join|left\s+join)\b", 1),
+        ],
+    ),
+    (
+        Language::Bash,
+        &[
+            (r"^#!\s*/(?:usr/)?bin/(?:env\s+)?(?:ba|z)?sh", 5),
+            (r"(?m)^\s*(?:fi|done|esac|then|do)\s*$|\bif\s+\[\[?\s", 3),
+            (r"(?m)^\s*(?:echo|export|source|set\s+-\w+)\s", 2),
+            (r#""?\$\{?\w+\}?"?|\$\("#, 1),
+            (r"(?m)^\s*(?:sudo|apt(?:-get)?|brew|npm|yarn|git|cargo|docker|curl|wget|cd|ls|mkdir|rm|chmod)\s", 2),
+            (r"\s\|\s*(?:grep|sed|awk|xargs|sort|head|tail|wc)\b|\s2>&1|\s&&\s", 2),
+        ],
+    ),
+    (
+        Language::Html,
+        &[
+            (r"(?i)<!DOCTYPE\s+html|<html[\s>]", 5),
+            (r"(?i)<(?:div|span|p|a|ul|ol|li|head|body|section|button|img|form|input|table|h[1-6])[\s>/]", 3),
+            (r"</\w+>", 1),
+            (r#"\s(?:class|href|src|id|style)=""#, 1),
+        ],
+    ),
+    (
+        Language::Css,
+        &[
+            (r"(?m)^\s*[.#]?[\w-]+(?:[\s,>+~]+[.#:]?[\w-]+)*(?::{1,2}[\w-]+)?\s*\{", 2),
+            (r"(?m)^\s*[\w-]+\s*:\s*[^;{}]+;\s*$", 2),
+            (r"@(?:media|import|keyframes|font-face|tailwind|apply)\b", 3),
+            (r"\b\d+(?:px|rem|em|vh|vw)\b|#[0-9a-fA-F]{3,6}\b", 1),
+        ],
+    ),
+    (
+        Language::Php,
+        &[
+            (r"<\?php", 6),
+            (r"\$\w+\s*(?:=|->)", 2),
+            (r"(?m)^\s*(?:public\s+|private\s+|protected\s+)?function\s+\w+\s*\(\s*(?:\??\w+\s+)?\$", 3),
+            (r"\b(?:echo|namespace|use)\s+[\w\\]+;|\$this->", 2),
+        ],
+    ),
+    (
+        Language::C,
+        &[
+            (r"(?m)^\s*#include\s*<\w+\.h>", 4),
+            (r"\bint\s+main\s*\(", 3),
+            (r"\b(?:printf|scanf|malloc|free|memcpy|strlen|sizeof)\s*\(", 2),
+            (r"(?m)^\s*(?:static\s+)?(?:unsigned\s+)?(?:int|char|void|float|double|long|struct\s+\w+)\s+\**\w+\s*[(=;\[]", 2),
+            (r"\bNULL\b|->\w+", 1),
+        ],
+    ),
+    (
+        Language::Cpp,
+        &[
+            (r"(?m)^\s*#include\s*<(?:iostream|vector|string|map|memory|algorithm)>", 5),
+            (r"\bstd::\w+|\busing\s+namespace\b", 4),
+            (r"\bstd::c(?:out|err)\s*<<|\bcout\s*<<", 2),
+            (r"\btemplate\s*<|\bnullptr\b|\bauto\s+\w+\s*=|(?:public|private|protected):", 2),
+            (r"\bint\s+main\s*\(", 3),
+            (r"(?m)^\s*#include\s*[<\x22]", 1),
+        ],
+    ),
+];
+
+static COMPILED: LazyLock<Vec<(Language, RegexSet, Vec<u32>)>> = LazyLock::new(|| {
+    SIGNALS
+        .iter()
+        .map(|&(language, signals)| {
+            let set = RegexSet::new(signals.iter().map(|(pattern, _)| pattern)).expect("language patterns are valid");
+            (language, set, signals.iter().map(|&(_, weight)| weight).collect())
+        })
+        .collect()
+});
+
+/// Language of text already classified as `content_type`. Structured types
+/// map straight to their language; only generic code needs guessing.
+pub fn language_of(content_type: ContentType, text: &str) -> Option<Language> {
+    match content_type {
+        ContentType::Json => Some(Language::Json),
+        ContentType::Yaml => Some(Language::Yaml),
+        ContentType::Sql => Some(Language::Sql),
+        ContentType::Shell => Some(Language::Bash),
+        ContentType::Code => detect(text.trim()),
+        _ => None,
+    }
+}
+
+fn detect(text: &str) -> Option<Language> {
+    let mut best: Option<(Language, u32)> = None;
+    for (language, set, weights) in COMPILED.iter() {
+        let score = set.matches(text).iter().map(|index| weights[index]).sum();
+        if score >= MIN_SCORE && best.is_none_or(|(_, top)| score > top) {
+            best = Some((*language, score));
+        }
+    }
+    best.map(|(language, _)| language)
+}
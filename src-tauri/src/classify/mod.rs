@@ -0,0 +1,57 @@
+// src-tauri/src/classify/mod.rs
+
+//! Content type detection for captured text.
+//!
+//! Each rule in `rules.rs` looks at the trimmed text and reports how sure it
+//! is that the text is of its kind. The most confident rule wins; text that
+//! no rule claims with at least `MIN_CONFIDENCE` stays plain `Text`.
+//! Code-like results also get a language from `language.rs`.
+
+mod language;
+mod rules;
+
+#[cfg(test)]
+mod tests;
+
+use serde::Serialize;
+
+use crate::models::{ContentType, Language};
+
+pub use language::language_of;
+
+const MIN_CONFIDENCE: f32 = 0.5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Classification {
+    pub content_type: ContentType,
+    /// How sure the winning rule is, from 0 to 1. For `Text` this is how
+    /// little the other rules believed in themselves.
+    pub confidence: f32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<Language>,
+}
+
+pub fn classify(text: &str) -> Classification {
+    let trimmed = text.trim();
+    let mut best: Option<(ContentType, f32)> = None;
+    let mut strongest_rejected: f32 = 0.0;
+
+    if !trimmed.is_empty() {
+        for &(content_type, rule) in rules::RULES {
+            let Some(confidence) = rule(trimmed) else { continue };
+            if confidence < MIN_CONFIDENCE {
+                strongest_rejected = strongest_rejected.max(confidence);
+            } else if best.is_none_or(|(_, top)| confidence > top) {
+                best = Some((content_type, confidence));
+            }
+        }
+    }
+
+    match best {
+        Some((content_type, confidence)) => {
+            Classification { content_type, confidence, language: language_of(content_type, trimmed) }
+        }
+        None => Classification { content_type: ContentType::Text, confidence: 1.0 - strongest_rejected, language: None },
+    }
+}
@@ -0,0 +1,444 @@
+// src-tauri/src/classify/rules.rs
+
+//! The individual detectors. Each returns `Some(confidence)` when the text
+//! looks like its kind. Rules are tried in order and earlier ones win ties,
+//! so the narrow formats come first. Generic `Code` never scores above 0.8,
+//! which lets JSON, SQL, shell and the like beat it.
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::LazyLock;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use regex::Regex;
+
+use crate::models::ContentType;
+
+pub(super) type Rule = fn(&str) -> Option<f32>;
+
+pub(super) const RULES: &[(ContentType, Rule)] = &[
+    (ContentType::Uuid, uuid),
+    (ContentType::Ip, ip),
+    (ContentType::Email, email),
+    (ContentType::Url, url),
+    (ContentType::Color, color),
+    (ContentType::Date, date),
+    (ContentType::Number, number),
+    (ContentType::Phone, phone),
+    (ContentType::Path, path),
+    (ContentType::Json, json),
+    (ContentType::StackTrace, stack_trace),
+    (ContentType::Sql, sql),
+    (ContentType::Shell, shell),
+    (ContentType::Yaml, yaml),
+    (ContentType::Code, code),
+];
+
+fn regex(pattern: &str) -> Regex {
+    Regex::new(pattern).expect("classifier patterns are valid")
+}
+
+fn is_single_line(text: &str) -> bool {
+    !text.contains('\n')
+}
+
+fn meaningful_lines(text: &str) -> impl Iterator<Item = &str> {
+    text.lines().map(str::trim_end).filter(|line| !line.trim().is_empty())
+}
+
+/// Sentences read as prose: most lines end in punctuation and are wordy.
+fn looks_like_prose(text: &str) -> bool {
+    let lines: Vec<&str> = meaningful_lines(text).collect();
+    let prose_lines = lines
+        .iter()
+        .filter(|line| line.ends_with(['.', '!', '?', '…']) && line.split_whitespace().count() >= 5)
+        .count();
+    !lines.is_empty() && prose_lines * 2 >= lines.len()
+}
+
+// --- Identifiers ---
+
+static UUID: LazyLock<Regex> = LazyLock::new(|| {
+    regex(r"^\{?[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}\}?$")
+});
+
+fn uuid(text: &str) -> Option<f32> {
+    UUID.is_match(text).then_some(0.99)
+}
+
+fn ip(text: &str) -> Option<f32> {
+    if text.parse::<Ipv4Addr>().is_ok() {
+        return Some(0.97);
+    }
+    if text.contains(':') && text.parse::<Ipv6Addr>().is_ok() {
+        return Some(0.95);
+    }
+    if text.parse::<SocketAddr>().is_ok() {
+        return Some(0.9);
+    }
+
+    // CIDR notation: 10.0.0.0/8, 2001:db8::/32
+    let (address, prefix) = text.split_once('/')?;
+    let prefix: u8 = prefix.parse().ok()?;
+    match address.parse::<IpAddr>().ok()? {
+        IpAddr::V4(_) if prefix <= 32 => Some(0.9),
+        IpAddr::V6(_) if prefix <= 128 => Some(0.9),
+        _ => None,
+    }
+}
+
+static EMAIL: LazyLock<Regex> = LazyLock::new(|| {
+    regex(r"^(?:mailto:)?[\p{L}\p{N}._%+'-]+@[\p{L}\p{N}](?:[\p{L}\p{N}-]*[\p{L}\p{N}])?(?:\.[\p{L}\p{N}-]+)*\.\p{L}{2,}$")
+});
+
+fn email(text: &str) -> Option<f32> {
+    EMAIL.is_match(text).then_some(0.97)
+}
+
+static WWW: LazyLock<Regex> = LazyLock::new(|| regex(r"^www\.[\p{L}\p{N}-]+(?:\.[\p{L}\p{N}-]+)+(?:[/?#]\S*)?$"));
+
+fn url(text: &str) -> Option<f32> {
+    if is_safe_url(text) {
+        Some(0.99)
+    } else if WWW.is_match(text) {
+        Some(0.85)
+    } else {
+        None
+    }
+}
+
+/// Only http(s) counts: other schemes shouldn't get an "open" button.
+fn is_safe_url(text: &str) -> bool {
+    let rest = text
+        .strip_prefix("https://")
+        .or_else(|| text.strip_prefix("http://"));
+    match rest {
+        Some(rest) => {
+            let host = rest.split(['/', '?', '#']).next().unwrap_or_default();
+            !host.is_empty() && !text.chars().any(char::is_whitespace)
+        }
+        None => false,
+    }
+}
+
+// --- Values ---
+
+static HEX_COLOR: LazyLock<Regex> =
+    LazyLock::new(|| regex(r"^#(?:[0-9a-fA-F]{3,4}|[0-9a-fA-F]{6}|[0-9a-fA-F]{8})$"));
+static FUNCTIONAL_COLOR: LazyLock<Regex> = LazyLock::new(|| {
+    regex(r"(?i)^(?:rgba?|hsla?)\(\s*-?[\d.]+(?:%|deg)?(?:\s*[,/\s]\s*-?[\d.]+%?){2,3}\s*\)$")
+});
+
+fn color(text: &str) -> Option<f32> {
+    if FUNCTIONAL_COLOR.is_match(text) {
+        Some(0.97)
+    } else if HEX_COLOR.is_match(text) {
+        // "#123" is as likely an issue number as a color.
+        let has_letter = text.chars().any(|c| c.is_ascii_alphabetic());
+        Some(if has_letter || text.len() > 5 { 0.95 } else { 0.6 })
+    } else {
+        None
+    }
+}
+
+const DATE_TIME_FORMATS: &[&str] = &[
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%dT%H:%M:%S",
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M",
+    "%d.%m.%Y %H:%M:%S",
+    "%d.%m.%Y %H:%M",
+    "%d/%m/%Y %H:%M",
+    "%m/%d/%Y %H:%M",
+];
+const DATE_FORMATS: &[&str] = &["%Y-%m-%d", "%d.%m.%Y", "%d/%m/%Y", "%m/%d/%Y", "%Y/%m/%d", "%Y.%m.%d"];
+
+fn date(text: &str) -> Option<f32> {
+    if text.len() > 40 || !is_single_line(text) {
+        return None;
+    }
+    if DateTime::parse_from_rfc3339(text).is_ok() {
+        return Some(0.97);
+    }
+    if DateTime::parse_from_rfc2822(text).is_ok() {
+        return Some(0.95);
+    }
+    if DATE_TIME_FORMATS.iter().any(|format| NaiveDateTime::parse_from_str(text, format).is_ok()) {
+        return Some(0.93);
+    }
+    if DATE_FORMATS.iter().any(|format| NaiveDate::parse_from_str(text, format).is_ok()) {
+        return Some(0.9);
+    }
+    None
+}
+
+static NUMBER: LazyLock<Regex> = LazyLock::new(|| {
+    regex(r"^[-+−]?(?:\d{1,3}(?:[ \u{a0}\u{202f},_']\d{3})+|\d+)(?:[.,]\d+)?(?:[eE][-+]?\d+)?\s?%?$")
+});
+static MONEY: LazyLock<Regex> = LazyLock::new(|| {
+    regex(r"^[$€£¥₽]\s?[\d \u{a0},.]*\d$|^[\d \u{a0},.]*\d\s?(?:[$€£¥₽]|руб\.?|USD|EUR|RUB)$")
+});
+
+fn number(text: &str) -> Option<f32> {
+    if NUMBER.is_match(text) {
+        Some(0.9)
+    } else if MONEY.is_match(text) {
+        Some(0.85)
+    } else {
+        None
+    }
+}
+
+fn phone(text: &str) -> Option<f32> {
+    if !is_single_line(text) || !text.chars().all(|c| c.is_ascii_digit() || " -().+\u{a0}".contains(c)) {
+        return None;
+    }
+    let digits = text.chars().filter(char::is_ascii_digit).count();
+    if !(7..=15).contains(&digits) || text.rfind('+').is_some_and(|at| at > 0) {
+        return None;
+    }
+
+    if text.starts_with('+') {
+        // Beats `Number`, which would also accept "+442071838750".
+        Some(0.92)
+    } else if text.contains('(') && text.contains(')') {
+        Some(0.85)
+    } else if text.contains(['-', ' ']) {
+        // 555-12-34 style groups; long digit runs are more likely numbers.
+        let longest_group = text.split(|c: char| !c.is_ascii_digit()).map(str::len).max().unwrap_or(0);
+        (longest_group <= 4).then_some(0.7)
+    } else {
+        None
+    }
+}
+
+// --- Files ---
+
+static WINDOWS_PATH: LazyLock<Regex> = LazyLock::new(|| regex(r#"^(?:[A-Za-z]:|\\\\[^\\/\s]+)[\\/][^<>:"|?*]*$"#));
+
+fn path(text: &str) -> Option<f32> {
+    // Copied files arrive as one path per line.
+    meaningful_lines(text)
+        .map(|line| path_line(line.trim()))
+        .try_fold(1.0f32, |lowest, score| Some(lowest.min(score?)))
+}
+
+fn path_line(line: &str) -> Option<f32> {
+    if WINDOWS_PATH.is_match(line) || line.starts_with("file://") {
+        return Some(0.9);
+    }
+    if line.starts_with("~/") || line.starts_with("./") || line.starts_with("../") {
+        return Some(if line.contains(char::is_whitespace) { 0.8 } else { 0.85 });
+    }
+    let rest = line.strip_prefix('/')?;
+    if rest.starts_with('/') || rest.starts_with('*') || rest.is_empty() {
+        // `//` and `/*` are comments, a lone `/` is nothing.
+        return None;
+    }
+    let segments = rest.split('/').filter(|s| !s.is_empty()).count();
+    match (segments, line.contains(char::is_whitespace)) {
+        (1, false) => Some(0.6),
+        (_, false) => Some(0.85),
+        (_, true) => Some(0.55),
+    }
+}
+
+// --- Structured text ---
+
+fn json(text: &str) -> Option<f32> {
+    if !text.starts_with(['{', '[']) {
+        return None;
+    }
+    match serde_json::from_str::<serde_json::Value>(text).ok()? {
+        serde_json::Value::Object(map) if !map.is_empty() => Some(0.98),
+        serde_json::Value::Array(items) if !items.is_empty() => Some(0.93),
+        _ => Some(0.6),
+    }
+}
+
+static YAML_KEY: LazyLock<Regex> =
+    LazyLock::new(|| regex(r#"^\s*(?:-\s+)?["']?[A-Za-z_][\w.-]*["']?:(?:\s+\S.*)?$"#));
+static YAML_ITEM: LazyLock<Regex> = LazyLock::new(|| regex(r"^\s*-\s+\S"));
+
+fn yaml(text: &str) -> Option<f32> {
+    if (text.contains('{') && text.contains(';')) || looks_like_prose(text) {
+        return None;
+    }
+    let lines: Vec<&str> =
+        meaningful_lines(text).filter(|line| !line.trim_start().starts_with('#') && line.trim() != "---").collect();
+    if lines.len() < 2 {
+        return None;
+    }
+
+    let keys = lines.iter().filter(|line| YAML_KEY.is_match(line)).count();
+    let items = lines.iter().filter(|line| !YAML_KEY.is_match(line) && YAML_ITEM.is_match(line)).count();
+    let ratio = (keys + items) as f32 / lines.len() as f32;
+    if keys == 0 || ratio < 0.8 {
+        return None;
+    }
+
+    let nested = lines.iter().any(|line| line.starts_with([' ', '\t']));
+    let marker = text.starts_with("---");
+    Some((0.6 + 0.25 * ratio + if nested || marker { 0.05 } else { 0.0 }).min(0.92))
+}
+
+static SQL_STATEMENT: LazyLock<Regex> = LazyLock::new(|| {
+    regex(
+        r"(?is)^(?:select\s+.+?\s+from\s|select\s+\d|insert\s+into\s+\S|update\s+\S+\s+set\s|delete\s+from\s+\S|(?:create|alter|drop)\s+(?:table|index|view|database|schema|trigger|unique\s+index)\b|create\s+or\s+replace\b|with\s+(?:recursive\s+)?\w+\s+as\s*\(|truncate\s+table\b)",
+    )
+});
+
+fn sql(text: &str) -> Option<f32> {
+    if !SQL_STATEMENT.is_match(text) {
+        return None;
+    }
+    let keyword = text.split_whitespace().next().unwrap_or_default();
+    if keyword.chars().all(|c| c.is_ascii_uppercase()) {
+        return Some(0.92);
+    }
+    // Lowercase "select the rows from …" is just as likely to be prose.
+    text.contains(['*', '=', ';', '(']).then_some(0.82)
+}
+
+static STACK_HEADER: LazyLock<Regex> = LazyLock::new(|| {
+    regex(r#"(?m)^(?:Traceback \(most recent call last\):|thread '.*' panicked at|goroutine \d+ \[|Exception in thread ")"#)
+});
+static STACK_FRAME: LazyLock<Regex> = LazyLock::new(|| {
+    regex(
+        r#"(?m)^(?:\s+at\s+\S.*(?:\(.*\)|:\d+(?::\d+)?)\s*$|\s+File ".+", line \d+|\s+\d+:\s+(?:0x[0-9a-f]+\s+-\s+)?[\w:<>$]+|\s+\S+\.go:\d+|#\d+\s+\S+\(\d+\):)"#,
+    )
+});
+
+fn stack_trace(text: &str) -> Option<f32> {
+    let header = STACK_HEADER.is_match(text);
+    let frames = STACK_FRAME.find_iter(text).count();
+    let first_line = text.lines().next().unwrap_or_default();
+
+    match (header, frames) {
+        (true, 1..) => Some(0.97),
+        (true, 0) => Some(0.85),
+        (false, 2..) => Some(0.9),
+        (false, 1) if first_line.contains("Error") || first_line.contains("Exception") => Some(0.8),
+        _ => None,
+    }
+}
+
+// --- Commands and code ---
+
+/// Commands whose names aren't everyday words.
+const SHELL_COMMANDS: &[&str] = &[
+    "git", "npm", "npx", "yarn", "pnpm", "bun", "cargo", "rustup", "rustc", "docker", "docker-compose",
+    "kubectl", "helm", "sudo", "apt", "apt-get", "brew", "pip", "pip3", "python", "python3", "node",
+    "deno", "curl", "wget", "ssh", "scp", "rsync", "rm", "mkdir", "cp", "mv", "chmod", "chown", "ls",
+    "grep", "sed", "awk", "tar", "systemctl", "journalctl", "ps", "psql", "mysql", "terraform", "gh",
+    "dig", "nslookup", "xdg-open", "dnf", "yum", "pacman", "snap", "flatpak", "mvn", "gradle",
+    "dotnet", "composer", "gem", "rails", "unzip", "pwsh", "winget", "choco", "ffmpeg", "ollama",
+];
+/// Commands that are also English words; they need shell-looking arguments.
+const AMBIGUOUS_COMMANDS: &[&str] = &[
+    "cd", "go", "find", "make", "set", "open", "source", "code", "kill", "top", "head", "tail", "touch",
+    "cat", "env", "echo", "export", "which", "ping", "java", "php", "ruby", "zip", "ln", "alias",
+];
+
+static SHEBANG: LazyLock<Regex> = LazyLock::new(|| regex(r"^#!\s*/(?:usr/)?bin/(?:env\s+)?(?:ba|z|da)?sh\b"));
+
+static SHELL_OPERATOR: LazyLock<Regex> =
+    LazyLock::new(|| regex(r"(?:^|\s)(?:--?[\w-]+|\||&&|\|\||>>?|2>&1)(?:\s|=|$)|\$\{?\w+"));
+
+fn shell(text: &str) -> Option<f32> {
+    if SHEBANG.is_match(text) {
+        return Some(0.97);
+    }
+    let lines: Vec<&str> = meaningful_lines(text).map(str::trim).filter(|line| !line.starts_with('#')).collect();
+    if lines.is_empty() || lines.len() > 30 || looks_like_prose(text) {
+        return None;
+    }
+
+    let mut prompt = false;
+    let mut operators = false;
+    let mut commands = 0;
+    let mut continued = false;
+
+    for line in &lines {
+        let (line, had_prompt) = match line.strip_prefix("$ ").or_else(|| line.strip_prefix("> ")) {
+            Some(rest) => (rest, true),
+            None => (*line, false),
+        };
+        prompt |= had_prompt;
+        operators |= SHELL_OPERATOR.is_match(line);
+
+        if continued || had_prompt || is_command_line(line) {
+            commands += 1;
+        }
+        continued = line.ends_with('\\');
+    }
+
+    if commands < lines.len() {
+        return (commands * 5 >= lines.len() * 4).then_some(0.6);
+    }
+    Some(if prompt {
+        0.92
+    } else if operators {
+        0.88
+    } else if lines.len() == 1 && !lines[0].contains(' ') {
+        0.6
+    } else {
+        0.75
+    })
+}
+
+fn is_command_line(line: &str) -> bool {
+    let mut words = line.split_whitespace().peekable();
+    // Skip `sudo` and leading `VAR=value` assignments.
+    while words.peek().is_some_and(|w| *w == "sudo" || (w.contains('=') && !w.starts_with('='))) {
+        words.next();
+    }
+    let Some(command) = words.next() else { return false };
+    let args: Vec<&str> = words.collect();
+
+    if SHELL_COMMANDS.contains(&command) {
+        return args.len() <= 12;
+    }
+    if AMBIGUOUS_COMMANDS.contains(&command) {
+        let shellish = |arg: &&str| arg.starts_with('-') || arg.contains(['/', '~', '.', '=', '$', '@', ':']);
+        return args.is_empty() || (args.len() <= 8 && args.iter().any(shellish));
+    }
+    // ./script.sh, ~/bin/tool
+    command.starts_with("./") || command.starts_with("~/")
+}
+
+static STRONG_CODE: LazyLock<Regex> = LazyLock::new(|| {
+    regex(
+        r#"(?m)<\?php|#include\s*[<"]|\bconsole\.log\(|\bSystem\.out\.print|\bpublic\s+static\s|^\s*(?:pub(?:\(crate\))?\s+)?(?:async\s+)?fn\s+\w+\s*[<(]|^\s*(?:async\s+)?def\s+\w+\s*\(.*\)\s*(?:->\s*[^:]+)?:\s*$|^\s*(?:export\s+)?(?:default\s+)?(?:async\s+)?function\s*\*?\s*[\w$]*\s*\(|^\s*package\s+[\w.]+;?\s*$|^\s*import\s+(?:[\w*{}\s,$]+\s+from\s+)?['"][^'"]+['"];?\s*$|^\s*from\s+[\w.]+\s+import\s+\w|^\s*(?:pub\s+)?(?:struct|enum|trait|impl)\b[^;.]*\{\s*$|^\s*(?:export\s+)?(?:interface|type)\s+\w+(?:<[^>]*>)?\s*(?:=|\{|extends)|^\s*(?:let|const|var)\s+(?:mut\s+)?(?:[\w$]+|\[[^\]]*\]|\{[^}]*\})\s*(?::\s*[^=]+)?=\s*\S|^\s*(?:public|private|protected)\s+(?:static\s+)?(?:final\s+)?[\w<>\[\]]+\s+\w+\s*\(|^\s*(?:abstract\s+)?class\s+\w+(?:\s*\(.*\))?(?:\s+extends\s+\w+)?\s*[{:]\s*$|^\s*func\s+(?:\([^)]*\)\s*)?\w+\s*\("#,
+    )
+});
+static CODE_LINE: LazyLock<Regex> = LazyLock::new(|| {
+    regex(
+        r"[;{}]\s*$|^\s*[})\]]|=>|->|::|&&|\|\||[!=]==?|\+=|^\s*(?://|/\*|\*\s|\*/)|^\s*(?:if|for|while|switch|else|return|try|catch)\b.*[({;:]\s*$|\w\([^()]*\)\s*;?\s*$",
+    )
+});
+static MARKUP: LazyLock<Regex> =
+    LazyLock::new(|| regex(r"(?s)^<(?:!DOCTYPE\s+html|\?xml|[a-zA-Z][\w:-]*)(?:\s[^>]*)?/?>.*>$"));
+static CSS_RULE: LazyLock<Regex> =
+    LazyLock::new(|| regex(r"(?m)^[^{}\n]+\{\s*(?:\n\s*)?[\w-]+\s*:\s*[^;\n]+;"));
+
+fn code(text: &str) -> Option<f32> {
+    let lines: Vec<&str> = meaningful_lines(text).collect();
+    let signal = lines.iter().filter(|line| CODE_LINE.is_match(line)).count();
+    let ratio = signal as f32 / lines.len().max(1) as f32;
+
+    let confidence = if STRONG_CODE.is_match(text) || CSS_RULE.is_match(text) {
+        0.7 + 0.1 * ratio
+    } else if MARKUP.is_match(text) {
+        0.75
+    } else if lines.len() >= 2 && ratio >= 0.5 {
+        0.55 + 0.2 * ratio
+    } else if text.contains(';') && text.contains('{') && text.contains('}') {
+        0.65
+    } else {
+        return None;
+    };
+
+    // A sentence that merely mentions `return x;` is still a sentence.
+    Some(if looks_like_prose(text) { confidence - 0.3 } else { confidence })
+}
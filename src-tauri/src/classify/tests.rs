@@ -0,0 +1,226 @@
+// src-tauri/src/classify/tests.rs
+
+//! The rules against things people actually copy.
+
+use super::*;
+use crate::models::ContentType::{self, *};
+use crate::models::Language;
+
+const CORPUS: &[(&str, ContentType)] = &[
+    // URLs
+    ("https://github.com/tauri-apps/tauri/issues/4521#issuecomment-1144532", Url),
+    ("http://localhost:1420/", Url),
+    ("https://ru.wikipedia.org/wiki/Буфер_обмена", Url),
+    ("www.example.com/pricing?plan=pro", Url),
+    ("  https://docs.rs/regex/latest/regex/  \n", Url),
+    // Emails
+    ("ivan.petrov@yandex.ru", Email),
+    ("mailto:support+billing@example.co.uk", Email),
+    ("o'connor@mail.example.org", Email),
+    // Phones
+    ("+7 (912) 345-67-89", Phone),
+    ("+1-202-555-0143", Phone),
+    ("(495) 123-45-67", Phone),
+    ("8 800 555-35-35", Phone),
+    ("+442071838750", Phone),
+    // Paths
+    ("/home/user/.config/clipboard-manager/data.db", Path),
+    ("~/Downloads/report final.pdf", Path),
+    ("C:\\Users\\Admin\\AppData\\Roaming\\app\\config.json", Path),
+    ("\\\\fileserver\\share\\docs", Path),
+    ("./src-tauri/src/lib.rs", Path),
+    ("file:///tmp/screenshot.png", Path),
+    ("/etc/nginx/nginx.conf\n/etc/nginx/sites-enabled/default", Path),
+    // Colors
+    ("#ff5733", Color),
+    ("#FFF", Color),
+    ("#1e1e1eCC", Color),
+    ("rgb(255, 87, 51)", Color),
+    ("rgba(0 0 0 / 0.5)", Color),
+    ("hsl(210deg, 40%, 96%)", Color),
+    // JSON
+    (r#"{"id": 1, "name": "Clipboard", "tags": ["a", "b"]}"#, Json),
+    ("[\n  1,\n  2,\n  3\n]", Json),
+    ("{\n  \"compilerOptions\": {\n    \"strict\": true\n  }\n}", Json),
+    // YAML
+    ("name: CI\non:\n  push:\n    branches: [main]\njobs:\n  build:\n    runs-on: ubuntu-latest", Yaml),
+    ("---\nversion: '3.8'\nservices:\n  db:\n    image: postgres:16\n    ports:\n      - \"5432:5432\"", Yaml),
+    ("apiVersion: v1\nkind: Service\nmetadata:\n  name: web", Yaml),
+    // SQL
+    ("SELECT id, text FROM history WHERE is_favorite = 1 ORDER BY seq DESC LIMIT 50;", Sql),
+    ("select * from users where email like '%@example.com'", Sql),
+    ("INSERT INTO notes (id, folder_id, text) VALUES ('1', '2', 'hi');", Sql),
+    ("UPDATE history SET is_favorite = 0 WHERE id = '42'", Sql),
+    ("CREATE TABLE IF NOT EXISTS meta (key TEXT PRIMARY KEY, value TEXT NOT NULL);", Sql),
+    ("WITH recent AS (\n  SELECT * FROM history ORDER BY seq DESC LIMIT 10\n)\nSELECT count(*) FROM recent;", Sql),
+    // Shell
+    ("git rebase -i HEAD~3", Shell),
+    ("$ npm run tauri dev", Shell),
+    ("sudo apt-get install -y libwebkit2gtk-4.1-dev", Shell),
+    ("curl -fsSL https://example.com/install.sh | sh", Shell),
+    ("docker compose up -d --build", Shell),
+    ("cd ~/projects/app && cargo build --release", Shell),
+    ("RUST_LOG=debug cargo run", Shell),
+    ("kubectl get pods -n production\nkubectl logs web-7d9f -f", Shell),
+    ("ls", Shell),
+    // Stack traces
+    (
+        "Traceback (most recent call last):\n  File \"/app/main.py\", line 12, in <module>\n    main()\nKeyError: 'user'",
+        StackTrace,
+    ),
+    (
+        "thread 'main' panicked at src/main.rs:4:5:\ncalled `Option::unwrap()` on a `None` value\nnote: run with `RUST_BACKTRACE=1`",
+        StackTrace,
+    ),
+    (
+        "TypeError: Cannot read properties of undefined (reading 'map')\n    at App (App.tsx:42:17)\n    at renderWithHooks (react-dom.development.js:14985:18)",
+        StackTrace,
+    ),
+    (
+        "Exception in thread \"main\" java.lang.NullPointerException\n\tat com.example.Main.run(Main.java:21)\n\tat com.example.Main.main(Main.java:8)",
+        StackTrace,
+    ),
+    ("goroutine 1 [running]:\nmain.main()\n\t/tmp/sandbox/prog.go:9 +0x1d", StackTrace),
+    // UUIDs
+    ("550e8400-e29b-41d4-a716-446655440000", Uuid),
+    ("{6F9619FF-8B86-D011-B42D-00C04FC964FF}", Uuid),
+    // IP addresses
+    ("192.168.1.1", Ip),
+    ("2001:db8::ff00:42:8329", Ip),
+    ("127.0.0.1:8080", Ip),
+    ("10.0.0.0/8", Ip),
+    // Dates
+    ("2026-09-14", Date),
+    ("14.09.2026", Date),
+    ("2026-09-14T08:30:00Z", Date),
+    ("2026-09-14 08:30:00", Date),
+    ("Mon, 14 Sep 2026 08:30:00 +0300", Date),
+    // Numbers
+    ("42", Number),
+    ("-3.14", Number),
+    ("1 234 567,89", Number),
+    ("1,000,000", Number),
+    ("6.022e23", Number),
+    ("15%", Number),
+    ("$1,299.99", Number),
+    // Code
+    ("fn main() {\n    println!(\"Hello, world!\");\n}", Code),
+    ("const [items, setItems] = useState<HistoryItem[]>([]);", Code),
+    ("def fold(c):\n    return c.lower()", Code),
+    ("import { invoke } from '@tauri-apps/api/core';", Code),
+    ("public static void main(String[] args) {\n    System.out.println(\"hi\");\n}", Code),
+    ("<div class=\"card\">\n  <span>Title</span>\n</div>", Code),
+    (".card {\n  padding: 8px;\n  color: #333;\n}", Code),
+    ("if (items.length === 0) {\n  return null;\n}", Code),
+    ("#include <stdio.h>\nint main(void) { return 0; }", Code),
+    ("package main\n\nfunc main() {\n\tfmt.Println(\"hi\")\n}", Code),
+    // Plain text
+    ("Привет! Созвонимся завтра в 10?", Text),
+    ("Don't forget to buy milk", Text),
+    ("Let me know if you want to return the book.", Text),
+    ("Please select the rows from the table and update the totals by Friday.", Text),
+    ("Can you find the file I sent yesterday? It should be in the shared folder.", Text),
+    ("The meeting is at 5 pm in room 204.", Text),
+    ("hello", Text),
+    ("make sure to set the alarm", Text),
+    ("Итоги: выручка выросла на 12%, расходы снизились.\nПодробности в отчёте.", Text),
+    ("Note: the deploy is scheduled for Monday.\nOwner: Anna", Text),
+];
+
+#[test]
+fn corpus_is_classified() {
+    let failures: Vec<String> = CORPUS
+        .iter()
+        .filter_map(|&(text, expected)| {
+            let got = classify(text);
+            (got.content_type != expected)
+                .then(|| format!("{text:?}: expected {expected:?}, got {:?} ({:.2})", got.content_type, got.confidence))
+        })
+        .collect();
+    assert!(failures.is_empty(), "misclassified:\n{}", failures.join("\n"));
+}
+
+#[test]
+fn confidence_is_a_probability() {
+    for &(text, _) in CORPUS {
+        let confidence = classify(text).confidence;
+        assert!((0.0..=1.0).contains(&confidence), "{text:?} has confidence {confidence}");
+    }
+}
+
+#[test]
+fn unambiguous_formats_are_near_certain() {
+    for text in ["550e8400-e29b-41d4-a716-446655440000", "https://example.com", r#"{"a": 1}"#, "192.168.0.1"] {
+        assert!(classify(text).confidence >= 0.95, "{text:?}");
+    }
+    // A bare hex triplet of digits could just as well be an issue number.
+    assert!(classify("#123").confidence < 0.9);
+}
+
+#[test]
+fn empty_and_blank_text_is_plain_text() {
+    for text in ["", "   ", "\n\t\n"] {
+        assert_eq!(classify(text), Classification { content_type: Text, confidence: 1.0, language: None });
+    }
+}
+
+#[test]
+fn unsafe_schemes_are_not_urls() {
+    for text in ["javascript:alert(1)", "ftp://example.com/file", "data:text/html,<b>hi</b>"] {
+        assert_ne!(classify(text).content_type, Url, "{text:?}");
+    }
+}
+
+const LANGUAGES: &[(&str, Language)] = &[
+    ("fn main() {\n    println!(\"Hello, world!\");\n}", Language::Rust),
+    ("let mut items: Vec<String> = Vec::new();\nitems.push(name.to_string());", Language::Rust),
+    ("#[derive(Debug, Clone)]\npub struct Folder {\n    pub id: String,\n}", Language::Rust),
+    ("impl Database {\n    pub fn open(path: &Path) -> Result<Self> {\n        Self::init(Connection::open(path)?)\n    }\n}", Language::Rust),
+    ("const [items, setItems] = useState<HistoryItem[]>([]);", Language::TypeScript),
+    ("import { invoke } from '@tauri-apps/api/core';", Language::TypeScript),
+    ("export interface NoteItem {\n  id: string;\n  tags: string[];\n}", Language::TypeScript),
+    ("if (items.length === 0) {\n  return null;\n}", Language::TypeScript),
+    ("function debounce(fn, ms) {\n  let timer;\n  return (...args) => {\n    clearTimeout(timer);\n  };\n}", Language::TypeScript),
+    ("def fold(c):\n    return c.lower()", Language::Python),
+    ("class Cache:\n    def __init__(self):\n        self.items = {}", Language::Python),
+    ("for item in items:\n    if item is not None:\n        print(f\"{item.id}\")", Language::Python),
+    ("package main\n\nfunc main() {\n\tfmt.Println(\"hi\")\n}", Language::Go),
+    ("rows, err := db.Query(q)\nif err != nil {\n\treturn err\n}", Language::Go),
+    ("<div class=\"card\">\n  <span>Title</span>\n</div>", Language::Html),
+    ("<!DOCTYPE html>\n<html lang=\"ru\">\n<head><title>App</title></head>\n</html>", Language::Html),
+    (".card {\n  padding: 8px;\n  color: #333;\n}", Language::Css),
+    ("@media (max-width: 600px) {\n  .sidebar { display: none; }\n}", Language::Css),
+    ("<?php\necho $user->name;", Language::Php),
+    ("public function show($id)\n{\n    $post = Post::find($id);\n    return view('post', ['post' => $post]);\n}", Language::Php),
+    ("#include <stdio.h>\nint main(void) { return 0; }", Language::C),
+    ("char *buf = malloc(len + 1);\nmemcpy(buf, src, len);\nbuf[len] = 0;", Language::C),
+    ("#include <iostream>\nint main() {\n    std::cout << \"hi\" << std::endl;\n}", Language::Cpp),
+    ("template <typename T>\nT max(T a, T b) { return a > b ? a : b; }\nauto x = std::max(1, 2);", Language::Cpp),
+    ("#!/bin/bash\nset -e\nfor f in *.log; do\n  gzip \"$f\"\ndone", Language::Bash),
+    (r#"{"id": 1, "name": "Clipboard"}"#, Language::Json),
+    ("apiVersion: v1\nkind: Service\nmetadata:\n  name: web", Language::Yaml),
+    ("SELECT id, text FROM history WHERE is_favorite = 1;", Language::Sql),
+    ("git rebase -i HEAD~3", Language::Bash),
+];
+
+#[test]
+fn code_languages_are_detected() {
+    let failures: Vec<String> = LANGUAGES
+        .iter()
+        .filter_map(|&(text, expected)| {
+            let got = classify(text);
+            (got.language != Some(expected))
+                .then(|| format!("{text:?}: expected {expected:?}, got {:?} as {:?}", got.language, got.content_type))
+        })
+        .collect();
+    assert!(failures.is_empty(), "wrong language:\n{}", failures.join("\n"));
+}
+
+#[test]
+fn only_code_like_content_has_a_language() {
+    for text in ["https://example.com/main.rs", "Let me know if you want to return the book.", "#ff5733", "42"] {
+        assert_eq!(classify(text).language, None, "{text:?}");
+    }
+    // Too little to go on.
+    assert_eq!(language_of(Code, "x;"), None);
+}
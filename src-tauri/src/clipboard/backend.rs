@@ -0,0 +1,203 @@
+// src-tauri/src/clipboard/backend.rs
+
+//! Abstraction over the system clipboard so capture, dedup and restore can
+//! run against an in-memory fake on machines without a desktop session.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+
+use crate::error::{Error, Result};
+
+/// Raw RGBA8 pixels, row-major.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ClipboardImage {
+    pub width: usize,
+    pub height: usize,
+    pub rgba: Vec<u8>,
+}
+
+impl ClipboardImage {
+    /// Hashes the dimensions and every pixel in one pass: a change
+    /// anywhere in the image, however small, gives a different value.
+    pub fn fingerprint(&self, hasher: &mut impl Hasher) {
+        (self.width, self.height).hash(hasher);
+        hasher.write(&self.rgba);
+    }
+}
+
+/// Read methods return `Ok(None)` when the clipboard holds no data in
+/// that format; `Err` is reserved for actual failures.
+pub trait ClipboardBackend {
+    fn read_text(&mut self) -> Result<Option<String>>;
+    fn write_text(&mut self, text: &str) -> Result<()>;
+
+    fn read_html(&mut self) -> Result<Option<String>>;
+    /// `alt_text` is offered as plain text to apps that can't paste HTML.
+    fn write_html(&mut self, html: &str, alt_text: Option<&str>) -> Result<()>;
+
+    fn read_image(&mut self) -> Result<Option<ClipboardImage>>;
+    fn write_image(&mut self, image: &ClipboardImage) -> Result<()>;
+
+    fn read_files(&mut self) -> Result<Option<Vec<PathBuf>>>;
+    fn write_files(&mut self, paths: &[PathBuf]) -> Result<()>;
+
+    /// Opaque token that changes whenever the clipboard contents change.
+    /// Cheap to poll; callers only compare it with the previous value.
+    fn change_token(&mut self) -> Result<u64>;
+}
+
+// --- System clipboard ---
+
+/// The real OS clipboard. Must be created and used on the same thread.
+pub struct SystemClipboard {
+    inner: arboard::Clipboard,
+}
+
+impl SystemClipboard {
+    pub fn new() -> Result<Self> {
+        Ok(Self { inner: arboard::Clipboard::new().map_err(clipboard_error)? })
+    }
+}
+
+fn clipboard_error(err: arboard::Error) -> Error {
+    Error::Clipboard(err.to_string())
+}
+
+/// Maps "no data in this format" to `None`.
+fn optional<T>(result: std::result::Result<T, arboard::Error>) -> Result<Option<T>> {
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(arboard::Error::ContentNotAvailable) => Ok(None),
+        Err(err) => Err(clipboard_error(err)),
+    }
+}
+
+impl ClipboardBackend for SystemClipboard {
+    fn read_text(&mut self) -> Result<Option<String>> {
+        optional(self.inner.get_text())
+    }
+
+    fn write_text(&mut self, text: &str) -> Result<()> {
+        self.inner.set_text(text).map_err(clipboard_error)
+    }
+
+    fn read_html(&mut self) -> Result<Option<String>> {
+        optional(self.inner.get().html())
+    }
+
+    fn write_html(&mut self, html: &str, alt_text: Option<&str>) -> Result<()> {
+        self.inner.set_html(html, alt_text).map_err(clipboard_error)
+    }
+
+    fn read_image(&mut self) -> Result<Option<ClipboardImage>> {
+        Ok(optional(self.inner.get_image())?.map(|image| ClipboardImage {
+            width: image.width,
+            height: image.height,
+            rgba: image.bytes.into_owned(),
+        }))
+    }
+
+    fn write_image(&mut self, image: &ClipboardImage) -> Result<()> {
+        self.inner
+            .set_image(arboard::ImageData {
+                width: image.width,
+                height: image.height,
+                bytes: image.rgba.as_slice().into(),
+            })
+            .map_err(clipboard_error)
+    }
+
+    fn read_files(&mut self) -> Result<Option<Vec<PathBuf>>> {
+        optional(self.inner.get().file_list())
+    }
+
+    fn write_files(&mut self, paths: &[PathBuf]) -> Result<()> {
+        self.inner.set().file_list(paths).map_err(clipboard_error)
+    }
+
+    /// No portable change counter exists, so hash whatever is cheapest to
+    /// read: text if present, then HTML, and only then the full image or
+    /// the file list. An image is still read on every poll while it sits
+    /// on the clipboard alone; hashing all of it is what makes a one-pixel
+    /// edit count as a change.
+    fn change_token(&mut self) -> Result<u64> {
+        let mut hasher = DefaultHasher::new();
+        if let Some(text) = self.read_text()? {
+            text.hash(&mut hasher);
+        } else if let Some(html) = self.read_html()? {
+            html.hash(&mut hasher);
+        } else if let Some(image) = self.read_image()? {
+            image.fingerprint(&mut hasher);
+        } else if let Some(files) = self.read_files()? {
+            files.hash(&mut hasher);
+        }
+        Ok(hasher.finish())
+    }
+}
+
+// --- In-memory fake ---
+
+#[cfg(test)]
+#[derive(Debug, Default)]
+pub struct MemoryClipboard {
+    text: Option<String>,
+    html: Option<String>,
+    image: Option<ClipboardImage>,
+    files: Option<Vec<PathBuf>>,
+    changes: u64,
+}
+
+#[cfg(test)]
+impl MemoryClipboard {
+    /// Like a real clipboard, every write replaces all previous formats.
+    fn replace(&mut self) -> &mut Self {
+        *self = Self { changes: self.changes + 1, ..Self::default() };
+        self
+    }
+}
+
+#[cfg(test)]
+impl ClipboardBackend for MemoryClipboard {
+    fn read_text(&mut self) -> Result<Option<String>> {
+        Ok(self.text.clone())
+    }
+
+    fn write_text(&mut self, text: &str) -> Result<()> {
+        self.replace().text = Some(text.to_string());
+        Ok(())
+    }
+
+    fn read_html(&mut self) -> Result<Option<String>> {
+        Ok(self.html.clone())
+    }
+
+    fn write_html(&mut self, html: &str, alt_text: Option<&str>) -> Result<()> {
+        let clipboard = self.replace();
+        clipboard.html = Some(html.to_string());
+        clipboard.text = alt_text.map(str::to_string);
+        Ok(())
+    }
+
+    fn read_image(&mut self) -> Result<Option<ClipboardImage>> {
+        Ok(self.image.clone())
+    }
+
+    fn write_image(&mut self, image: &ClipboardImage) -> Result<()> {
+        self.replace().image = Some(image.clone());
+        Ok(())
+    }
+
+    fn read_files(&mut self) -> Result<Option<Vec<PathBuf>>> {
+        Ok(self.files.clone())
+    }
+
+    fn write_files(&mut self, paths: &[PathBuf]) -> Result<()> {
+        self.replace().files = Some(paths.to_vec());
+        Ok(())
+    }
+
+    fn change_token(&mut self) -> Result<u64> {
+        Ok(self.changes)
+    }
+}
@@ -0,0 +1,237 @@
+// src-tauri/src/clipboard/capture.rs
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::io::Cursor;
+use std::path::PathBuf;
+
+use chrono::Local;
+use image::{ImageFormat, RgbaImage};
+
+use super::backend::{ClipboardBackend, ClipboardImage};
+use crate::classify::classify;
+use crate::error::{Error, Result};
+use crate::ids;
+use crate::images::{ImageStore, StoredImage};
+use crate::models::{ContentType, HistoryItem};
+use crate::retention;
+use crate::secrets::{self, SecretPolicy};
+use crate::storage::Database;
+use crate::vault::inbox;
+
+/// A single clipboard reading.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClipboardContent {
+    Text(String),
+    Image(ClipboardImage),
+}
+
+impl ClipboardContent {
+    /// Reads the most useful representation: text wins over images
+    /// (matching the old webview poller), and copied files are captured
+    /// as their newline-separated paths.
+    pub fn read(backend: &mut dyn ClipboardBackend) -> Result<Option<Self>> {
+        if let Some(text) = backend.read_text()? {
+            if !text.trim().is_empty() {
+                return Ok(Some(ClipboardContent::Text(text)));
+            }
+        }
+
+        if let Some(image) = backend.read_image()? {
+            return Ok(Some(ClipboardContent::Image(image)));
+        }
+
+        if let Some(files) = backend.read_files()? {
+            if !files.is_empty() {
+                let paths: Vec<_> = files.iter().map(|p| p.to_string_lossy()).collect();
+                return Ok(Some(ClipboardContent::Text(paths.join("\n"))));
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn fingerprint(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        match self {
+            ClipboardContent::Text(text) => {
+                0u8.hash(&mut hasher);
+                text.hash(&mut hasher);
+            }
+            ClipboardContent::Image(image) => {
+                1u8.hash(&mut hasher);
+                image.hash(&mut hasher);
+            }
+        }
+        hasher.finish()
+    }
+}
+
+/// Turns clipboard readings into persisted history items.
+pub struct Capture {
+    db: Database,
+    images: ImageStore,
+    last_fingerprint: Option<u64>,
+}
+
+impl Capture {
+    pub fn new(db: Database, images: ImageStore) -> Self {
+        Self { db, images, last_fingerprint: None }
+    }
+
+    /// Persists `content` if it differs from the previous reading.
+    /// Returns the stored item, or `None` when nothing new was captured.
+    /// While the store is locked, captures go to the inbox and `None` is
+    /// returned; they show up in history after unlocking.
+    pub fn process(&mut self, content: ClipboardContent) -> Result<Option<HistoryItem>> {
+        if let ClipboardContent::Text(text) = &content {
+            if text.trim().is_empty() {
+                return Ok(None);
+            }
+        }
+
+        let fingerprint = content.fingerprint();
+        if self.last_fingerprint == Some(fingerprint) {
+            return Ok(None);
+        }
+
+        let locked = self.db.keyring().is_locked();
+        let retention = retention::policy(&self.db)?;
+        let now = Local::now();
+        let id = ids::new_id();
+        let created_at = now.timestamp_millis();
+        let utc_offset = now.offset().local_minus_utc() / 60;
+
+        let mut stored_image = None;
+        let mut pending_image = None;
+        let item = match content {
+            ClipboardContent::Text(text) => {
+                let classification = classify(&text);
+                let found = secrets::scan(&text);
+                let mut expires_at = None;
+                let text = if found.is_empty() {
+                    text
+                } else {
+                    match secrets::policy(&self.db)? {
+                        SecretPolicy::Skip => {
+                            self.last_fingerprint = Some(fingerprint);
+                            return Ok(None);
+                        }
+                        SecretPolicy::Mask => secrets::mask(&text, &found),
+                        SecretPolicy::Expire => {
+                            expires_at = retention.secret_ttl_secs.map(|ttl| now.timestamp_millis() + ttl as i64 * 1000);
+                            text
+                        }
+                    }
+                };
+                HistoryItem {
+                    id,
+                    text,
+                    created_at,
+                    updated_at: created_at,
+                    utc_offset,
+                    content_type: classification.content_type,
+                    image_data: None,
+                    language: classification.language,
+                    is_favorite: false,
+                    is_secret: !found.is_empty(),
+                    expires_at,
+                }
+            }
+            ClipboardContent::Image(image) => {
+                let png = encode_png(image)?;
+                // The image store can't be written while locked; the bytes
+                // travel with the inbox entry instead.
+                let image_data = if locked {
+                    pending_image = Some(png);
+                    None
+                } else {
+                    let stored = self.images.put(&png)?;
+                    let file_name = stored.file_name.clone();
+                    stored_image = Some(stored);
+                    Some(file_name)
+                };
+                HistoryItem {
+                    id,
+                    text: "Image".into(),
+                    created_at,
+                    updated_at: created_at,
+                    utc_offset,
+                    content_type: ContentType::Image,
+                    image_data,
+                    language: None,
+                    is_favorite: false,
+                    is_secret: false,
+                    expires_at: None,
+                }
+            }
+        };
+
+        // Remember the reading even if it turns out to be a duplicate of the
+        // newest stored item, so we don't re-check it on every tick.
+        self.last_fingerprint = Some(fingerprint);
+
+        if locked {
+            inbox::push(&self.db, &item, pending_image.as_deref())?;
+            return Ok(None);
+        }
+        if self.db.push_history_item(&item, retention.max_items)? {
+            return Ok(Some(item));
+        }
+
+        // Don't leave a fresh blob behind that nothing points to.
+        if let Some(StoredImage { file_name, created: true, .. }) = stored_image {
+            self.images.release(&file_name)?;
+        }
+        Ok(None)
+    }
+}
+
+fn encode_png(image: ClipboardImage) -> Result<Vec<u8>> {
+    let ClipboardImage { width, height, rgba } = image;
+    let image = RgbaImage::from_raw(width as u32, height as u32, rgba).ok_or_else(|| {
+        Error::InvalidInput(format!("clipboard image buffer does not match {width}x{height}"))
+    })?;
+
+    let mut png = Cursor::new(Vec::new());
+    image.write_to(&mut png, ImageFormat::Png)?;
+    Ok(png.into_inner())
+}
+
+/// Puts `item` back on the clipboard. The watcher then sees the change like
+/// any other copy and moves the item to the top of the history.
+///
+/// Links also go out as HTML, so rich editors paste them clickable, and
+/// paths that still exist go out as files a file manager can paste.
+pub fn restore(backend: &mut dyn ClipboardBackend, images: &ImageStore, item: &HistoryItem) -> Result<()> {
+    match (item.content_type, &item.image_data) {
+        (ContentType::Image, Some(file_name)) => {
+            let image = image::load_from_memory(&images.get(file_name)?)?.into_rgba8();
+            backend.write_image(&ClipboardImage {
+                width: image.width() as usize,
+                height: image.height() as usize,
+                rgba: image.into_raw(),
+            })
+        }
+        (ContentType::Url, _) => {
+            let href = escape_html(item.text.trim());
+            backend.write_html(&format!("<a href=\"{href}\">{href}</a>"), Some(&item.text))
+        }
+        (ContentType::Path, _) => match existing_paths(&item.text) {
+            Some(paths) => backend.write_files(&paths),
+            None => backend.write_text(&item.text),
+        },
+        _ => backend.write_text(&item.text),
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('"', "&quot;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+/// One absolute path per line, all of them present on disk.
+fn existing_paths(text: &str) -> Option<Vec<PathBuf>> {
+    let paths: Vec<PathBuf> = text.lines().map(PathBuf::from).collect();
+    let usable = !paths.is_empty() && paths.iter().all(|path| path.is_absolute() && path.exists());
+    usable.then_some(paths)
+}
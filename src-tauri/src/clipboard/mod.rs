@@ -0,0 +1,26 @@
+// src-tauri/src/clipboard/mod.rs
+
+//! Native clipboard capture. Runs independently of the webview so
+//! nothing is missed while the window is hidden or reloading.
+
+mod backend;
+mod capture;
+mod watcher;
+
+#[cfg(test)]
+mod tests;
+
+pub use backend::SystemClipboard;
+pub use capture::{restore, Capture};
+pub use watcher::ClipboardWatcher;
+
+use serde::Serialize;
+
+/// Emitted after a new history item has been persisted.
+pub const CHANGED_EVENT: &str = "clipboard://changed";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClipboardChanged {
+    pub id: String,
+}
@@ -0,0 +1,86 @@
+// src-tauri/src/clipboard/watcher.rs
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use super::backend::ClipboardBackend;
+use super::capture::{Capture, ClipboardContent};
+use crate::error::Result;
+use crate::models::HistoryItem;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Background thread that polls a clipboard backend and persists changes.
+/// Stops when dropped.
+pub struct ClipboardWatcher {
+    stop: Arc<AtomicBool>,
+    handle: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl ClipboardWatcher {
+    /// `open_backend` runs on the watcher thread, since OS clipboard
+    /// handles must live on the thread that uses them.
+    pub fn spawn<B, O, F>(open_backend: O, mut capture: Capture, on_change: F) -> Self
+    where
+        B: ClipboardBackend,
+        O: FnOnce() -> Result<B> + Send + 'static,
+        F: Fn(&HistoryItem) + Send + 'static,
+    {
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = Arc::clone(&stop);
+
+        let handle = thread::Builder::new()
+            .name("clipboard-watcher".into())
+            .spawn(move || {
+                let mut backend = match open_backend() {
+                    Ok(backend) => backend,
+                    Err(err) => {
+                        eprintln!("[clipboard] watcher disabled: {err}");
+                        return;
+                    }
+                };
+
+                let mut last_token = None;
+                while !thread_stop.load(Ordering::Relaxed) {
+                    match poll_once(&mut backend, &mut capture, &mut last_token) {
+                        Ok(Some(item)) => on_change(&item),
+                        Ok(None) => {}
+                        Err(err) => eprintln!("[clipboard] capture failed: {err}"),
+                    }
+                    thread::sleep(POLL_INTERVAL);
+                }
+            })
+            .expect("failed to spawn clipboard watcher thread");
+
+        Self { stop, handle: Mutex::new(Some(handle)) }
+    }
+}
+
+impl Drop for ClipboardWatcher {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.lock().ok().and_then(|mut h| h.take()) {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// One watcher tick: reads the clipboard only if its change token moved.
+pub(crate) fn poll_once(
+    backend: &mut dyn ClipboardBackend,
+    capture: &mut Capture,
+    last_token: &mut Option<u64>,
+) -> Result<Option<HistoryItem>> {
+    let token = backend.change_token()?;
+    if *last_token == Some(token) {
+        return Ok(None);
+    }
+    *last_token = Some(token);
+
+    match ClipboardContent::read(backend)? {
+        Some(content) => capture.process(content),
+        None => Ok(None),
+    }
+}
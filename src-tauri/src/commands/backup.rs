@@ -0,0 +1,91 @@
+// src-tauri/src/commands/backup.rs
+
+use std::io::Cursor;
+
+use tauri::ipc::{InvokeBody, Request, Response};
+use tauri::State;
+
+use crate::backup::{self, MergeReport};
+use crate::error::{Error, Result};
+use crate::images::{ImageStore, ThumbnailCache};
+use crate::storage::{Database, ImportCounts};
+
+/// The backup as pretty-printed JSON, for the webview to save.
+#[tauri::command]
+pub fn export_backup(db: State<'_, Database>, include_secrets: bool) -> Result<String> {
+    backup::export(&db, include_secrets)?.to_json()
+}
+
+/// Validates `json` without touching the store and returns what a restore
+/// would bring in.
+#[tauri::command]
+pub fn check_backup(json: String) -> Result<ImportCounts> {
+    Ok(backup::parse(&json)?.counts())
+}
+
+#[tauri::command]
+pub fn restore_backup(db: State<'_, Database>, json: String) -> Result<ImportCounts> {
+    backup::restore(&db, &backup::parse(&json)?)
+}
+
+/// The backup plus its images as `.zip` bytes (an `ArrayBuffer` in JS).
+#[tauri::command]
+pub fn export_bundle(
+    db: State<'_, Database>,
+    images: State<'_, ImageStore>,
+    thumbs: State<'_, ThumbnailCache>,
+    include_secrets: bool,
+) -> Result<Response> {
+    let mut zip = Cursor::new(Vec::new());
+    backup::export_bundle(&db, &images, &thumbs, include_secrets, &mut zip)?;
+    Ok(Response::new(zip.into_inner()))
+}
+
+/// Restores a `.zip` sent as the raw invoke body.
+#[tauri::command]
+pub fn restore_bundle(
+    db: State<'_, Database>,
+    images: State<'_, ImageStore>,
+    thumbs: State<'_, ThumbnailCache>,
+    request: Request<'_>,
+) -> Result<ImportCounts> {
+    let bytes = raw_body(&request)?;
+    backup::restore_bundle(&db, &images, &thumbs, Cursor::new(bytes))
+}
+
+/// Merges a JSON backup into the store. With `dry_run` only reports what
+/// would be added, skipped and renamed.
+#[tauri::command]
+pub fn merge_backup(db: State<'_, Database>, json: String, dry_run: bool) -> Result<MergeReport> {
+    backup::merge(&db, &backup::parse(&json)?, dry_run)
+}
+
+/// `merge_backup` for a `.zip` sent as the raw invoke body, as a preview.
+#[tauri::command]
+pub fn preview_merge_bundle(
+    db: State<'_, Database>,
+    images: State<'_, ImageStore>,
+    thumbs: State<'_, ThumbnailCache>,
+    request: Request<'_>,
+) -> Result<MergeReport> {
+    let bytes = raw_body(&request)?;
+    backup::merge_bundle(&db, &images, &thumbs, Cursor::new(bytes), true)
+}
+
+#[tauri::command]
+pub fn merge_bundle(
+    db: State<'_, Database>,
+    images: State<'_, ImageStore>,
+    thumbs: State<'_, ThumbnailCache>,
+    request: Request<'_>,
+) -> Result<MergeReport> {
+    let bytes = raw_body(&request)?;
+    backup::merge_bundle(&db, &images, &thumbs, Cursor::new(bytes), false)
+}
+
+fn raw_body<'a>(request: &'a Request<'_>) -> Result<&'a [u8]> {
+    match request.body() {
+        InvokeBody::Raw(bytes) => Ok(bytes),
+        InvokeBody::Json(_) => Err(Error::InvalidInput("expected raw archive bytes".into())),
+    }
+}
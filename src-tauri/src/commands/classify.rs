@@ -0,0 +1,9 @@
+// src-tauri/src/commands/classify.rs
+
+use crate::classify::{self, Classification};
+
+/// Content type of text the webview creates itself, such as a typed note.
+#[tauri::command]
+pub fn classify_text(text: String) -> Classification {
+    classify::classify(&text)
+}
@@ -0,0 +1,16 @@
+// src-tauri/src/commands/clipboard.rs
+
+use tauri::State;
+
+use crate::clipboard::{self, SystemClipboard};
+use crate::error::Result;
+use crate::images::ImageStore;
+use crate::storage::Database;
+
+/// Writes a history item back to the system clipboard.
+#[tauri::command]
+pub fn copy_history_item(db: State<'_, Database>, images: State<'_, ImageStore>, id: String) -> Result<()> {
+    let item = db.history_item(&id)?;
+    let mut backend = SystemClipboard::new()?;
+    clipboard::restore(&mut backend, &images, &item)
+}
@@ -0,0 +1,20 @@
+// src-tauri/src/commands/dates.rs
+
+use chrono::Local;
+use tauri::State;
+
+use crate::dates::{self, DateRange, HistoryGroup};
+use crate::error::Result;
+use crate::models::HistoryItem;
+use crate::storage::Database;
+
+#[tauri::command]
+pub fn history_in_range(db: State<'_, Database>, range: DateRange) -> Result<Vec<HistoryItem>> {
+    dates::history_between(&db, range)
+}
+
+/// History within `range`, bucketed into "Today", "Yesterday" and so on.
+#[tauri::command]
+pub fn history_by_period(db: State<'_, Database>, range: DateRange) -> Result<Vec<HistoryGroup>> {
+    Ok(dates::group_history(dates::history_between(&db, range)?, &Local::now()))
+}
@@ -0,0 +1,34 @@
+// src-tauri/src/commands/images.rs
+
+use tauri::ipc::{InvokeBody, Request, Response};
+use tauri::State;
+
+use crate::error::{Error, Result};
+use crate::images::{GcReport, ImageStore, StoredImage, GC_GRACE_PERIOD};
+
+/// Stores the raw image bytes sent as the invoke body
+/// (`invoke('put_image', bytes)` with a `Uint8Array`).
+#[tauri::command]
+pub fn put_image(images: State<'_, ImageStore>, request: Request<'_>) -> Result<StoredImage> {
+    match request.body() {
+        InvokeBody::Raw(bytes) => images.put(bytes),
+        InvokeBody::Json(_) => Err(Error::InvalidInput("expected raw image bytes".into())),
+    }
+}
+
+/// Returns the blob as an `ArrayBuffer`, skipping base64 round-trips.
+#[tauri::command]
+pub fn get_image(images: State<'_, ImageStore>, file_name: String) -> Result<Response> {
+    Ok(Response::new(images.get(&file_name)?))
+}
+
+#[tauri::command]
+pub fn release_image(images: State<'_, ImageStore>, file_name: String) -> Result<bool> {
+    images.release(&file_name)
+}
+
+/// Deletes orphaned image files. With `dry_run` only reports what would go.
+#[tauri::command]
+pub async fn collect_image_garbage(images: State<'_, ImageStore>, dry_run: bool) -> Result<GcReport> {
+    images.collect_garbage(GC_GRACE_PERIOD, dry_run)
+}
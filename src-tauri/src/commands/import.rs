@@ -0,0 +1,21 @@
+// src-tauri/src/commands/import.rs
+
+use std::path::PathBuf;
+
+use tauri::State;
+
+use crate::error::Result;
+use crate::images::ImageStore;
+use crate::import::{self, ImportReport, Source};
+use crate::storage::Database;
+
+/// Adds history from another clipboard manager's file at `path`.
+#[tauri::command]
+pub fn import_history(
+    db: State<'_, Database>,
+    images: State<'_, ImageStore>,
+    source: Source,
+    path: PathBuf,
+) -> Result<ImportReport> {
+    import::import(&db, &images, source, &path)
+}
@@ -0,0 +1,22 @@
+// src-tauri/src/commands/markdown.rs
+
+use std::path::PathBuf;
+
+use tauri::State;
+
+use crate::error::Result;
+use crate::images::ImageStore;
+use crate::markdown::{self, MarkdownExport};
+use crate::storage::Database;
+
+/// Writes the project as Markdown files into a new directory under `dir`.
+#[tauri::command]
+pub fn export_project_markdown(
+    db: State<'_, Database>,
+    images: State<'_, ImageStore>,
+    project_id: String,
+    dir: PathBuf,
+    include_secrets: bool,
+) -> Result<MarkdownExport> {
+    markdown::export_project(&db, &images, &project_id, &dir, include_secrets)
+}
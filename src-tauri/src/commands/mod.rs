@@ -0,0 +1,18 @@
+// src-tauri/src/commands/mod.rs
+
+//! Thin `#[tauri::command]` wrappers. Business logic lives in the
+//! domain modules so it can be exercised without a running app.
+
+pub mod backup;
+pub mod classify;
+pub mod clipboard;
+pub mod dates;
+pub mod images;
+pub mod import;
+pub mod markdown;
+pub mod retention;
+pub mod search;
+pub mod secrets;
+pub mod storage;
+pub mod trash;
+pub mod vault;
@@ -0,0 +1,24 @@
+// src-tauri/src/commands/retention.rs
+
+use chrono::Utc;
+use tauri::State;
+
+use crate::error::Result;
+use crate::retention::{self, PruneReport, RetentionPolicy};
+use crate::storage::Database;
+
+#[tauri::command]
+pub fn get_retention_policy(db: State<'_, Database>) -> Result<RetentionPolicy> {
+    retention::policy(&db)
+}
+
+#[tauri::command]
+pub fn set_retention_policy(db: State<'_, Database>, policy: RetentionPolicy) -> Result<()> {
+    retention::set_policy(&db, &policy)
+}
+
+/// Applies the stored policy right away instead of waiting for the timer.
+#[tauri::command]
+pub fn prune_history(db: State<'_, Database>) -> Result<PruneReport> {
+    retention::prune(&db, &retention::policy(&db)?, Utc::now().timestamp_millis())
+}
@@ -0,0 +1,60 @@
+// src-tauri/src/commands/search.rs
+
+use tauri::State;
+
+use crate::error::Result;
+use crate::models::NoteItem;
+use crate::search::{self, Query, RegexOptions, RegexSearchResult, SearchHit, SearchScope, DEFAULT_LIMIT};
+use crate::storage::Database;
+
+/// Ranked full-text search. `scope` defaults to everything.
+#[tauri::command]
+pub fn search(
+    db: State<'_, Database>,
+    query: String,
+    scope: Option<SearchScope>,
+    limit: Option<usize>,
+) -> Result<Vec<SearchHit>> {
+    search::search(&db, &query, scope.unwrap_or_default(), limit.unwrap_or(DEFAULT_LIMIT))
+}
+
+/// fzf-style subsequence matching for the command palette.
+#[tauri::command]
+pub fn fuzzy_search(db: State<'_, Database>, query: String, limit: Option<usize>) -> Result<Vec<SearchHit>> {
+    search::fuzzy_search(&db, &query, limit.unwrap_or(DEFAULT_LIMIT))
+}
+
+/// Parses a structured query without running it, for inline validation.
+#[tauri::command]
+pub fn parse_query(query: String) -> Result<Query> {
+    Ok(search::parse(&query)?)
+}
+
+/// History items, folders and notes matching a structured query such as
+/// `type:url tag:work -draft`, in display order.
+#[tauri::command]
+pub fn filter_items(db: State<'_, Database>, query: String) -> Result<Vec<SearchHit>> {
+    let query = search::parse(&query)?;
+    search::evaluate(&db, &query)
+}
+
+#[tauri::command]
+pub fn regex_search(
+    db: State<'_, Database>,
+    pattern: String,
+    options: Option<RegexOptions>,
+) -> Result<RegexSearchResult> {
+    search::regex_search(&db, &pattern, options.unwrap_or_default())
+}
+
+/// Saves every history match of `pattern` as a new note in `folder_id`.
+#[tauri::command]
+pub fn extract_regex_matches(
+    db: State<'_, Database>,
+    pattern: String,
+    options: Option<RegexOptions>,
+    folder_id: String,
+    unique: bool,
+) -> Result<NoteItem> {
+    search::extract_to_note(&db, &pattern, options.unwrap_or_default(), &folder_id, unique)
+}
@@ -0,0 +1,18 @@
+// src-tauri/src/commands/secrets.rs
+
+use tauri::State;
+
+use crate::error::Result;
+use crate::secrets::{self, SecretPolicy};
+use crate::storage::Database;
+
+/// What happens to captured text that contains a credential.
+#[tauri::command]
+pub fn get_secret_policy(db: State<'_, Database>) -> Result<SecretPolicy> {
+    secrets::policy(&db)
+}
+
+#[tauri::command]
+pub fn set_secret_policy(db: State<'_, Database>, policy: SecretPolicy) -> Result<()> {
+    secrets::set_policy(&db, policy)
+}
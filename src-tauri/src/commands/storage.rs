@@ -0,0 +1,170 @@
+// src-tauri/src/commands/storage.rs
+
+use tauri::State;
+
+use crate::classify::language_of;
+use crate::error::Result;
+use crate::ids;
+use crate::images::ImageStore;
+use crate::models::{AppData, ContentType, HistoryItem, Language, NoteItem};
+use crate::storage::{Database, LegacyPayload, MigrationReport};
+use crate::trash::{self, TrashEntry, TrashKind};
+
+#[tauri::command]
+pub fn load_app_data(db: State<'_, Database>) -> Result<AppData> {
+    db.load()
+}
+
+// --- Projects ---
+
+/// Returns the new project's id.
+#[tauri::command]
+pub fn add_project(db: State<'_, Database>, name: String) -> Result<String> {
+    let id = ids::new_id();
+    db.add_project(&id, &name)?;
+    Ok(id)
+}
+
+#[tauri::command]
+pub fn rename_project(db: State<'_, Database>, id: String, name: String) -> Result<()> {
+    db.rename_project(&id, &name)
+}
+
+/// Moves the project, with its folders and notes, to the trash.
+#[tauri::command]
+pub fn delete_project(db: State<'_, Database>, id: String) -> Result<TrashEntry> {
+    trash::delete(&db, TrashKind::Project, &id)
+}
+
+// --- Folders ---
+
+/// Returns the new folder's id.
+#[tauri::command]
+pub fn add_folder(db: State<'_, Database>, project_id: String, name: String) -> Result<String> {
+    let id = ids::new_id();
+    db.add_folder(&project_id, &id, &name)?;
+    Ok(id)
+}
+
+#[tauri::command]
+pub fn rename_folder(db: State<'_, Database>, id: String, name: String) -> Result<()> {
+    db.rename_folder(&id, &name)
+}
+
+#[tauri::command]
+pub fn reorder_folders(db: State<'_, Database>, project_id: String, ids: Vec<String>) -> Result<()> {
+    db.reorder_folders(&project_id, &ids)
+}
+
+/// Moves the folder and its notes to the trash.
+#[tauri::command]
+pub fn delete_folder(db: State<'_, Database>, id: String) -> Result<TrashEntry> {
+    trash::delete(&db, TrashKind::Folder, &id)
+}
+
+// --- Notes ---
+
+/// Stores `note` under a new id, whatever id it came with, and returns it.
+#[tauri::command]
+pub fn add_note(db: State<'_, Database>, folder_id: String, mut note: NoteItem) -> Result<NoteItem> {
+    note.id = ids::new_id();
+    note.language = note.language.or_else(|| language_of(note.content_type, &note.text));
+    db.add_note(&folder_id, &note)?;
+    Ok(note)
+}
+
+#[tauri::command]
+pub fn edit_note(
+    db: State<'_, Database>,
+    id: String,
+    text: String,
+    content_type: ContentType,
+    language: Option<Language>,
+    tags: Option<Vec<String>>,
+) -> Result<()> {
+    // Edited text may be in another language now, so only an explicit
+    // choice from the webview is kept.
+    let language = language.or_else(|| language_of(content_type, &text));
+    db.edit_note(&id, &text, content_type, language, tags.as_deref())
+}
+
+#[tauri::command]
+pub fn reorder_notes(db: State<'_, Database>, folder_id: String, ids: Vec<String>) -> Result<()> {
+    db.reorder_notes(&folder_id, &ids)
+}
+
+/// Moves the note to the trash.
+#[tauri::command]
+pub fn delete_note(db: State<'_, Database>, id: String) -> Result<TrashEntry> {
+    trash::delete(&db, TrashKind::Note, &id)
+}
+
+// --- History ---
+
+/// Stores `item` under a new id and returns it, or `None` when it repeats
+/// the newest entry.
+#[tauri::command]
+pub fn push_history_item(
+    db: State<'_, Database>,
+    mut item: HistoryItem,
+    max_items: Option<usize>,
+) -> Result<Option<HistoryItem>> {
+    item.id = ids::new_id();
+    item.language = item.language.or_else(|| language_of(item.content_type, &item.text));
+    Ok(db.push_history_item(&item, max_items)?.then_some(item))
+}
+
+#[tauri::command]
+pub fn restore_history_item(db: State<'_, Database>, item: HistoryItem) -> Result<()> {
+    db.restore_history_item(&item)
+}
+
+#[tauri::command]
+pub fn delete_history_item(db: State<'_, Database>, id: String) -> Result<()> {
+    db.delete_history_item(&id)
+}
+
+#[tauri::command]
+pub fn clear_history(db: State<'_, Database>) -> Result<()> {
+    db.clear_history()
+}
+
+#[tauri::command]
+pub fn toggle_favorite(db: State<'_, Database>, id: String) -> Result<bool> {
+    db.toggle_favorite(&id)
+}
+
+// --- Global Tags ---
+
+#[tauri::command]
+pub fn add_global_tag(db: State<'_, Database>, tag: String) -> Result<()> {
+    db.add_global_tag(&tag)
+}
+
+#[tauri::command]
+pub fn delete_global_tag(db: State<'_, Database>, tag: String) -> Result<()> {
+    db.delete_global_tag(&tag)
+}
+
+// --- Legacy Migration ---
+
+#[tauri::command]
+pub fn legacy_migration_status(db: State<'_, Database>) -> Result<bool> {
+    db.legacy_migration_done()
+}
+
+/// Also moves the images the migrated items point to into the image store.
+/// The data is in either way, so a failure there is only logged; the next
+/// startup and enabling encryption try again.
+#[tauri::command]
+pub fn migrate_legacy_data(
+    db: State<'_, Database>,
+    images: State<'_, ImageStore>,
+    payload: LegacyPayload,
+) -> Result<MigrationReport> {
+    let report = db.migrate_legacy(&payload)?;
+    if let Err(err) = images.adopt_legacy_files() {
+        eprintln!("[images] failed to adopt legacy images: {err}");
+    }
+    Ok(report)
+}
@@ -0,0 +1,25 @@
+// src-tauri/src/commands/trash.rs
+
+use tauri::State;
+
+use crate::error::Result;
+use crate::images::ImageStore;
+use crate::storage::Database;
+use crate::trash::{self, TrashEntry};
+
+#[tauri::command]
+pub fn list_trash(db: State<'_, Database>) -> Result<Vec<TrashEntry>> {
+    trash::list(&db)
+}
+
+/// `parent_id` puts a folder or note into another project or folder.
+#[tauri::command]
+pub fn restore_from_trash(db: State<'_, Database>, id: String, parent_id: Option<String>) -> Result<()> {
+    trash::restore(&db, &id, parent_id.as_deref())
+}
+
+/// Empties the whole trash when `id` is omitted.
+#[tauri::command]
+pub fn purge_trash(db: State<'_, Database>, images: State<'_, ImageStore>, id: Option<String>) -> Result<usize> {
+    trash::purge(&db, &images, id.as_deref())
+}
@@ -0,0 +1,77 @@
+// src-tauri/src/commands/vault.rs
+
+use tauri::State;
+use zeroize::Zeroizing;
+
+use crate::error::Result;
+use crate::images::{ImageStore, ThumbnailCache};
+use crate::models::HistoryItem;
+use crate::storage::Database;
+use crate::vault::{self, VaultStatus};
+
+#[tauri::command]
+pub fn encryption_status(db: State<'_, Database>) -> VaultStatus {
+    vault::status(&db)
+}
+
+/// Encrypts the whole store under `passphrase`. Takes a while on large
+/// histories: every item and image is rewritten.
+#[tauri::command]
+pub async fn enable_encryption(
+    db: State<'_, Database>,
+    images: State<'_, ImageStore>,
+    thumbs: State<'_, ThumbnailCache>,
+    passphrase: String,
+) -> Result<()> {
+    let passphrase = Zeroizing::new(passphrase);
+    vault::enable(&db, &images, &thumbs, &passphrase)
+}
+
+#[tauri::command]
+pub async fn disable_encryption(
+    db: State<'_, Database>,
+    images: State<'_, ImageStore>,
+    thumbs: State<'_, ThumbnailCache>,
+    passphrase: String,
+) -> Result<()> {
+    let passphrase = Zeroizing::new(passphrase);
+    vault::disable(&db, &images, &thumbs, &passphrase)
+}
+
+/// Returns the items captured while locked, now added to history.
+#[tauri::command]
+pub async fn unlock_store(
+    db: State<'_, Database>,
+    images: State<'_, ImageStore>,
+    passphrase: String,
+) -> Result<Vec<HistoryItem>> {
+    let passphrase = Zeroizing::new(passphrase);
+    vault::unlock(&db, &images, &passphrase)
+}
+
+#[tauri::command]
+pub fn lock_store(db: State<'_, Database>) -> Result<()> {
+    vault::lock(&db)
+}
+
+#[tauri::command]
+pub async fn change_passphrase(db: State<'_, Database>, current: String, new: String) -> Result<()> {
+    let (current, new) = (Zeroizing::new(current), Zeroizing::new(new));
+    vault::change_passphrase(&db, &current, &new)
+}
+
+/// Called by the webview on user input (throttled) to postpone the idle lock.
+#[tauri::command]
+pub fn report_activity(db: State<'_, Database>) {
+    db.keyring().touch();
+}
+
+#[tauri::command]
+pub fn get_auto_lock_minutes(db: State<'_, Database>) -> Result<Option<u32>> {
+    vault::auto_lock_minutes(&db)
+}
+
+#[tauri::command]
+pub fn set_auto_lock_minutes(db: State<'_, Database>, minutes: Option<u32>) -> Result<()> {
+    vault::set_auto_lock_minutes(&db, minutes)
+}
@@ -0,0 +1,97 @@
+// src-tauri/src/dates/legacy.rs
+
+//! Records from before timestamps only had `date`, the local time of day
+//! as `toLocaleTimeString` printed it ("14:05", "2:05 PM"). Their ids are
+//! usually `Date.now()` though, which is the exact capture instant; the
+//! difference between the two is the offset the record was made in.
+
+use chrono::{DateTime, Days, Local, NaiveTime, TimeZone, Timelike, Utc};
+use serde_json::Value;
+
+/// 2000-01-01T00:00:00Z; smaller numeric ids aren't timestamps.
+const EARLIEST_ID_MILLIS: i64 = 946_684_800_000;
+const DAY_MINUTES: i32 = 24 * 60;
+const DAY_MILLIS: i64 = 24 * 60 * 60 * 1000;
+
+/// Creation instant (Unix ms) and UTC offset (minutes) for a record that
+/// only has a legacy `date`. Without a timestamp id the time of day is
+/// placed on the latest day it could have been, as of `now`.
+pub(crate) fn from_legacy(id: &str, date: &str, now: DateTime<Local>) -> (i64, i32) {
+    let clock = parse_clock(date);
+
+    let millis = id.parse::<i64>().ok().filter(|m| (EARLIEST_ID_MILLIS..=now.timestamp_millis() + DAY_MILLIS).contains(m));
+    if let Some(instant) = millis.and_then(DateTime::<Utc>::from_timestamp_millis) {
+        let offset = match clock {
+            Some(clock) => offset_between(clock, instant),
+            None => local_offset(instant),
+        };
+        return (instant.timestamp_millis(), offset);
+    }
+
+    let Some(clock) = clock else {
+        return (now.timestamp_millis(), now.offset().local_minus_utc() / 60);
+    };
+    let mut day = now.date_naive();
+    loop {
+        // A time skipped by a DST change doesn't exist on that day.
+        if let Some(at) = Local.from_local_datetime(&day.and_time(clock)).earliest().filter(|at| *at <= now) {
+            return (at.timestamp_millis(), at.offset().local_minus_utc() / 60);
+        }
+        day = day - Days::new(1);
+    }
+}
+
+/// Replaces `date` on a serialized history item or note with
+/// `createdAt`, `updatedAt` and `utcOffset`. Records that already have
+/// `createdAt`, and values that aren't objects, are left as they are.
+pub(crate) fn upgrade_record(record: &mut Value, now: DateTime<Local>) {
+    let Some(record) = record.as_object_mut() else { return };
+    if record.contains_key("createdAt") {
+        return;
+    }
+    let date = record.remove("date");
+    let id = record.get("id").and_then(Value::as_str).unwrap_or_default();
+    let (created_at, utc_offset) = from_legacy(id, date.as_ref().and_then(Value::as_str).unwrap_or_default(), now);
+    record.insert("createdAt".into(), created_at.into());
+    record.insert("updatedAt".into(), created_at.into());
+    record.insert("utcOffset".into(), utc_offset.into());
+}
+
+/// "14:05", "14:05:09", "2:05 PM", "02:05 am".
+fn parse_clock(date: &str) -> Option<NaiveTime> {
+    let date = date.trim().to_ascii_lowercase();
+    let (clock, meridiem) = match date.strip_suffix("am").or_else(|| date.strip_suffix("a.m.")) {
+        Some(clock) => (clock, Some(0)),
+        None => match date.strip_suffix("pm").or_else(|| date.strip_suffix("p.m.")) {
+            Some(clock) => (clock, Some(12)),
+            None => (date.as_str(), None),
+        },
+    };
+    let mut parts = clock.trim().split(':').map(|part| part.parse::<u32>().ok());
+    let (Some(Some(hour)), Some(Some(minute))) = (parts.next(), parts.next()) else {
+        return None;
+    };
+    let hour = match meridiem {
+        Some(_) if !(1..=12).contains(&hour) => return None,
+        Some(shift) => hour % 12 + shift,
+        None => hour,
+    };
+    NaiveTime::from_hms_opt(hour, minute, 0)
+}
+
+/// The offset that makes `instant` read as `clock`, rounded to the
+/// quarter hour (the clock has no seconds, and no zone is finer).
+fn offset_between(clock: NaiveTime, instant: DateTime<Utc>) -> i32 {
+    let utc_minutes = (instant.hour() * 60 + instant.minute()) as i32;
+    let local_minutes = (clock.hour() * 60 + clock.minute()) as i32;
+    // Offsets run from -12:00 to +14:00.
+    let mut offset = (local_minutes - utc_minutes).rem_euclid(DAY_MINUTES);
+    if offset > 14 * 60 {
+        offset -= DAY_MINUTES;
+    }
+    (offset as f64 / 15.0).round() as i32 * 15
+}
+
+fn local_offset(instant: DateTime<Utc>) -> i32 {
+    instant.with_timezone(&Local).offset().local_minus_utc() / 60
+}
@@ -0,0 +1,93 @@
+// src-tauri/src/dates/mod.rs
+
+//! When things were copied. History items and notes store UTC instants
+//! (Unix milliseconds) plus the UTC offset of the machine that made them;
+//! relative buckets like "Yesterday" are worked out on the viewer's clock.
+
+mod legacy;
+
+#[cfg(test)]
+mod tests;
+
+pub(crate) use legacy::{from_legacy, upgrade_record};
+
+use chrono::{DateTime, Days, TimeZone};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::models::HistoryItem;
+use crate::storage::{history_from_row, Database, HISTORY_COLUMNS, HISTORY_ORDER};
+
+/// Where an instant falls relative to today, newest first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Period {
+    Today,
+    Yesterday,
+    /// The five days before yesterday.
+    LastWeek,
+    /// Up to 30 days back.
+    LastMonth,
+    Older,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryGroup {
+    pub period: Period,
+    pub items: Vec<HistoryItem>,
+}
+
+/// Unix milliseconds; `from` is inclusive, `to` exclusive, either open.
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DateRange {
+    pub from: Option<i64>,
+    pub to: Option<i64>,
+}
+
+/// History items created within `range`, newest first.
+pub fn history_between(db: &Database, range: DateRange) -> Result<Vec<HistoryItem>> {
+    if let (Some(from), Some(to)) = (range.from, range.to) {
+        if from > to {
+            return Err(Error::InvalidInput("date range ends before it starts".into()));
+        }
+    }
+    db.read(|conn| {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {HISTORY_COLUMNS} FROM history
+             WHERE created_at >= COALESCE(?1, created_at) AND created_at < COALESCE(?2, created_at + 1)
+             ORDER BY {HISTORY_ORDER}"
+        ))?;
+        let items = stmt.query_map([range.from, range.to], history_from_row)?.collect::<rusqlite::Result<_>>()?;
+        Ok(items)
+    })
+}
+
+pub fn period_of<Tz: TimeZone>(created_at: i64, now: &DateTime<Tz>) -> Period {
+    let today = now.date_naive();
+    let starts = [(0, Period::Today), (1, Period::Yesterday), (6, Period::LastWeek), (30, Period::LastMonth)];
+    for (days_back, period) in starts {
+        let day = today - Days::new(days_back);
+        // Midnight may not exist on a DST day; the day then starts at 01:00.
+        let start = (0..3).find_map(|hour| now.timezone().from_local_datetime(&day.and_hms_opt(hour, 0, 0)?).earliest());
+        if start.is_some_and(|start| created_at >= start.timestamp_millis()) {
+            return period;
+        }
+    }
+    Period::Older
+}
+
+/// Buckets `items`, keeping their order within each; empty periods are
+/// left out. Items are expected newest first, as `history_between` returns.
+pub fn group_history<Tz: TimeZone>(items: Vec<HistoryItem>, now: &DateTime<Tz>) -> Vec<HistoryGroup> {
+    let mut groups: Vec<HistoryGroup> = Vec::new();
+    for item in items {
+        let period = period_of(item.created_at, now);
+        match groups.iter_mut().find(|group| group.period == period) {
+            Some(group) => group.items.push(item),
+            None => groups.push(HistoryGroup { period, items: vec![item] }),
+        }
+    }
+    groups
+}
@@ -0,0 +1,85 @@
+// src-tauri/src/dates/tests.rs
+
+use chrono::{Duration, FixedOffset, Local, TimeZone};
+use serde_json::json;
+
+use super::*;
+use crate::models::ContentType;
+
+/// 2023-11-14T22:13:20Z.
+const INSTANT: i64 = 1_700_000_000_000;
+
+fn item(id: &str, created_at: i64) -> HistoryItem {
+    HistoryItem {
+        id: id.into(),
+        text: id.into(),
+        created_at,
+        updated_at: created_at,
+        utc_offset: 0,
+        content_type: ContentType::Text,
+        image_data: None,
+        language: None,
+        is_favorite: false,
+        is_secret: false,
+        expires_at: None,
+    }
+}
+
+#[test]
+fn legacy_clock_times_give_the_offset_of_timestamp_ids() {
+    let now = Local::now();
+    assert_eq!(from_legacy("1700000000000", "01:13", now), (INSTANT, 180));
+    assert_eq!(from_legacy("1700000000000", "5:13 PM", now), (INSTANT, -300));
+    assert_eq!(from_legacy("1700000000000", "03:43:20", now), (INSTANT, 330));
+
+    // Without a timestamp id, the latest such time that isn't in the future.
+    let (created_at, _) = from_legacy("n1", "00:00", now);
+    assert!(created_at <= now.timestamp_millis());
+    assert!(now.timestamp_millis() - created_at < Duration::days(1).num_milliseconds() + Duration::hours(1).num_milliseconds());
+    assert_eq!(from_legacy("n1", "whenever", now).0, now.timestamp_millis());
+
+    let mut record = json!({ "id": "1700000000000", "text": "x", "date": "01:13" });
+    upgrade_record(&mut record, now);
+    assert_eq!(record, json!({ "id": "1700000000000", "text": "x", "createdAt": INSTANT, "updatedAt": INSTANT, "utcOffset": 180 }));
+    let upgraded = record.clone();
+    upgrade_record(&mut record, now);
+    assert_eq!(record, upgraded);
+}
+
+#[test]
+fn periods_follow_the_viewers_calendar() {
+    let zone = FixedOffset::east_opt(3 * 3600).unwrap();
+    let now = zone.with_ymd_and_hms(2026, 3, 10, 9, 30, 0).unwrap();
+    let at = |day: u32, hour: u32| zone.with_ymd_and_hms(2026, 3, day, hour, 0, 0).unwrap().timestamp_millis();
+
+    assert_eq!(period_of(at(10, 0), &now), Period::Today);
+    assert_eq!(period_of(at(9, 23), &now), Period::Yesterday);
+    assert_eq!(period_of(at(4, 0), &now), Period::LastWeek);
+    assert_eq!(period_of(at(3, 23), &now), Period::LastMonth);
+    assert_eq!(period_of(zone.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap().timestamp_millis(), &now), Period::Older);
+
+    let items = vec![item("a", at(10, 8)), item("b", at(9, 12)), item("c", at(10, 1)), item("d", at(1, 0))];
+    let groups: Vec<_> = group_history(items, &now)
+        .into_iter()
+        .map(|group| (group.period, group.items.into_iter().map(|i| i.id).collect::<Vec<_>>()))
+        .collect();
+    assert_eq!(groups, [
+        (Period::Today, vec!["a".to_string(), "c".into()]),
+        (Period::Yesterday, vec!["b".into()]),
+        (Period::LastMonth, vec!["d".into()]),
+    ]);
+}
+
+#[test]
+fn ranges_are_half_open_and_newest_first() {
+    let db = Database::open_in_memory().unwrap();
+    for (id, created_at) in [("old", INSTANT), ("mid", INSTANT + 1000), ("new", INSTANT + 2000)] {
+        db.push_history_item(&item(id, created_at), None).unwrap();
+    }
+    let ids = |range| history_between(&db, range).unwrap().into_iter().map(|i| i.id).collect::<Vec<_>>();
+
+    assert_eq!(ids(DateRange::default()), ["new", "mid", "old"]);
+    assert_eq!(ids(DateRange { from: Some(INSTANT + 1000), to: Some(INSTANT + 2000) }), ["mid"]);
+    assert_eq!(ids(DateRange { from: None, to: Some(INSTANT + 1) }), ["old"]);
+    assert!(matches!(history_between(&db, DateRange { from: Some(2), to: Some(1) }), Err(Error::InvalidInput(_))));
+}
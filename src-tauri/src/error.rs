@@ -0,0 +1,109 @@
+// src-tauri/src/error.rs
+
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+
+/// Errors returned by the backend. They cross the IPC boundary as
+/// `{ kind, message }` so the webview can branch on `kind`.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("database error: {0}")]
+    Database(#[source] rusqlite::Error),
+
+    #[error("i/o error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("json error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("image error: {0}")]
+    Image(#[from] image::ImageError),
+
+    #[error("clipboard error: {0}")]
+    Clipboard(String),
+
+    #[error("invalid input: {0}")]
+    InvalidInput(String),
+
+    #[error("invalid query: {0}")]
+    InvalidQuery(#[from] crate::search::ParseError),
+
+    #[error("{entity} not found: {id}")]
+    NotFound { entity: &'static str, id: String },
+
+    #[error("store is locked")]
+    Locked,
+
+    #[error("wrong passphrase")]
+    WrongPassphrase,
+
+    #[error("encryption error: {0}")]
+    Crypto(String),
+
+    #[error("archive error: {0}")]
+    Archive(#[from] zip::result::ZipError),
+
+    #[error("invalid backup: {} problem(s), first {}", .0.len(), .0.first().map(ToString::to_string).unwrap_or_default())]
+    InvalidBackup(Vec<crate::backup::FieldError>),
+}
+
+impl From<rusqlite::Error> for Error {
+    fn from(err: rusqlite::Error) -> Self {
+        // `seal()` or `unseal()` failing inside a query, e.g. while locked.
+        crate::vault::take_function_error(&err).unwrap_or(Error::Database(err))
+    }
+}
+
+impl Error {
+    pub fn not_found(entity: &'static str, id: impl Into<String>) -> Self {
+        Error::NotFound { entity, id: id.into() }
+    }
+
+    /// Stable machine-readable tag for the frontend.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Error::Database(_) => "database",
+            Error::Io(_) => "io",
+            Error::Json(_) => "json",
+            Error::Image(_) => "image",
+            Error::Clipboard(_) => "clipboard",
+            Error::InvalidInput(_) => "invalidInput",
+            Error::InvalidQuery(_) => "invalidQuery",
+            Error::NotFound { .. } => "notFound",
+            Error::Locked => "locked",
+            Error::WrongPassphrase => "wrongPassphrase",
+            Error::Crypto(_) => "crypto",
+            Error::Archive(_) => "archive",
+            Error::InvalidBackup(_) => "invalidBackup",
+        }
+    }
+}
+
+impl Serialize for Error {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // Query errors also carry the offending span for inline display,
+        // backup errors every problem found.
+        let span = match self {
+            Error::InvalidQuery(err) => Some((err.start, err.end)),
+            _ => None,
+        };
+        let problems = match self {
+            Error::InvalidBackup(errors) => Some(errors),
+            _ => None,
+        };
+        let len = 2 + if span.is_some() { 2 } else { 0 } + usize::from(problems.is_some());
+        let mut state = serializer.serialize_struct("Error", len)?;
+        state.serialize_field("kind", self.kind())?;
+        state.serialize_field("message", &self.to_string())?;
+        if let Some((start, end)) = span {
+            state.serialize_field("start", &start)?;
+            state.serialize_field("end", &end)?;
+        }
+        if let Some(problems) = problems {
+            state.serialize_field("errors", problems)?;
+        }
+        state.end()
+    }
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -0,0 +1,25 @@
+// src-tauri/src/ids.rs
+
+//! Ids for projects, folders, notes and history items.
+//!
+//! New ids are UUIDv7: a millisecond timestamp followed by random bits, so
+//! they don't collide and sort by creation time as plain strings. Records
+//! from before keep their `Date.now()` ids. Either way, the storage layer
+//! refuses an id that's already used by a project, folder, note or history
+//! item, or held by an entry in the trash.
+
+use uuid::{NoContext, Timestamp, Uuid};
+
+/// An id for a record created now. Ids made in the same millisecond by
+/// this process still sort in the order they were made.
+pub fn new_id() -> String {
+    Uuid::now_v7().to_string()
+}
+
+/// An id that sorts as if the record was created at `millis` (Unix ms),
+/// for records brought in from elsewhere.
+pub fn id_at(millis: i64) -> String {
+    let millis = u64::try_from(millis).unwrap_or_default();
+    let timestamp = Timestamp::from_unix(NoContext, millis / 1000, (millis % 1000) as u32 * 1_000_000);
+    Uuid::new_v7(timestamp).to_string()
+}
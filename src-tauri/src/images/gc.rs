@@ -0,0 +1,93 @@
+// src-tauri/src/images/gc.rs
+
+//! Removes image blobs that no history item, note or trash entry points to.
+//!
+//! Only blobs registered in the `images` table are candidates: anything else
+//! in the directory isn't ours to delete. The webview's old `img_*.png`
+//! files become ours once `adopt_legacy_files` has moved them in, which the
+//! startup run does first. Only files older than the grace period are
+//! touched, so an image that was just written (or is waiting for an undo)
+//! survives until the next run.
+
+use std::collections::HashSet;
+use std::fs;
+use std::time::{Duration, SystemTime};
+
+use serde::Serialize;
+
+use super::ImageStore;
+use crate::error::Result;
+
+/// Default age an orphan must reach before it is deleted.
+pub const GC_GRACE_PERIOD: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[derive(Debug, Default, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GcReport {
+    pub dry_run: bool,
+    pub scanned: usize,
+    /// Orphans that were deleted (or would be, in a dry run).
+    pub removed: Vec<String>,
+    pub reclaimed_bytes: u64,
+    /// Orphans still inside the grace period.
+    pub pending: usize,
+}
+
+impl ImageStore {
+    /// Deletes registered blobs that nothing references and whose file is
+    /// older than `grace`. With `dry_run` nothing is deleted, only reported.
+    pub fn collect_garbage(&self, grace: Duration, dry_run: bool) -> Result<GcReport> {
+        let mut report = GcReport { dry_run, ..Default::default() };
+        let cutoff = SystemTime::now().checked_sub(grace).unwrap_or(SystemTime::UNIX_EPOCH);
+
+        // Hold the write lock for the whole sweep so a capture can't start
+        // referencing a file between the check and the delete.
+        self.db.write(|tx| {
+            let referenced: HashSet<String> = tx
+                .prepare(
+                    "SELECT image_data FROM history WHERE image_data IS NOT NULL
+                     UNION
+                     SELECT image_data FROM notes WHERE image_data IS NOT NULL
+                     UNION
+                     SELECT file_name FROM trash_images",
+                )?
+                .query_map([], |row| row.get(0))?
+                .collect::<rusqlite::Result<_>>()?;
+            let registered: Vec<String> = tx
+                .prepare("SELECT file_name FROM images ORDER BY file_name")?
+                .query_map([], |row| row.get(0))?
+                .collect::<rusqlite::Result<_>>()?;
+
+            for file_name in registered {
+                report.scanned += 1;
+                if referenced.contains(&file_name) {
+                    continue;
+                }
+
+                let path = self.path_of(&file_name)?;
+                let metadata = match fs::metadata(&path) {
+                    Ok(metadata) => Some(metadata),
+                    Err(err) if err.kind() == std::io::ErrorKind::NotFound => None,
+                    Err(err) => return Err(err.into()),
+                };
+                let modified = metadata.as_ref().and_then(|m| m.modified().ok()).unwrap_or(SystemTime::UNIX_EPOCH);
+                if modified > cutoff {
+                    report.pending += 1;
+                    continue;
+                }
+
+                if !dry_run {
+                    if metadata.is_some() {
+                        fs::remove_file(&path)?;
+                    }
+                    tx.execute("DELETE FROM images WHERE file_name = ?1", [&file_name])?;
+                }
+                report.reclaimed_bytes += metadata.map_or(0, |m| m.len());
+                report.removed.push(file_name);
+            }
+            Ok(())
+        })?;
+
+        Ok(report)
+    }
+}
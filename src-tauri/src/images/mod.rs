@@ -0,0 +1,252 @@
+// src-tauri/src/images/mod.rs
+
+//! Content-addressed image blobs in `AppLocalData/images/`.
+//!
+//! Files are named by the SHA-256 of their bytes, so copying the same
+//! screenshot twice stores it once. Reference counts are maintained by
+//! SQLite triggers on `history` and `notes` (see `storage/schema.rs`).
+//! With encryption on, the files hold sealed bytes; names and hashes are
+//! still those of the plaintext.
+
+mod gc;
+mod thumbs;
+
+#[cfg(test)]
+mod tests;
+
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use rusqlite::{params, OptionalExtension};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::error::{Error, Result};
+use crate::storage::Database;
+use crate::vault::Keyring;
+
+pub use gc::{GcReport, GC_GRACE_PERIOD};
+pub use thumbs::ThumbnailCache;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoredImage {
+    /// Value to put into `imageData` on history items and notes.
+    pub file_name: String,
+    pub sha256: String,
+    pub byte_size: u64,
+    /// `false` when identical bytes were already stored.
+    pub created: bool,
+}
+
+#[derive(Clone)]
+pub struct ImageStore {
+    db: Database,
+    dir: PathBuf,
+}
+
+impl ImageStore {
+    pub fn new(db: Database, dir: PathBuf) -> Self {
+        Self { db, dir }
+    }
+
+    #[cfg(test)]
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub(crate) fn keyring(&self) -> &Keyring {
+        self.db.keyring()
+    }
+
+    /// Stores `bytes` (PNG, JPEG, WebP or GIF) unless an identical blob
+    /// already exists, and returns the blob's file name either way.
+    pub fn put(&self, bytes: &[u8]) -> Result<StoredImage> {
+        let sha256 = hex_digest(bytes);
+        let file_name = format!("{sha256}.{}", extension(bytes)?);
+        let byte_size = bytes.len() as u64;
+
+        self.db.write(|tx| {
+            let known = tx
+                .query_row("SELECT 1 FROM images WHERE sha256 = ?1", [&sha256], |_| Ok(()))
+                .optional()?
+                .is_some();
+
+            // Re-write the file if it went missing behind our back; otherwise
+            // refresh its mtime so the GC grace period starts over.
+            let path = self.dir.join(&file_name);
+            if !known || !path.is_file() {
+                write_atomically(&self.dir, &file_name, &self.keyring().seal(bytes)?)?;
+            } else {
+                fs::File::options().append(true).open(&path)?.set_modified(SystemTime::now())?;
+            }
+            if !known {
+                tx.execute(
+                    "INSERT INTO images (file_name, sha256, byte_size, ref_count, created_at)
+                     VALUES (?1, ?2, ?3, (SELECT COUNT(*) FROM history WHERE image_data = ?1)
+                                       + (SELECT COUNT(*) FROM notes WHERE image_data = ?1), ?4)",
+                    params![file_name, sha256, byte_size as i64, now_millis()],
+                )?;
+            }
+
+            Ok(StoredImage { file_name: file_name.clone(), sha256: sha256.clone(), byte_size, created: !known })
+        })
+    }
+
+    /// The file name `put` would store `bytes` under.
+    pub fn name_for(bytes: &[u8]) -> Result<String> {
+        Ok(format!("{}.{}", hex_digest(bytes), extension(bytes)?))
+    }
+
+    pub fn get(&self, file_name: &str) -> Result<Vec<u8>> {
+        let bytes = fs::read(self.path_of(file_name)?)?;
+        Ok(self.keyring().open(&bytes)?.into_owned())
+    }
+
+    /// Full path of a blob. Rejects names that would escape the store.
+    pub fn path_of(&self, file_name: &str) -> Result<PathBuf> {
+        let is_plain = !file_name.is_empty()
+            && !file_name.starts_with('.')
+            && !file_name.contains(['/', '\\']);
+        if !is_plain {
+            return Err(Error::InvalidInput(format!("invalid image name: {file_name}")));
+        }
+        Ok(self.dir.join(file_name))
+    }
+
+    /// Deletes the blob if no history item, note or trash entry references it
+    /// anymore.
+    /// Returns whether the file was removed.
+    pub fn release(&self, file_name: &str) -> Result<bool> {
+        let path = self.path_of(file_name)?;
+        self.db.write(|tx| {
+            let ref_count: Option<i64> = tx
+                .query_row("SELECT ref_count FROM images WHERE file_name = ?1", [file_name], |row| row.get(0))
+                .optional()?;
+
+            match ref_count {
+                Some(count) if count <= 0 => {
+                    tx.execute("DELETE FROM images WHERE file_name = ?1", [file_name])?;
+                    match fs::remove_file(&path) {
+                        Ok(()) => Ok(true),
+                        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(false),
+                        Err(err) => Err(err.into()),
+                    }
+                }
+                Some(_) => Ok(false),
+                None => Err(Error::not_found("image", file_name)),
+            }
+        })
+    }
+
+    /// Moves the webview's old `img_*.png` files that history items and
+    /// notes still point to into the store, so they are sealed and
+    /// collected like any other blob. The old file stays while a trash entry
+    /// still names it. Returns how many files were adopted.
+    pub fn adopt_legacy_files(&self) -> Result<usize> {
+        let file_names: Vec<String> = self.db.read(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT image_data FROM history WHERE image_data IS NOT NULL
+                 UNION
+                 SELECT image_data FROM notes WHERE image_data IS NOT NULL
+                 EXCEPT
+                 SELECT file_name FROM images
+                 ORDER BY 1",
+            )?;
+            let rows = stmt.query_map([], |row| row.get(0))?;
+            Ok(rows.collect::<rusqlite::Result<_>>()?)
+        })?;
+
+        let mut adopted = 0;
+        for file_name in file_names {
+            let Ok(path) = self.path_of(&file_name) else { continue };
+            let bytes = match fs::read(&path) {
+                Ok(bytes) => bytes,
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(err) => return Err(err.into()),
+            };
+            let stored = match self.put(&self.keyring().open(&bytes)?) {
+                Ok(stored) => stored,
+                // Not an image we can store; leave it where it is.
+                Err(Error::InvalidInput(_)) => continue,
+                Err(err) => return Err(err),
+            };
+
+            let in_trash = self.db.write(|tx| {
+                tx.execute("UPDATE history SET image_data = ?2 WHERE image_data = ?1", [&file_name, &stored.file_name])?;
+                tx.execute("UPDATE notes SET image_data = ?2 WHERE image_data = ?1", [&file_name, &stored.file_name])?;
+                Ok(tx
+                    .query_row("SELECT 1 FROM trash_images WHERE file_name = ?1", [&file_name], |_| Ok(()))
+                    .optional()?
+                    .is_some())
+            })?;
+            if !in_trash {
+                match fs::remove_file(&path) {
+                    // Another run got there first.
+                    Err(err) if err.kind() != std::io::ErrorKind::NotFound => return Err(err.into()),
+                    _ => {}
+                }
+            }
+            adopted += 1;
+        }
+        Ok(adopted)
+    }
+
+    /// Passes every registered blob through `convert` and replaces those it
+    /// returns new bytes for. Used when encryption is switched on or off.
+    /// Files the `images` table doesn't know about are left alone.
+    pub(crate) fn rewrite_all(&self, convert: impl Fn(&[u8]) -> Result<Option<Vec<u8>>>) -> Result<usize> {
+        let file_names: Vec<String> = self.db.read(|conn| {
+            let mut stmt = conn.prepare("SELECT file_name FROM images ORDER BY file_name")?;
+            let rows = stmt.query_map([], |row| row.get(0))?;
+            Ok(rows.collect::<rusqlite::Result<_>>()?)
+        })?;
+
+        let mut rewritten = 0;
+        for file_name in file_names {
+            let bytes = match fs::read(self.path_of(&file_name)?) {
+                Ok(bytes) => bytes,
+                // Gone already; the GC drops the row.
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(err) => return Err(err.into()),
+            };
+            if let Some(bytes) = convert(&bytes)? {
+                write_atomically(&self.dir, &file_name, &bytes)?;
+                rewritten += 1;
+            }
+        }
+        Ok(rewritten)
+    }
+}
+
+fn extension(bytes: &[u8]) -> Result<&'static str> {
+    match image::guess_format(bytes) {
+        Ok(image::ImageFormat::Png) => Ok("png"),
+        Ok(image::ImageFormat::Jpeg) => Ok("jpg"),
+        Ok(image::ImageFormat::WebP) => Ok("webp"),
+        Ok(image::ImageFormat::Gif) => Ok("gif"),
+        _ => Err(Error::InvalidInput("unsupported image format".into())),
+    }
+}
+
+pub(crate) fn hex_digest(bytes: &[u8]) -> String {
+    Sha256::digest(bytes).iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Writes to a temp file first so a crash never leaves a truncated blob
+/// under its final (content-addressed, hence trusted) name.
+fn write_atomically(dir: &Path, file_name: &str, bytes: &[u8]) -> Result<()> {
+    fs::create_dir_all(dir)?;
+    let tmp = dir.join(format!(".{file_name}.tmp"));
+    let mut file = fs::File::create(&tmp)?;
+    file.write_all(bytes)?;
+    file.sync_all()?;
+    fs::rename(&tmp, dir.join(file_name))?;
+    Ok(())
+}
+
+fn now_millis() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
@@ -0,0 +1,207 @@
+// src-tauri/src/images/tests.rs
+
+use std::fs;
+use std::io::Cursor;
+use std::time::Duration;
+
+use image::{ImageFormat, RgbaImage};
+
+use super::*;
+use crate::models::{ContentType, HistoryItem, NoteItem};
+use crate::trash::{self, TrashKind};
+
+struct Store {
+    db: Database,
+    images: ImageStore,
+    dir: tempfile::TempDir,
+}
+
+impl Store {
+    fn new() -> Self {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::open_in_memory().unwrap();
+        let images = ImageStore::new(db.clone(), dir.path().join("images"));
+        Self { db, images, dir }
+    }
+
+    /// Stores an image and, when `id` is given, a history item showing it.
+    fn capture(&self, id: Option<&str>, red: u8) -> String {
+        let stored = self.images.put(&png(red)).unwrap();
+        if let Some(id) = id {
+            self.db.push_history_item(&history_image(id, &stored.file_name), None).unwrap();
+        }
+        stored.file_name
+    }
+
+    fn on_disk(&self) -> Vec<String> {
+        let mut names: Vec<_> = fs::read_dir(self.dir.path().join("images"))
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        names
+    }
+}
+
+fn png(red: u8) -> Vec<u8> {
+    let mut out = Cursor::new(Vec::new());
+    RgbaImage::from_pixel(2, 2, image::Rgba([red, 0, 0, 255])).write_to(&mut out, ImageFormat::Png).unwrap();
+    out.into_inner()
+}
+
+fn history_image(id: &str, file_name: &str) -> HistoryItem {
+    HistoryItem {
+        id: id.into(),
+        text: "Image".into(),
+        created_at: 1_700_000_000_000,
+        updated_at: 1_700_000_000_000,
+        utc_offset: 0,
+        content_type: ContentType::Image,
+        image_data: Some(file_name.into()),
+        language: None,
+        is_favorite: false,
+        is_secret: false,
+        expires_at: None,
+    }
+}
+
+#[test]
+fn orphans_wait_out_the_grace_period() {
+    let store = Store::new();
+    let orphan = store.capture(None, 1);
+
+    let report = store.images.collect_garbage(Duration::from_secs(60), false).unwrap();
+    assert_eq!((report.pending, report.removed.len()), (1, 0));
+
+    let report = store.images.collect_garbage(Duration::ZERO, false).unwrap();
+    assert_eq!(report.removed, [orphan]);
+    assert!(report.reclaimed_bytes > 0);
+    assert!(store.on_disk().is_empty());
+}
+
+#[test]
+fn a_dry_run_only_reports() {
+    let store = Store::new();
+    let orphan = store.capture(None, 1);
+
+    let report = store.images.collect_garbage(Duration::ZERO, true).unwrap();
+    assert!(report.dry_run);
+    assert_eq!(report.removed, [orphan.as_str()]);
+    assert_eq!(store.on_disk(), [orphan.as_str()]);
+    assert_eq!(store.images.get(&orphan).unwrap(), png(1));
+}
+
+#[test]
+fn referenced_and_trashed_images_are_kept() {
+    let store = Store::new();
+    let in_history = store.capture(Some("h1"), 1);
+    let in_trash = store.capture(None, 2);
+    let note = NoteItem {
+        id: "n1".into(),
+        text: "Image".into(),
+        created_at: 1_700_000_000_000,
+        updated_at: 1_700_000_000_000,
+        utc_offset: 0,
+        content_type: ContentType::Image,
+        tags: Vec::new(),
+        image_data: Some(in_trash.clone()),
+        language: None,
+        is_favorite: false,
+        is_secret: false,
+    };
+    store.db.add_note("f1", &note).unwrap();
+    trash::delete(&store.db, TrashKind::Note, "n1").unwrap();
+
+    let report = store.images.collect_garbage(Duration::ZERO, false).unwrap();
+    assert_eq!((report.scanned, report.removed.len()), (2, 0));
+    let mut kept = vec![in_history, in_trash];
+    kept.sort();
+    assert_eq!(store.on_disk(), kept);
+}
+
+#[test]
+fn files_the_store_did_not_write_are_left_alone() {
+    let store = Store::new();
+    store.capture(None, 1);
+    // The webview's own captures, from before the store existed.
+    let foreign = "img_1700000000000_5f0c6a1e-4c2b-4c55-9a43-0a4b1f0e9d11.png";
+    fs::write(store.dir.path().join("images").join(foreign), png(3)).unwrap();
+    fs::write(store.dir.path().join("images").join("notes.txt"), "keep").unwrap();
+
+    let report = store.images.collect_garbage(Duration::ZERO, false).unwrap();
+    assert_eq!((report.scanned, report.removed.len()), (1, 1));
+    assert_eq!(store.on_disk(), [foreign, "notes.txt"]);
+}
+
+#[test]
+fn legacy_files_still_in_use_are_adopted_and_later_collected() {
+    let store = Store::new();
+    let legacy = "img_1700000000000_5f0c6a1e-4c2b-4c55-9a43-0a4b1f0e9d11.png";
+    let unused = "img_1700000000001_0d6c0f59-2c57-4d7e-8f0b-1b9b0f6c2a40.png";
+    fs::create_dir_all(store.dir.path().join("images")).unwrap();
+    fs::write(store.images.path_of(legacy).unwrap(), png(3)).unwrap();
+    fs::write(store.images.path_of(unused).unwrap(), png(4)).unwrap();
+    store.db.push_history_item(&history_image("h1", legacy), None).unwrap();
+
+    assert_eq!(store.images.adopt_legacy_files().unwrap(), 1);
+    let adopted = ImageStore::name_for(&png(3)).unwrap();
+    assert_eq!(store.db.history_item("h1").unwrap().image_data.as_deref(), Some(adopted.as_str()));
+    assert_eq!(store.images.get(&adopted).unwrap(), png(3));
+    assert_eq!(store.on_disk(), [adopted.as_str(), unused]);
+    assert_eq!(store.images.adopt_legacy_files().unwrap(), 0);
+
+    store.db.delete_history_item("h1").unwrap();
+    let report = store.images.collect_garbage(Duration::ZERO, false).unwrap();
+    assert_eq!(report.removed, [adopted]);
+    assert_eq!(store.on_disk(), [unused]);
+}
+
+fn wide_png() -> Vec<u8> {
+    let mut out = Cursor::new(Vec::new());
+    RgbaImage::from_pixel(1000, 500, image::Rgba([0, 90, 0, 255])).write_to(&mut out, ImageFormat::Png).unwrap();
+    out.into_inner()
+}
+
+fn thumbs_of(store: &Store) -> ThumbnailCache {
+    ThumbnailCache::new(store.images.clone(), store.dir.path().join("thumbs"))
+}
+
+#[test]
+fn thumbnails_fit_the_max_edge() {
+    let store = Store::new();
+    let stored = store.images.put(&wide_png()).unwrap();
+
+    let thumb = image::load_from_memory(&thumbs_of(&store).get(&stored.file_name).unwrap()).unwrap();
+    assert_eq!((thumb.width(), thumb.height()), (thumbs::THUMB_MAX_EDGE, thumbs::THUMB_MAX_EDGE / 2));
+}
+
+#[test]
+fn a_cached_thumbnail_is_served_without_the_source() {
+    let store = Store::new();
+    let thumbs = thumbs_of(&store);
+    let stored = store.images.put(&wide_png()).unwrap();
+    let first = thumbs.get(&stored.file_name).unwrap();
+
+    fs::remove_file(store.images.path_of(&stored.file_name).unwrap()).unwrap();
+    assert_eq!(thumbs.get(&stored.file_name).unwrap(), first);
+
+    thumbs.clear().unwrap();
+    assert!(thumbs.get(&stored.file_name).is_err(), "cleared cache regenerated from a missing file");
+}
+
+#[test]
+fn replacing_a_legacy_file_invalidates_its_thumbnail() {
+    let store = Store::new();
+    let thumbs = thumbs_of(&store);
+    // Webview-era names carry no hash, so the cache keys them by content.
+    let legacy = "img_1700000000000_5f0c6a1e-4c2b-4c55-9a43-0a4b1f0e9d11.png";
+    let path = store.images.path_of(legacy).unwrap();
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+
+    fs::write(&path, png(10)).unwrap();
+    let before = image::load_from_memory(&thumbs.get(legacy).unwrap()).unwrap().to_rgba8();
+    fs::write(&path, png(250)).unwrap();
+    let after = image::load_from_memory(&thumbs.get(legacy).unwrap()).unwrap().to_rgba8();
+
+    assert_eq!((before.get_pixel(0, 0)[0], after.get_pixel(0, 0)[0]), (10, 250));
+}
@@ -0,0 +1,102 @@
+// src-tauri/src/images/thumbs.rs
+
+//! Small previews for image cards, cached in `AppLocalData/thumbs/`.
+//!
+//! Thumbnails are keyed by the SHA-256 of the source image, so renamed or
+//! duplicated files share one preview and a changed file never serves a
+//! stale one. Previews are sealed like the images they come from.
+
+use std::fs;
+use std::io::Cursor;
+use std::path::PathBuf;
+
+use image::ImageFormat;
+
+use super::{hex_digest, ImageStore};
+use crate::error::Result;
+
+/// Longest edge of a generated thumbnail, in pixels.
+pub const THUMB_MAX_EDGE: u32 = 320;
+
+#[derive(Clone)]
+pub struct ThumbnailCache {
+    images: ImageStore,
+    dir: PathBuf,
+}
+
+impl ThumbnailCache {
+    pub fn new(images: ImageStore, dir: PathBuf) -> Self {
+        Self { images, dir }
+    }
+
+    /// Returns the PNG thumbnail for `file_name`, generating it on first use.
+    pub fn get(&self, file_name: &str) -> Result<Vec<u8>> {
+        let keyring = self.images.keyring();
+        // Rejects names that would escape the store, even on a cache hit.
+        self.images.path_of(file_name)?;
+
+        // Content-addressed blobs already carry their hash in the name.
+        let (key, source) = match content_hash_from_name(file_name) {
+            Some(hash) => (hash.to_string(), None),
+            None => {
+                let bytes = self.images.get(file_name)?;
+                (hex_digest(&bytes), Some(bytes))
+            }
+        };
+
+        if let Ok(bytes) = fs::read(self.cached_path(&key)) {
+            return Ok(keyring.open(&bytes)?.into_owned());
+        }
+
+        let source = match source {
+            Some(bytes) => bytes,
+            None => self.images.get(file_name)?,
+        };
+        let bytes = render(&source)?;
+        self.insert(&key, &bytes)?;
+        Ok(bytes)
+    }
+
+    /// Caches a ready-made preview, e.g. one restored from a backup, for
+    /// the image whose SHA-256 is `source_sha256`.
+    pub fn insert(&self, source_sha256: &str, bytes: &[u8]) -> Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let tmp = self.dir.join(format!(".{source_sha256}.tmp"));
+        fs::write(&tmp, self.images.keyring().seal(bytes)?)?;
+        fs::rename(&tmp, self.cached_path(source_sha256))?;
+        Ok(())
+    }
+
+    fn cached_path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{key}_{THUMB_MAX_EDGE}.png"))
+    }
+
+    /// Deletes every cached preview; they are regenerated on demand.
+    pub fn clear(&self) -> Result<()> {
+        match fs::remove_dir_all(&self.dir) {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(err.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Decodes `source` and scales it down to fit `THUMB_MAX_EDGE`.
+/// Images that are already small are re-encoded unchanged.
+pub fn render(source: &[u8]) -> Result<Vec<u8>> {
+    let image = image::load_from_memory(source)?;
+    let thumb = if image.width() > THUMB_MAX_EDGE || image.height() > THUMB_MAX_EDGE {
+        image.thumbnail(THUMB_MAX_EDGE, THUMB_MAX_EDGE)
+    } else {
+        image
+    };
+
+    let mut out = Cursor::new(Vec::new());
+    thumb.write_to(&mut out, ImageFormat::Png)?;
+    Ok(out.into_inner())
+}
+
+/// `<64 hex chars>.<ext>` → the hex part.
+fn content_hash_from_name(file_name: &str) -> Option<&str> {
+    let (stem, _) = file_name.split_once('.')?;
+    (stem.len() == 64 && stem.bytes().all(|b| b.is_ascii_hexdigit())).then_some(stem)
+}
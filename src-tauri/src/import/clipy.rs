@@ -0,0 +1,54 @@
+// src-tauri/src/import/clipy.rs
+
+//! Clipy keeps its history in a Realm database, which can't be read
+//! without Realm itself. What it can export is snippets:
+//!
+//! ```xml
+//! <folders>
+//!   <folder><title>Git</title><snippets>
+//!     <snippet><title>status</title><content>git status</content></snippet>
+//!   </snippets></folder>
+//! </folders>
+//! ```
+//!
+//! Snippets carry no dates and are dated at import, in file order. They
+//! were kept on purpose, so they come in as favorites.
+
+use std::fs;
+use std::path::Path;
+
+use super::{xml, Parsed};
+use crate::error::{Error, Result};
+
+/// Found at byte 16 of every Realm file.
+const REALM_MAGIC: &[u8] = b"T-DB";
+
+pub(super) fn read(path: &Path) -> Result<Parsed> {
+    let bytes = fs::read(path)?;
+    if bytes.get(16..20) == Some(REALM_MAGIC) {
+        return Err(Error::InvalidInput(
+            "Clipy's history database (Realm) can't be read; export snippets from Clipy's snippet editor instead"
+                .into(),
+        ));
+    }
+    let source = String::from_utf8(bytes).map_err(|_| Error::InvalidInput("snippet export is not UTF-8".into()))?;
+    let root = xml::parse(&source)?;
+    if root.name != "folders" {
+        return Err(Error::InvalidInput("not a Clipy snippet export".into()));
+    }
+
+    let mut parsed = Parsed::default();
+    for (f, folder) in root.children("folder").enumerate() {
+        let snippets = folder.child("snippets").map(|s| s.children("snippet").collect()).unwrap_or_else(Vec::new);
+        for (s, snippet) in snippets.into_iter().enumerate() {
+            let record = format!("folders/{f}/snippets/{s}");
+            match snippet.child_text("content") {
+                Some(content) if !content.trim().is_empty() => {
+                    parsed.text(record, None, content.to_string()).is_favorite = true;
+                }
+                _ => parsed.unsupported(record, "empty snippet"),
+            }
+        }
+    }
+    Ok(parsed)
+}
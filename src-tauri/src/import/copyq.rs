@@ -0,0 +1,149 @@
+// src-tauri/src/import/copyq.rs
+
+//! CopyQ saves each tab as a Qt `QDataStream` (big-endian), newest item
+//! first:
+//!
+//! ```text
+//! qint32 item count
+//! per item:   qint32 -2, qint32 format count
+//! per format: QString MIME type, bool compressed, QByteArray data
+//! ```
+//!
+//! MIME types have common prefixes shortened to a digit (`1plain` is
+//! `text/plain`); compressed data is `qCompress`ed (a big-endian length,
+//! then zlib). CopyQ keeps no copy times, so items are dated at import,
+//! in tab order. Tabs saved by the encryption or synchronization plugins
+//! start with a header and aren't supported.
+
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+use flate2::read::ZlibDecoder;
+
+use super::{to_png, Parsed};
+use crate::error::{Error, Result};
+
+/// Marks the per-item layout above; `-1` was an older `QVariantMap` one.
+const ITEM_VERSION: i32 = -2;
+
+const MIME_PREFIXES: [(char, &str); 4] =
+    [('0', "application/x-copyq-"), ('1', "text/"), ('2', "application/"), ('3', "image/")];
+
+pub(super) fn read(path: &Path) -> Result<Parsed> {
+    let bytes = fs::read(path)?;
+    let mut stream = Stream { bytes: &bytes, pos: 0 };
+    if let Some(header) = stream.peek_header() {
+        return Err(Error::InvalidInput(format!("CopyQ tabs saved as \"{header}\" aren't supported")));
+    }
+
+    let count = stream.i32()?;
+    let mut items = Vec::new();
+    for index in 0..count.max(0) {
+        if stream.i32()? != ITEM_VERSION {
+            return Err(Error::InvalidInput("tab file from a CopyQ version that's too old".into()));
+        }
+        let mut formats = Vec::new();
+        for _ in 0..stream.i32()?.max(0) {
+            let mime = expand_mime(&stream.string()?);
+            let compressed = stream.bool()?;
+            let data = stream.byte_array()?;
+            formats.push((mime, if compressed { uncompress(data)? } else { data.to_vec() }));
+        }
+        items.push((index, formats));
+    }
+    if stream.pos != bytes.len() {
+        return Err(stream.error());
+    }
+
+    let mut parsed = Parsed::default();
+    for (index, formats) in items.into_iter().rev() {
+        let record = format!("items/{index}");
+        let find = |mime: &str| formats.iter().find(|(m, _)| m == mime).map(|(_, data)| data.as_slice());
+
+        if let Some(image) = find("image/png").or_else(|| find("image/jpeg")).or_else(|| find("image/gif")) {
+            parsed.image(record, None, image.to_vec());
+        } else if let Some(bmp) = find("image/bmp") {
+            match to_png(bmp, image::ImageFormat::Bmp) {
+                Ok(png) => {
+                    parsed.image(record, None, png);
+                }
+                Err(err) => parsed.unsupported(record, format!("unreadable bitmap: {err}")),
+            }
+        } else if let Some(text) = find("text/plain").or_else(|| find("text/uri-list")) {
+            parsed.text(record, None, String::from_utf8_lossy(text).into_owned());
+        } else {
+            let mimes: Vec<_> = formats.iter().map(|(mime, _)| mime.as_str()).collect();
+            parsed.unsupported(record, format!("no text or image among {}", mimes.join(", ")));
+        }
+    }
+    Ok(parsed)
+}
+
+fn expand_mime(mime: &str) -> String {
+    let mut chars = mime.chars();
+    match chars.next().and_then(|digit| MIME_PREFIXES.iter().find(|(d, _)| *d == digit)) {
+        Some((_, prefix)) => format!("{prefix}{}", chars.as_str()),
+        None => mime.to_string(),
+    }
+}
+
+/// `qUncompress`: the uncompressed size, then a zlib stream.
+fn uncompress(data: &[u8]) -> Result<Vec<u8>> {
+    let Some(zlib) = data.get(4..) else {
+        return Err(Error::InvalidInput("compressed CopyQ data is truncated".into()));
+    };
+    let mut out = Vec::new();
+    ZlibDecoder::new(zlib).read_to_end(&mut out)?;
+    Ok(out)
+}
+
+struct Stream<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Stream<'a> {
+    /// The plugin header (e.g. `CopyQ_encrypted_tab`) a tab starts with, if any.
+    fn peek_header(&self) -> Option<String> {
+        let mut probe = Stream { bytes: self.bytes, pos: self.pos };
+        probe.string().ok().filter(|header| header.starts_with("CopyQ"))
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let bytes = self.bytes.get(self.pos..self.pos + len).ok_or_else(|| self.error())?;
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    fn i32(&mut self) -> Result<i32> {
+        let b = self.take(4)?;
+        Ok(i32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    fn bool(&mut self) -> Result<bool> {
+        Ok(self.take(1)?[0] != 0)
+    }
+
+    /// A length (`0xFFFFFFFF` for null) and that many bytes.
+    fn byte_array(&mut self) -> Result<&'a [u8]> {
+        match self.i32()? as u32 {
+            u32::MAX => Ok(&[]),
+            len => self.take(len as usize),
+        }
+    }
+
+    /// A `byte_array` of UTF-16BE code units.
+    fn string(&mut self) -> Result<String> {
+        let bytes = self.byte_array()?;
+        if bytes.len() % 2 != 0 {
+            return Err(self.error());
+        }
+        let units: Vec<u16> = bytes.chunks_exact(2).map(|pair| u16::from_be_bytes([pair[0], pair[1]])).collect();
+        String::from_utf16(&units).map_err(|_| self.error())
+    }
+
+    fn error(&self) -> Error {
+        Error::InvalidInput(format!("not a CopyQ tab file (unexpected data at byte {})", self.pos))
+    }
+}
@@ -0,0 +1,96 @@
+// src-tauri/src/import/ditto.rs
+
+//! Ditto keeps clips in SQLite: one `Main` row per clip (`lDate` in Unix
+//! seconds, `mText` a plain-text description) and one `Data` row per
+//! Windows clipboard format it captured.
+
+use std::path::Path;
+
+use rusqlite::{Connection, OpenFlags};
+
+use super::{from_unix_seconds, to_png, Parsed};
+use crate::error::{Error, Result};
+
+pub(super) fn read(path: &Path) -> Result<Parsed> {
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+    let is_ditto = conn
+        .query_row("SELECT COUNT(*) FROM sqlite_master WHERE name IN ('Main', 'Data')", [], |row| row.get::<_, i64>(0))
+        .unwrap_or(0)
+        == 2;
+    if !is_ditto {
+        return Err(Error::InvalidInput("not a Ditto database".into()));
+    }
+
+    let mut parsed = Parsed::default();
+    let mut clips = conn.prepare("SELECT lID, lDate, mText, bIsGroup, lDontAutoDelete FROM Main ORDER BY lDate, lID")?;
+    let mut formats = conn.prepare("SELECT strClipBoardFormat, ooData FROM Data WHERE lParentID = ?1")?;
+    let mut rows = clips.query([])?;
+    while let Some(row) = rows.next()? {
+        let id: i64 = row.get(0)?;
+        let record = format!("Main/{id}");
+        if row.get::<_, Option<bool>>(3)?.unwrap_or_default() {
+            parsed.unsupported(record, "groups aren't imported, only the clips in them");
+            continue;
+        }
+        let copied_at = from_unix_seconds(row.get(1)?);
+        let description: Option<String> = row.get(2)?;
+        // A date here means "never auto delete", the closest thing to a favorite.
+        let keep = row.get::<_, Option<i64>>(4)?.unwrap_or_default() > 0;
+
+        let data: Vec<(String, Vec<u8>)> =
+            formats.query_map([id], |row| Ok((row.get(0)?, row.get(1)?)))?.collect::<rusqlite::Result<_>>()?;
+        let find = |name: &str| data.iter().find(|(format, _)| format == name).map(|(_, bytes)| bytes.as_slice());
+
+        let entry = if let Some(png) = find("PNG") {
+            parsed.image(record, copied_at, png.to_vec())
+        } else if let Some(dib) = find("CF_DIB") {
+            match dib_to_png(dib) {
+                Ok(png) => parsed.image(record, copied_at, png),
+                Err(err) => {
+                    parsed.unsupported(record, format!("unreadable bitmap: {err}"));
+                    continue;
+                }
+            }
+        } else if let Some(text) = find("CF_UNICODETEXT") {
+            let units: Vec<u16> = text.chunks_exact(2).map(|pair| u16::from_le_bytes([pair[0], pair[1]])).collect();
+            parsed.text(record, copied_at, String::from_utf16_lossy(&units).trim_end_matches('\0').to_string())
+        } else if let Some(text) = find("CF_TEXT") {
+            parsed.text(record, copied_at, String::from_utf8_lossy(text).trim_end_matches('\0').to_string())
+        } else if let (Some(_), Some(paths)) = (find("CF_HDROP"), description.filter(|d| !d.is_empty())) {
+            // Copied files; the description lists their paths.
+            parsed.text(record, copied_at, paths)
+        } else {
+            let names: Vec<_> = data.iter().map(|(format, _)| format.as_str()).collect();
+            parsed.unsupported(record, format!("no text or image among {}", names.join(", ")));
+            continue;
+        };
+        entry.is_favorite = keep;
+    }
+    Ok(parsed)
+}
+
+/// `CF_DIB` is a BMP file without its 14-byte file header.
+fn dib_to_png(dib: &[u8]) -> Result<Vec<u8>> {
+    let u32_at = |offset: usize| dib.get(offset..offset + 4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]));
+    let (Some(header_size), Some(bit_count), Some(compression), Some(colors_used)) =
+        (u32_at(0), dib.get(14..16), u32_at(16), u32_at(32))
+    else {
+        return Err(Error::InvalidInput("bitmap header is truncated".into()));
+    };
+    let bit_count = u16::from_le_bytes([bit_count[0], bit_count[1]]);
+    let palette = match colors_used {
+        0 if bit_count <= 8 => 1 << bit_count,
+        n => n,
+    };
+    const BI_BITFIELDS: u32 = 3;
+    let masks = if header_size == 40 && compression == BI_BITFIELDS { 12 } else { 0 };
+    let pixels_at = 14 + header_size + palette * 4 + masks;
+
+    let mut bmp = Vec::with_capacity(14 + dib.len());
+    bmp.extend_from_slice(b"BM");
+    bmp.extend_from_slice(&(14 + dib.len() as u32).to_le_bytes());
+    bmp.extend_from_slice(&[0; 4]);
+    bmp.extend_from_slice(&pixels_at.to_le_bytes());
+    bmp.extend_from_slice(dib);
+    to_png(&bmp, image::ImageFormat::Bmp)
+}
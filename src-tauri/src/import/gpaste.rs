@@ -0,0 +1,54 @@
+// src-tauri/src/import/gpaste.rs
+
+//! GPaste's `history.xml`, newest item first:
+//!
+//! ```xml
+//! <history version="2.0">
+//!   <item kind="Text" uuid="…" date="1700000000"><value><![CDATA[…]]></value></item>
+//!   <item kind="Image" uuid="…" date="…"><value><![CDATA[/…/images/….png]]></value></item>
+//! </history>
+//! ```
+//!
+//! `date` is in Unix seconds. Image values are paths to PNG files; relative
+//! ones are taken from the history file's directory. `Uris` items (copied
+//! files) come in as their URI list, `Password` items as secrets.
+
+use std::fs;
+use std::path::Path;
+
+use super::{from_unix_seconds, xml, Parsed};
+use crate::error::{Error, Result};
+
+pub(super) fn read(path: &Path) -> Result<Parsed> {
+    let root = xml::parse(&fs::read_to_string(path)?)?;
+    if root.name != "history" {
+        return Err(Error::InvalidInput("not a GPaste history file".into()));
+    }
+    let dir = path.parent().unwrap_or(Path::new(""));
+
+    let mut parsed = Parsed::default();
+    let items: Vec<_> = root.children("item").enumerate().collect();
+    for (index, item) in items.into_iter().rev() {
+        let record = format!("items/{index}");
+        let copied_at = item.attribute("date").and_then(|date| date.parse().ok()).and_then(from_unix_seconds);
+        let Some(value) = item.child_text("value") else {
+            parsed.unsupported(record, "no value");
+            continue;
+        };
+
+        match item.attribute("kind").unwrap_or("Text") {
+            "Text" | "Uris" => {
+                parsed.text(record, copied_at, value.to_string());
+            }
+            "Password" => parsed.text(record, copied_at, value.to_string()).is_secret = true,
+            "Image" => match fs::read(dir.join(value)) {
+                Ok(bytes) => {
+                    parsed.image(record, copied_at, bytes);
+                }
+                Err(err) => parsed.unsupported(record, format!("image {value}: {err}")),
+            },
+            kind => parsed.unsupported(record, format!("unknown kind {kind}")),
+        }
+    }
+    Ok(parsed)
+}
@@ -0,0 +1,72 @@
+// src-tauri/src/import/maccy.rs
+
+//! Maccy stores history with Core Data in SQLite: `ZHISTORYITEM` rows with
+//! copy times in seconds since 2001-01-01 UTC, and one
+//! `ZHISTORYITEMCONTENT` row per pasteboard type (a UTI such as
+//! `public.utf8-plain-text`).
+
+use std::path::Path;
+
+use chrono::{DateTime, TimeDelta, Utc};
+use rusqlite::{Connection, OpenFlags};
+
+use super::{to_png, Parsed};
+use crate::error::{Error, Result};
+
+/// 2001-01-01T00:00:00Z, where Core Data dates count from.
+const REFERENCE_DATE: i64 = 978_307_200;
+
+pub(super) fn read(path: &Path) -> Result<Parsed> {
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+    let is_maccy = conn
+        .query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE name IN ('ZHISTORYITEM', 'ZHISTORYITEMCONTENT')",
+            [],
+            |row| row.get::<_, i64>(0),
+        )
+        .unwrap_or(0)
+        == 2;
+    if !is_maccy {
+        return Err(Error::InvalidInput("not a Maccy database".into()));
+    }
+
+    let mut parsed = Parsed::default();
+    let mut items = conn.prepare("SELECT Z_PK, ZLASTCOPIEDAT, ZPIN FROM ZHISTORYITEM ORDER BY ZLASTCOPIEDAT, Z_PK")?;
+    let mut contents = conn.prepare("SELECT ZTYPE, ZVALUE FROM ZHISTORYITEMCONTENT WHERE ZITEM = ?1")?;
+    let mut rows = items.query([])?;
+    while let Some(row) = rows.next()? {
+        let id: i64 = row.get(0)?;
+        let record = format!("ZHISTORYITEM/{id}");
+        let copied_at = row.get::<_, Option<f64>>(1)?.and_then(from_core_data);
+        let pinned = row.get::<_, Option<String>>(2)?.is_some();
+
+        let data: Vec<(String, Option<Vec<u8>>)> =
+            contents.query_map([id], |row| Ok((row.get(0)?, row.get(1)?)))?.collect::<rusqlite::Result<_>>()?;
+        let find = |uti: &str| data.iter().find(|(kind, _)| kind == uti).and_then(|(_, value)| value.as_deref());
+
+        let entry = if let Some(png) = find("public.png") {
+            parsed.image(record, copied_at, png.to_vec())
+        } else if let Some(tiff) = find("public.tiff") {
+            match to_png(tiff, image::ImageFormat::Tiff) {
+                Ok(png) => parsed.image(record, copied_at, png),
+                Err(err) => {
+                    parsed.unsupported(record, format!("unreadable TIFF: {err}"));
+                    continue;
+                }
+            }
+        } else if let Some(text) = find("public.utf8-plain-text").or_else(|| find("public.file-url")) {
+            parsed.text(record, copied_at, String::from_utf8_lossy(text).into_owned())
+        } else {
+            let types: Vec<_> = data.iter().map(|(kind, _)| kind.as_str()).collect();
+            parsed.unsupported(record, format!("no text or image among {}", types.join(", ")));
+            continue;
+        };
+        entry.is_favorite = pinned;
+    }
+    Ok(parsed)
+}
+
+fn from_core_data(seconds: f64) -> Option<DateTime<Utc>> {
+    let millis = TimeDelta::try_milliseconds((seconds * 1000.0) as i64)?;
+    DateTime::from_timestamp(REFERENCE_DATE, 0)?.checked_add_signed(millis)
+}
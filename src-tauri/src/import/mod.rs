@@ -0,0 +1,229 @@
+// src-tauri/src/import/mod.rs
+
+//! Brings in history from other clipboard managers.
+//!
+//! Each reader turns a tool's files into `Entry`s, oldest first, and
+//! reports records it can't represent. Everything after that is shared:
+//! entries already in history are skipped, the rest get ids that sort by
+//! their copy time (see `ids::id_at`), a content type from
+//! `classify`, and images go through the image store as PNG, JPEG, WebP
+//! or GIF.
+//!
+//! Imported items keep the time they were originally copied, so they sort
+//! below today's copies and are the first to go when history is over its
+//! item limit. Age limits don't apply to them; they would otherwise wipe
+//! out years of imported history on the next prune.
+
+mod clipy;
+mod copyq;
+mod ditto;
+mod gpaste;
+mod maccy;
+mod xml;
+
+#[cfg(test)]
+mod tests;
+
+use std::collections::HashSet;
+use std::io::Cursor;
+use std::path::Path;
+
+use chrono::{DateTime, Local, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::classify::classify;
+use crate::error::Result;
+use crate::ids;
+use crate::images::ImageStore;
+use crate::models::{ContentType, HistoryItem};
+use crate::secrets;
+use crate::storage::{insert_history_item, mark_imported, Database};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Source {
+    /// A tab file, `copyq_tab_*.dat` in CopyQ's config directory.
+    CopyQ,
+    /// `Ditto.db`.
+    Ditto,
+    /// `Storage.sqlite` in Maccy's application support directory.
+    Maccy,
+    /// `~/.local/share/gpaste/history.xml`, images next to it.
+    GPaste,
+    /// A snippets `.xml` exported from Clipy's snippet editor.
+    Clipy,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Unsupported {
+    /// Where in the source, e.g. `Main/42` or `items/3`.
+    pub record: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportReport {
+    pub source: Source,
+    pub imported: usize,
+    /// Entries history already had.
+    pub duplicates: usize,
+    pub unsupported: Vec<Unsupported>,
+}
+
+/// One record read from another tool.
+struct Entry {
+    /// Where in the source, for `Unsupported`.
+    record: String,
+    /// `None` when the tool doesn't keep it; those are dated at import.
+    copied_at: Option<DateTime<Utc>>,
+    content: Content,
+    is_favorite: bool,
+    is_secret: bool,
+}
+
+enum Content {
+    Text(String),
+    /// In a format `ImageStore::put` accepts.
+    Image(Vec<u8>),
+}
+
+/// What a reader found.
+#[derive(Default)]
+struct Parsed {
+    entries: Vec<Entry>,
+    unsupported: Vec<Unsupported>,
+}
+
+impl Parsed {
+    fn text(&mut self, record: String, copied_at: Option<DateTime<Utc>>, text: String) -> &mut Entry {
+        self.push(record, copied_at, Content::Text(text))
+    }
+
+    fn image(&mut self, record: String, copied_at: Option<DateTime<Utc>>, bytes: Vec<u8>) -> &mut Entry {
+        self.push(record, copied_at, Content::Image(bytes))
+    }
+
+    fn push(&mut self, record: String, copied_at: Option<DateTime<Utc>>, content: Content) -> &mut Entry {
+        self.entries.push(Entry { record, copied_at, content, is_favorite: false, is_secret: false });
+        self.entries.last_mut().expect("just pushed")
+    }
+
+    fn unsupported(&mut self, record: impl Into<String>, reason: impl Into<String>) {
+        self.unsupported.push(Unsupported { record: record.into(), reason: reason.into() });
+    }
+}
+
+/// Reads `path` as written by `source` and adds what history doesn't have
+/// yet. Nothing is written if the file can't be read at all.
+pub fn import(db: &Database, images: &ImageStore, source: Source, path: &Path) -> Result<ImportReport> {
+    let Parsed { mut entries, unsupported } = match source {
+        Source::CopyQ => copyq::read(path)?,
+        Source::Ditto => ditto::read(path)?,
+        Source::Maccy => maccy::read(path)?,
+        Source::GPaste => gpaste::read(path)?,
+        Source::Clipy => clipy::read(path)?,
+    };
+    let now = Utc::now();
+    entries.sort_by_key(|entry| entry.copied_at.unwrap_or(now));
+
+    let mut known = db.read(|conn| {
+        let mut stmt = conn.prepare("SELECT content_type, unseal(text), image_data FROM history")?;
+        let rows = stmt.query_map([], |row| {
+            let content_type = ContentType::parse(&row.get::<_, String>(0)?);
+            Ok(match row.get::<_, Option<String>>(2)? {
+                Some(file_name) if content_type == ContentType::Image => file_name,
+                _ => row.get(1)?,
+            })
+        })?;
+        Ok(rows.collect::<rusqlite::Result<HashSet<_>>>()?)
+    })?;
+
+    let mut report = ImportReport { source, imported: 0, duplicates: 0, unsupported };
+    let mut items = Vec::new();
+    for entry in entries {
+        let copied_at = entry.copied_at.unwrap_or(now);
+        let created_at = copied_at.timestamp_millis();
+        let utc_offset = copied_at.with_timezone(&Local).offset().local_minus_utc() / 60;
+        // Undated entries share `now`; `new_id` keeps them in file order.
+        let id = match entry.copied_at {
+            Some(_) => ids::id_at(created_at),
+            None => ids::new_id(),
+        };
+        let item = match entry.content {
+            Content::Text(text) => {
+                if !known.insert(text.clone()) {
+                    report.duplicates += 1;
+                    continue;
+                }
+                let classification = classify(&text);
+                HistoryItem {
+                    id,
+                    is_secret: entry.is_secret || !secrets::scan(&text).is_empty(),
+                    text,
+                    created_at,
+                    updated_at: created_at,
+                    utc_offset,
+                    content_type: classification.content_type,
+                    image_data: None,
+                    language: classification.language,
+                    is_favorite: entry.is_favorite,
+                    expires_at: None,
+                }
+            }
+            Content::Image(bytes) => {
+                let file_name = match ImageStore::name_for(&bytes) {
+                    Ok(file_name) => file_name,
+                    Err(err) => {
+                        report.unsupported.push(Unsupported { record: entry.record, reason: err.to_string() });
+                        continue;
+                    }
+                };
+                if !known.insert(file_name) {
+                    report.duplicates += 1;
+                    continue;
+                }
+                // Before the history rows exist; the GC grace period
+                // covers a crash in between.
+                let stored = images.put(&bytes)?;
+                HistoryItem {
+                    id,
+                    text: "Image".into(),
+                    created_at,
+                    updated_at: created_at,
+                    utc_offset,
+                    content_type: ContentType::Image,
+                    image_data: Some(stored.file_name),
+                    language: None,
+                    is_favorite: entry.is_favorite,
+                    is_secret: entry.is_secret,
+                    expires_at: None,
+                }
+            }
+        };
+        items.push(item);
+    }
+
+    db.write(|tx| {
+        for item in items {
+            insert_history_item(tx, &item)?;
+            mark_imported(tx, &item.id, now.timestamp_millis())?;
+            report.imported += 1;
+        }
+        Ok(())
+    })?;
+    Ok(report)
+}
+
+/// Re-encodes an image `ImageStore::put` doesn't take as PNG.
+fn to_png(bytes: &[u8], format: image::ImageFormat) -> Result<Vec<u8>> {
+    let image = image::load_from_memory_with_format(bytes, format)?;
+    let mut png = Cursor::new(Vec::new());
+    image.write_to(&mut png, image::ImageFormat::Png)?;
+    Ok(png.into_inner())
+}
+
+fn from_unix_seconds(seconds: i64) -> Option<DateTime<Utc>> {
+    DateTime::from_timestamp(seconds, 0)
+}
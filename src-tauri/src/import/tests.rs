@@ -0,0 +1,189 @@
+// src-tauri/src/import/tests.rs
+
+use std::fs;
+use std::path::PathBuf;
+
+use super::*;
+use crate::error::Error;
+use crate::retention::{self, PruneReason, RetentionPolicy};
+
+/// Files as the other tools wrote them, in `fixtures/`.
+fn fixture(name: &str) -> PathBuf {
+    Path::new(file!()).with_file_name("fixtures").join(name)
+}
+
+struct Store {
+    db: Database,
+    images: ImageStore,
+    _dir: tempfile::TempDir,
+}
+
+impl Store {
+    fn new() -> Self {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::open_in_memory().unwrap();
+        let images = ImageStore::new(db.clone(), dir.path().join("images"));
+        Self { db, images, _dir: dir }
+    }
+
+    fn import(&self, source: Source, name: &str) -> ImportReport {
+        import(&self.db, &self.images, source, &fixture(name)).unwrap()
+    }
+
+    /// Oldest first.
+    fn history(&self) -> Vec<HistoryItem> {
+        let mut history = self.db.load().unwrap().history;
+        history.reverse();
+        history
+    }
+}
+
+fn unsupported(report: &ImportReport) -> Vec<&str> {
+    report.unsupported.iter().map(|u| u.record.as_str()).collect()
+}
+
+#[test]
+fn ditto_clips_keep_their_dates_and_bitmaps() {
+    let store = Store::new();
+    let report = store.import(Source::Ditto, "ditto.db");
+    assert_eq!((report.imported, report.duplicates), (3, 1));
+    assert_eq!(unsupported(&report), ["Main/3", "Main/4"]);
+    assert!(report.unsupported[1].reason.contains("Rich Text Format"));
+
+    let history = store.history();
+    assert_eq!(history[0].text, "Привет из Ditto");
+    assert_eq!(history[0].created_at, 1_700_000_000_000);
+    assert_eq!(history[1].text, r"C:\Users\me\report.pdf");
+    assert_eq!(history[2].content_type, ContentType::Image);
+    assert!(history[2].is_favorite, "\"never auto delete\" not kept");
+    assert!(history.windows(2).all(|pair| pair[0].id < pair[1].id), "ids don't sort by copy time");
+    let png = store.images.get(history[2].image_data.as_deref().unwrap()).unwrap();
+    assert_eq!(image::guess_format(&png).unwrap(), image::ImageFormat::Png);
+}
+
+#[test]
+fn imports_sort_below_local_history_and_are_trimmed_first() {
+    let store = Store::new();
+    let now = Utc::now().timestamp_millis();
+    for (i, text) in ["copied this morning", "copied just now"].into_iter().enumerate() {
+        let item = HistoryItem {
+            id: ids::new_id(),
+            text: text.into(),
+            created_at: now - 60_000 + i as i64,
+            updated_at: now,
+            utc_offset: 0,
+            content_type: ContentType::Text,
+            image_data: None,
+            language: None,
+            is_favorite: false,
+            is_secret: false,
+            expires_at: None,
+        };
+        store.db.push_history_item(&item, None).unwrap();
+    }
+    // Three more entries, years old, than the limit leaves room for.
+    store.import(Source::Ditto, "ditto.db");
+    let texts = |store: &Store| store.db.load().unwrap().history.into_iter().map(|h| h.text).collect::<Vec<_>>();
+    assert_eq!(texts(&store)[..2], ["copied just now", "copied this morning"]);
+
+    let policy = RetentionPolicy { max_items: Some(3), ..Default::default() };
+    let report = retention::prune(&store.db, &policy, now).unwrap();
+
+    assert!(report.removed.iter().all(|r| r.reason == PruneReason::OverLimit), "{report:?}");
+    assert_eq!(texts(&store), ["copied just now", "copied this morning", "Image"]);
+}
+
+#[test]
+fn old_imports_survive_the_default_retention_policy() {
+    let store = Store::new();
+    store.import(Source::Ditto, "ditto.db");
+    store.import(Source::Maccy, "maccy.sqlite");
+    let before = store.history();
+
+    let report = retention::prune(&store.db, &RetentionPolicy::default(), Utc::now().timestamp_millis()).unwrap();
+
+    assert!(report.removed.is_empty());
+    assert_eq!(store.history(), before);
+}
+
+#[test]
+fn maccy_items_map_pins_and_tiffs() {
+    let store = Store::new();
+    let report = store.import(Source::Maccy, "maccy.sqlite");
+    assert_eq!(report.imported, 3);
+    assert_eq!(unsupported(&report), ["ZHISTORYITEM/3"]);
+
+    let history = store.history();
+    assert_eq!(history[0].text, "file:///Users/me/notes.txt");
+    assert_eq!((history[1].text.as_str(), history[1].created_at), ("https://example.com/maccy", 1_700_000_000_500));
+    assert_eq!(history[1].content_type, ContentType::Url);
+    assert!(history[1].is_favorite);
+    assert_eq!(history[2].content_type, ContentType::Image);
+    assert!(history[2].image_data.as_deref().unwrap().ends_with(".png"));
+}
+
+#[test]
+fn gpaste_history_brings_passwords_as_secrets_and_images_from_disk() {
+    let store = Store::new();
+    let report = store.import(Source::GPaste, "gpaste/history.xml");
+    assert_eq!(report.imported, 4);
+    assert_eq!(unsupported(&report), ["items/5", "items/4"]);
+
+    let history = store.history();
+    let texts: Vec<_> = history.iter().map(|h| h.text.as_str()).collect();
+    assert_eq!(texts, ["file:///home/me/notes.txt", "Image", "correct horse", "echo \"a < b\" | grep b"]);
+    assert!(history[2].is_secret);
+    assert_eq!(history[3].created_at, 1_700_000_400_000);
+    let png = store.images.get(history[1].image_data.as_deref().unwrap()).unwrap();
+    assert_eq!(png, fs::read(fixture("gpaste/images/shot.png")).unwrap());
+}
+
+#[test]
+fn clipy_snippets_come_in_as_favorites() {
+    let store = Store::new();
+    let report = store.import(Source::Clipy, "clipy-snippets.xml");
+    assert_eq!(report.imported, 2);
+    assert_eq!(unsupported(&report), ["folders/0/snippets/1"]);
+
+    let history = store.history();
+    assert_eq!(history[0].text, "git status --short");
+    assert_eq!(history[1].text, "<div class=\"card\">&nbsp;</div>");
+    assert!(history.iter().all(|h| h.is_favorite));
+    assert!(history[0].id < history[1].id, "file order lost");
+
+    let dir = tempfile::tempdir().unwrap();
+    let realm = dir.path().join("default.realm");
+    fs::write(&realm, [&[0; 16][..], b"T-DB", &[0; 8]].concat()).unwrap();
+    assert!(matches!(import(&store.db, &store.images, Source::Clipy, &realm), Err(Error::InvalidInput(_))));
+}
+
+#[test]
+fn copyq_tabs_are_read_oldest_first() {
+    let store = Store::new();
+    let report = store.import(Source::CopyQ, "copyq_tab_Y2xpcGJvYXJk.dat");
+    assert_eq!(report.imported, 3);
+    assert_eq!(unsupported(&report), ["items/3"]);
+    assert!(report.unsupported[0].reason.contains("text/html"));
+
+    let history = store.history();
+    assert_eq!(history[0].content_type, ContentType::Image);
+    assert_eq!(history[1].text, "SELECT id FROM users WHERE name = 'a';", "compressed data not inflated");
+    assert_eq!(history[1].content_type, ContentType::Sql);
+    assert_eq!(history[2].text, "copied in CopyQ");
+}
+
+#[test]
+fn importing_twice_adds_nothing_and_bad_files_change_nothing() {
+    let store = Store::new();
+    store.import(Source::Maccy, "maccy.sqlite");
+    let before = store.db.load().unwrap();
+
+    let again = store.import(Source::Maccy, "maccy.sqlite");
+    assert_eq!((again.imported, again.duplicates), (0, 3));
+
+    for (source, name) in [(Source::Ditto, "maccy.sqlite"), (Source::CopyQ, "clipy-snippets.xml"), (Source::GPaste, "ditto.db")] {
+        let result = import(&store.db, &store.images, source, &fixture(name));
+        assert!(result.is_err(), "{name} read as {source:?}");
+    }
+    assert_eq!(store.db.load().unwrap(), before);
+}
@@ -0,0 +1,199 @@
+// src-tauri/src/import/xml.rs
+
+//! Just enough XML for GPaste's history and Clipy's snippet exports:
+//! elements, attributes, text, CDATA and the predefined and numeric
+//! entities. Comments, the prolog and doctype are skipped; namespaces
+//! aren't interpreted.
+
+use crate::error::{Error, Result};
+
+#[derive(Debug, Default)]
+pub(super) struct Element {
+    pub name: String,
+    pub attributes: Vec<(String, String)>,
+    pub children: Vec<Element>,
+    /// Text and CDATA directly inside this element, concatenated.
+    pub text: String,
+}
+
+impl Element {
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str())
+    }
+
+    pub fn child(&self, name: &str) -> Option<&Element> {
+        self.children.iter().find(|child| child.name == name)
+    }
+
+    pub fn children<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> {
+        self.children.iter().filter(move |child| child.name == name)
+    }
+
+    /// Text of the child element `name`, if present.
+    pub fn child_text(&self, name: &str) -> Option<&str> {
+        self.child(name).map(|child| child.text.as_str())
+    }
+}
+
+/// The document's root element.
+pub(super) fn parse(source: &str) -> Result<Element> {
+    let mut parser = Parser { source, pos: 0 };
+    parser.skip_misc()?;
+    let root = parser.element()?;
+    parser.skip_misc()?;
+    if parser.pos < source.len() {
+        return Err(parser.error("content after the root element"));
+    }
+    Ok(root)
+}
+
+struct Parser<'a> {
+    source: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.source[self.pos..]
+    }
+
+    /// Whitespace, comments, processing instructions and the doctype.
+    fn skip_misc(&mut self) -> Result<()> {
+        loop {
+            self.skip_whitespace();
+            if self.rest().starts_with("<?") {
+                self.skip_past("?>")?;
+            } else if self.rest().starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if self.rest().starts_with("<!DOCTYPE") {
+                self.skip_past(">")?;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn skip_past(&mut self, end: &str) -> Result<&'a str> {
+        let Some(at) = self.rest().find(end) else {
+            return Err(self.error(&format!("missing '{end}'")));
+        };
+        let skipped = &self.rest()[..at];
+        self.pos += at + end.len();
+        Ok(skipped)
+    }
+
+    fn element(&mut self) -> Result<Element> {
+        if !self.rest().starts_with('<') {
+            return Err(self.error("expected an element"));
+        }
+        self.pos += 1;
+        let mut element = Element { name: self.name()?, ..Default::default() };
+
+        loop {
+            self.skip_whitespace();
+            if self.rest().starts_with("/>") {
+                self.pos += 2;
+                return Ok(element);
+            }
+            if self.rest().starts_with('>') {
+                self.pos += 1;
+                break;
+            }
+            let key = self.name()?;
+            self.skip_whitespace();
+            if !self.rest().starts_with('=') {
+                return Err(self.error("expected '=' after an attribute name"));
+            }
+            self.pos += 1;
+            self.skip_whitespace();
+            let Some(quote) = self.rest().chars().next().filter(|c| *c == '"' || *c == '\'') else {
+                return Err(self.error("expected a quoted attribute value"));
+            };
+            self.pos += 1;
+            let raw = self.skip_past(if quote == '"' { "\"" } else { "'" })?;
+            let value = self.unescape(raw)?;
+            element.attributes.push((key, value));
+        }
+
+        loop {
+            if self.rest().starts_with("</") {
+                self.pos += 2;
+                let name = self.name()?;
+                if name != element.name {
+                    return Err(self.error(&format!("expected </{}>, found </{name}>", element.name)));
+                }
+                self.skip_whitespace();
+                self.skip_past(">")?;
+                return Ok(element);
+            } else if self.rest().starts_with("<![CDATA[") {
+                self.pos += "<![CDATA[".len();
+                let text = self.skip_past("]]>")?;
+                element.text.push_str(text);
+            } else if self.rest().starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if self.rest().starts_with("<?") {
+                self.skip_past("?>")?;
+            } else if self.rest().starts_with('<') {
+                element.children.push(self.element()?);
+            } else if self.rest().is_empty() {
+                return Err(self.error(&format!("<{}> is never closed", element.name)));
+            } else {
+                let end = self.rest().find('<').unwrap_or(self.rest().len());
+                let raw = &self.rest()[..end];
+                let text = self.unescape(raw)?;
+                element.text.push_str(&text);
+                self.pos += end;
+            }
+        }
+    }
+
+    fn name(&mut self) -> Result<String> {
+        let len = self.rest().find(|c: char| c.is_whitespace() || matches!(c, '>' | '/' | '=')).unwrap_or(0);
+        if len == 0 {
+            return Err(self.error("expected a name"));
+        }
+        let name = self.rest()[..len].to_string();
+        self.pos += len;
+        Ok(name)
+    }
+
+    fn skip_whitespace(&mut self) {
+        self.pos += self.rest().len() - self.rest().trim_start().len();
+    }
+
+    fn unescape(&self, raw: &str) -> Result<String> {
+        let mut out = String::with_capacity(raw.len());
+        let mut rest = raw;
+        while let Some(at) = rest.find('&') {
+            out.push_str(&rest[..at]);
+            let Some(end) = rest[at..].find(';') else {
+                return Err(self.error("unterminated entity"));
+            };
+            let entity = &rest[at + 1..at + end];
+            let decoded = match entity {
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "amp" => Some('&'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                _ => entity
+                    .strip_prefix("#x")
+                    .map(|hex| u32::from_str_radix(hex, 16))
+                    .or_else(|| entity.strip_prefix('#').map(str::parse))
+                    .and_then(|code| code.ok())
+                    .and_then(char::from_u32),
+            };
+            let Some(decoded) = decoded else {
+                return Err(self.error(&format!("unknown entity &{entity};")));
+            };
+            out.push(decoded);
+            rest = &rest[at + end + 1..];
+        }
+        out.push_str(rest);
+        Ok(out)
+    }
+
+    fn error(&self, message: &str) -> Error {
+        Error::InvalidInput(format!("malformed XML at byte {}: {message}", self.pos))
+    }
+}
@@ -1,5 +1,34 @@
 // src-tauri/src/lib.rs
 
+mod backup;
+mod classify;
+mod clipboard;
+mod commands;
+mod dates;
+mod error;
+mod ids;
+mod images;
+mod import;
+mod markdown;
+mod models;
+mod paths;
+mod protocols;
+mod retention;
+mod search;
+mod secrets;
+mod storage;
+mod trash;
+mod vault;
+
+use tauri::{Emitter, Manager};
+
+use clipboard::{Capture, ClipboardChanged, ClipboardWatcher, SystemClipboard};
+use images::{ImageStore, ThumbnailCache, GC_GRACE_PERIOD};
+use paths::AppPaths;
+use retention::RetentionTimer;
+use storage::Database;
+use vault::IdleLock;
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     let mut builder = tauri::Builder::default()
@@ -15,6 +44,133 @@ pub fn run() {
     }
 
     builder
+        .register_asynchronous_uri_scheme_protocol(protocols::THUMB_SCHEME, protocols::thumbnail)
+        .setup(|app| {
+            // 💾 All persistent data lives in AppLocalData, next to `images/`
+            let data_dir = app.path().app_local_data_dir()?;
+            std::fs::create_dir_all(&data_dir)?;
+
+            let paths = AppPaths::new(&data_dir);
+            let db = Database::open(&paths.data_dir.join(storage::DB_FILE_NAME))?;
+            let images = ImageStore::new(db.clone(), paths.images_dir.clone());
+            let thumbs = ThumbnailCache::new(images.clone(), paths.thumbs_dir.clone());
+
+            // 🧹 Adopt the webview's old images, then sweep orphaned blobs left
+            // behind by deleted or trimmed items (encrypted stores start locked
+            // and skip it; the command still works later)
+            let gc_images = images.clone();
+            std::thread::spawn(move || {
+                let swept = gc_images
+                    .adopt_legacy_files()
+                    .and_then(|_| gc_images.collect_garbage(GC_GRACE_PERIOD, false));
+                match swept {
+                    Ok(_) | Err(error::Error::Locked) => {}
+                    Err(err) => eprintln!("[images] startup cleanup failed: {err}"),
+                }
+            });
+
+            // 📋 Capture runs natively, so it keeps working while the window is hidden
+            let handle = app.handle().clone();
+            let watcher = ClipboardWatcher::spawn(
+                SystemClipboard::new,
+                Capture::new(db.clone(), images.clone()),
+                move |item| {
+                    let payload = ClipboardChanged { id: item.id.clone() };
+                    if let Err(err) = handle.emit(clipboard::CHANGED_EVENT, payload) {
+                        eprintln!("[clipboard] failed to emit change event: {err}");
+                    }
+                },
+            );
+
+            // ⏳ Retention: trims old history and expired secrets, never favorites or saved items
+            let handle = app.handle().clone();
+            let retention_timer = RetentionTimer::spawn(db.clone(), move |report| {
+                if let Err(err) = handle.emit(retention::PRUNED_EVENT, report) {
+                    eprintln!("[retention] failed to emit prune event: {err}");
+                }
+            });
+
+            // 🔐 Idle lock: drops the encryption key after a few quiet minutes
+            let handle = app.handle().clone();
+            let idle_lock = IdleLock::spawn(db.clone(), move || {
+                if let Err(err) = handle.emit(vault::LOCKED_EVENT, ()) {
+                    eprintln!("[vault] failed to emit lock event: {err}");
+                }
+            });
+
+            app.manage(paths);
+            app.manage(db);
+            app.manage(images);
+            app.manage(thumbs);
+            app.manage(watcher);
+            app.manage(retention_timer);
+            app.manage(idle_lock);
+            Ok(())
+        })
+        .invoke_handler(tauri::generate_handler![
+            commands::storage::load_app_data,
+            commands::storage::add_project,
+            commands::storage::rename_project,
+            commands::storage::delete_project,
+            commands::storage::add_folder,
+            commands::storage::rename_folder,
+            commands::storage::reorder_folders,
+            commands::storage::delete_folder,
+            commands::storage::add_note,
+            commands::storage::edit_note,
+            commands::storage::reorder_notes,
+            commands::storage::delete_note,
+            commands::storage::push_history_item,
+            commands::storage::restore_history_item,
+            commands::storage::delete_history_item,
+            commands::storage::clear_history,
+            commands::storage::toggle_favorite,
+            commands::storage::add_global_tag,
+            commands::storage::delete_global_tag,
+            commands::storage::legacy_migration_status,
+            commands::storage::migrate_legacy_data,
+            commands::classify::classify_text,
+            commands::clipboard::copy_history_item,
+            commands::images::put_image,
+            commands::images::get_image,
+            commands::images::release_image,
+            commands::images::collect_image_garbage,
+            commands::search::search,
+            commands::search::fuzzy_search,
+            commands::search::parse_query,
+            commands::search::filter_items,
+            commands::search::regex_search,
+            commands::search::extract_regex_matches,
+            commands::retention::get_retention_policy,
+            commands::retention::set_retention_policy,
+            commands::retention::prune_history,
+            commands::trash::list_trash,
+            commands::trash::restore_from_trash,
+            commands::trash::purge_trash,
+            commands::secrets::get_secret_policy,
+            commands::secrets::set_secret_policy,
+            commands::vault::encryption_status,
+            commands::vault::enable_encryption,
+            commands::vault::disable_encryption,
+            commands::vault::unlock_store,
+            commands::vault::lock_store,
+            commands::vault::change_passphrase,
+            commands::vault::report_activity,
+            commands::vault::get_auto_lock_minutes,
+            commands::vault::set_auto_lock_minutes,
+            commands::backup::export_backup,
+            commands::backup::check_backup,
+            commands::backup::restore_backup,
+            commands::backup::export_bundle,
+            commands::backup::restore_bundle,
+            commands::backup::merge_backup,
+            commands::backup::preview_merge_bundle,
+            commands::backup::merge_bundle,
+            commands::markdown::export_project_markdown,
+            commands::import::import_history,
+            commands::dates::history_in_range,
+            commands::dates::history_by_period,
+        ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
-}
\ No newline at end of file
+}
@@ -0,0 +1,210 @@
+// src-tauri/src/markdown/mod.rs
+
+//! Exports a project as plain Markdown files anyone can read.
+//!
+//! ```text
+//! <dir>/<project>/
+//!     <folder>.md        one per folder
+//!     images/<name>      images the notes show, decrypted
+//! ```
+//!
+//! Each file starts with YAML front-matter (project, folder, and every tag
+//! used in it). Each note becomes a `##` section, with its own tags on the
+//! first line. Code is fenced with its detected language, and images are
+//! linked relatively so the tree can be moved around as a whole.
+
+#[cfg(test)]
+mod tests;
+
+use std::collections::HashSet;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::error::{Error, Result};
+use crate::images::ImageStore;
+use crate::models::{ContentType, Folder, NoteItem, Project};
+use crate::storage::Database;
+
+const IMAGES_DIR: &str = "images";
+/// Longest section heading taken from a note's first line.
+const TITLE_MAX_CHARS: usize = 60;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarkdownExport {
+    /// The project's directory.
+    pub dir: PathBuf,
+    pub files: usize,
+    pub notes: usize,
+    pub images: usize,
+    /// Images notes refer to that aren't in the store anymore.
+    pub missing_images: Vec<String>,
+}
+
+/// Writes project `project_id` into a new directory under `dir`. Notes
+/// containing credentials are left out unless `include_secrets` is set.
+pub fn export_project(
+    db: &Database,
+    images: &ImageStore,
+    project_id: &str,
+    dir: &Path,
+    include_secrets: bool,
+) -> Result<MarkdownExport> {
+    let project = db
+        .load()?
+        .projects
+        .into_iter()
+        .find(|p| p.id == project_id)
+        .ok_or_else(|| Error::not_found("project", project_id))?;
+
+    let project_dir = unique_path(dir, &file_stem(&project.name), "");
+    fs::create_dir_all(&project_dir)?;
+    let mut report =
+        MarkdownExport { dir: project_dir.clone(), files: 0, notes: 0, images: 0, missing_images: Vec::new() };
+    let mut copied = HashSet::new();
+
+    for folder in &project.folders {
+        let notes: Vec<_> = folder.notes.iter().filter(|n| include_secrets || !n.is_secret).collect();
+        for note in &notes {
+            if let Some(file_name) = linked_image(note) {
+                if copied.insert(file_name.to_string()) {
+                    copy_image(images, file_name, &project_dir, &mut report)?;
+                }
+            }
+        }
+
+        let path = unique_path(&project_dir, &file_stem(&folder.name), ".md");
+        fs::write(path, render_folder(&project, folder, &notes))?;
+        report.files += 1;
+        report.notes += notes.len();
+    }
+    Ok(report)
+}
+
+fn copy_image(images: &ImageStore, file_name: &str, project_dir: &Path, report: &mut MarkdownExport) -> Result<()> {
+    let bytes = match images.get(file_name) {
+        Ok(bytes) => bytes,
+        Err(Error::Io(err)) if err.kind() == std::io::ErrorKind::NotFound => {
+            report.missing_images.push(file_name.to_string());
+            return Ok(());
+        }
+        Err(err) => return Err(err),
+    };
+    let images_dir = project_dir.join(IMAGES_DIR);
+    fs::create_dir_all(&images_dir)?;
+    fs::write(images_dir.join(file_name), bytes)?;
+    report.images += 1;
+    Ok(())
+}
+
+fn render_folder(project: &Project, folder: &Folder, notes: &[&NoteItem]) -> String {
+    let mut tags: Vec<&str> = Vec::new();
+    for tag in notes.iter().flat_map(|n| &n.tags) {
+        if !tags.contains(&tag.as_str()) {
+            tags.push(tag);
+        }
+    }
+
+    let mut out = String::new();
+    out.push_str("---\n");
+    let _ = writeln!(out, "project: {}", yaml_string(&project.name));
+    let _ = writeln!(out, "folder: {}", yaml_string(&folder.name));
+    let tags: Vec<_> = tags.iter().map(|t| yaml_string(t)).collect();
+    let _ = writeln!(out, "tags: [{}]", tags.join(", "));
+    out.push_str("---\n\n");
+    let _ = writeln!(out, "# {}", one_line(&folder.name));
+
+    for note in notes {
+        let _ = write!(out, "\n## {}\n\n", title(note));
+        if !note.tags.is_empty() {
+            let tags: Vec<_> = note.tags.iter().map(|t| format!("`#{t}`")).collect();
+            let _ = write!(out, "{}\n\n", tags.join(" "));
+        }
+        out.push_str(&body(note));
+        out.push('\n');
+    }
+    out
+}
+
+fn body(note: &NoteItem) -> String {
+    if let Some(file_name) = linked_image(note) {
+        return format!("![{}]({IMAGES_DIR}/{file_name})\n", title(note));
+    }
+    match fence_language(note) {
+        Some(language) => {
+            // Longer than any run of backticks inside, so the text can't close it.
+            let longest_run = note.text.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+            let fence = "`".repeat(longest_run.max(2) + 1);
+            format!("{fence}{language}\n{}\n{fence}\n", note.text.trim_end_matches('\n'))
+        }
+        None if note.content_type == ContentType::Url => format!("<{}>\n", note.text.trim()),
+        None => format!("{}\n", note.text.trim_end()),
+    }
+}
+
+/// `Some("")` for code of unknown language; `None` for prose.
+fn fence_language(note: &NoteItem) -> Option<&'static str> {
+    if let Some(language) = note.language {
+        return Some(language.as_str());
+    }
+    match note.content_type {
+        ContentType::Json => Some("json"),
+        ContentType::Yaml => Some("yaml"),
+        ContentType::Sql => Some("sql"),
+        ContentType::Shell => Some("bash"),
+        ContentType::Code | ContentType::StackTrace => Some(""),
+        _ => None,
+    }
+}
+
+/// The image file a note shows; legacy inline `data:` URLs aren't copied.
+fn linked_image(note: &NoteItem) -> Option<&str> {
+    note.image_data.as_deref().filter(|name| note.content_type == ContentType::Image && !name.starts_with("data:"))
+}
+
+fn title(note: &NoteItem) -> String {
+    if note.content_type == ContentType::Image {
+        return "Image".into();
+    }
+    let first_line = note.text.lines().map(str::trim).find(|line| !line.is_empty()).unwrap_or("Untitled");
+    let mut title: String = one_line(first_line).chars().take(TITLE_MAX_CHARS).collect();
+    if first_line.chars().count() > TITLE_MAX_CHARS {
+        title.push('…');
+    }
+    title
+}
+
+fn one_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Double-quoted, so names like `yes` or `1: 2` stay strings.
+fn yaml_string(value: &str) -> String {
+    serde_json::to_string(value).expect("strings always serialize")
+}
+
+/// `name` made safe as a file name on every platform.
+fn file_stem(name: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .map(|c| if c.is_control() || r#"<>:"/\|?*"#.contains(c) { '_' } else { c })
+        .collect();
+    let cleaned = cleaned.trim().trim_matches('.').trim();
+    if cleaned.is_empty() {
+        "Untitled".into()
+    } else {
+        cleaned.into()
+    }
+}
+
+/// `dir/stem.ext`, or `dir/stem (2).ext` and so on if that's taken.
+fn unique_path(dir: &Path, stem: &str, extension: &str) -> PathBuf {
+    let candidate = dir.join(format!("{stem}{extension}"));
+    if !candidate.exists() {
+        return candidate;
+    }
+    (2..).map(|n| dir.join(format!("{stem} ({n}){extension}"))).find(|path| !path.exists()).expect("names run out")
+}
@@ -0,0 +1,77 @@
+// src-tauri/src/markdown/tests.rs
+
+use std::fs;
+use std::io::Cursor;
+
+use image::{ImageFormat, RgbaImage};
+
+use super::*;
+use crate::models::Language;
+
+fn note(id: &str, text: &str, content_type: ContentType) -> NoteItem {
+    NoteItem {
+        id: id.into(),
+        text: text.into(),
+        created_at: 1_700_000_000_000,
+        updated_at: 1_700_000_000_000,
+        utc_offset: 0,
+        content_type,
+        tags: Vec::new(),
+        image_data: None,
+        language: None,
+        is_favorite: false,
+        is_secret: false,
+    }
+}
+
+fn png() -> Vec<u8> {
+    let mut out = Cursor::new(Vec::new());
+    RgbaImage::from_pixel(2, 2, image::Rgba([0, 0, 255, 255])).write_to(&mut out, ImageFormat::Png).unwrap();
+    out.into_inner()
+}
+
+#[test]
+fn projects_become_a_tree_of_markdown_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let db = Database::open_in_memory().unwrap();
+    let images = ImageStore::new(db.clone(), dir.path().join("store"));
+    let stored = images.put(&png()).unwrap();
+
+    db.add_project("docs", "Work: 2025").unwrap();
+    db.add_folder("docs", "snip", "Snippets").unwrap();
+    db.add_folder("docs", "old", "snippets/old").unwrap();
+    db.add_note("snip", &NoteItem {
+        tags: vec!["rust".into(), "cli".into()],
+        language: Some(Language::Rust),
+        ..note("n1", "fn main() {\n    println!(\"```\");\n}\n", ContentType::Code)
+    })
+    .unwrap();
+    db.add_note("snip", &NoteItem {
+        image_data: Some(stored.file_name.clone()),
+        ..note("n2", "Image", ContentType::Image)
+    })
+    .unwrap();
+    db.add_note("snip", &NoteItem { is_secret: true, ..note("n3", "password=hunter2", ContentType::Text) }).unwrap();
+    db.add_note("old", &NoteItem { image_data: Some("gone.png".into()), ..note("n4", "Image", ContentType::Image) })
+        .unwrap();
+
+    let out = dir.path().join("out");
+    let report = export_project(&db, &images, "docs", &out, false).unwrap();
+    assert_eq!(report.dir, out.join("Work_ 2025"));
+    assert_eq!((report.files, report.notes, report.images), (2, 3, 1));
+    assert_eq!(report.missing_images, ["gone.png"]);
+
+    let snippets = fs::read_to_string(report.dir.join("Snippets.md")).unwrap();
+    assert!(snippets.starts_with("---\nproject: \"Work: 2025\"\nfolder: \"Snippets\"\ntags: [\"rust\", \"cli\"]\n---\n"));
+    assert!(snippets.contains("## fn main() {\n\n`#rust` `#cli`\n\n````rust\nfn main() {"));
+    assert!(snippets.contains(&format!("![Image](images/{})", stored.file_name)));
+    assert!(!snippets.contains("hunter2"));
+    assert_eq!(fs::read(report.dir.join("images").join(&stored.file_name)).unwrap(), png());
+    assert!(report.dir.join("snippets_old.md").exists());
+
+    // A second export doesn't overwrite the first.
+    let again = export_project(&db, &images, "docs", &out, false).unwrap();
+    assert_eq!(again.dir, out.join("Work_ 2025 (2)"));
+
+    assert!(matches!(export_project(&db, &images, "nope", &out, false), Err(Error::NotFound { .. })));
+}
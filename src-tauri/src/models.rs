@@ -0,0 +1,218 @@
+// src-tauri/src/models.rs
+
+//! Serde mirrors of the types in `src/types.ts`.
+//! Field names are camelCase on the wire so the webview can use them as-is.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ContentType {
+    Url,
+    Color,
+    Code,
+    Text,
+    Image,
+    Email,
+    Phone,
+    Path,
+    Json,
+    Yaml,
+    Sql,
+    Shell,
+    StackTrace,
+    Uuid,
+    Ip,
+    Date,
+    Number,
+}
+
+impl ContentType {
+    pub const ALL: [ContentType; 17] = [
+        ContentType::Url,
+        ContentType::Color,
+        ContentType::Code,
+        ContentType::Text,
+        ContentType::Image,
+        ContentType::Email,
+        ContentType::Phone,
+        ContentType::Path,
+        ContentType::Json,
+        ContentType::Yaml,
+        ContentType::Sql,
+        ContentType::Shell,
+        ContentType::StackTrace,
+        ContentType::Uuid,
+        ContentType::Ip,
+        ContentType::Date,
+        ContentType::Number,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ContentType::Url => "url",
+            ContentType::Color => "color",
+            ContentType::Code => "code",
+            ContentType::Text => "text",
+            ContentType::Image => "image",
+            ContentType::Email => "email",
+            ContentType::Phone => "phone",
+            ContentType::Path => "path",
+            ContentType::Json => "json",
+            ContentType::Yaml => "yaml",
+            ContentType::Sql => "sql",
+            ContentType::Shell => "shell",
+            ContentType::StackTrace => "stackTrace",
+            ContentType::Uuid => "uuid",
+            ContentType::Ip => "ip",
+            ContentType::Date => "date",
+            ContentType::Number => "number",
+        }
+    }
+
+    /// Unknown values fall back to `Text` rather than failing the whole row.
+    pub fn parse(value: &str) -> Self {
+        Self::ALL.into_iter().find(|t| t.as_str() == value).unwrap_or(ContentType::Text)
+    }
+}
+
+/// Programming language of a code-like clip. The wire names are the ids
+/// syntax highlighters use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    Rust,
+    TypeScript,
+    Python,
+    Go,
+    Sql,
+    Bash,
+    Html,
+    Css,
+    Json,
+    Yaml,
+    Php,
+    C,
+    Cpp,
+}
+
+impl Language {
+    pub const ALL: [Language; 13] = [
+        Language::Rust,
+        Language::TypeScript,
+        Language::Python,
+        Language::Go,
+        Language::Sql,
+        Language::Bash,
+        Language::Html,
+        Language::Css,
+        Language::Json,
+        Language::Yaml,
+        Language::Php,
+        Language::C,
+        Language::Cpp,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Language::Rust => "rust",
+            Language::TypeScript => "typescript",
+            Language::Python => "python",
+            Language::Go => "go",
+            Language::Sql => "sql",
+            Language::Bash => "bash",
+            Language::Html => "html",
+            Language::Css => "css",
+            Language::Json => "json",
+            Language::Yaml => "yaml",
+            Language::Php => "php",
+            Language::C => "c",
+            Language::Cpp => "cpp",
+        }
+    }
+
+    /// Unknown values are dropped rather than failing the whole row.
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|l| l.as_str() == value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteItem {
+    pub id: String,
+    pub text: String,
+    /// Unix milliseconds (UTC) when it was created.
+    pub created_at: i64,
+    /// Unix milliseconds (UTC) of the last edit; `created_at` if never edited.
+    pub updated_at: i64,
+    /// Minutes east of UTC on the machine that created it.
+    pub utc_offset: i32,
+    pub content_type: ContentType,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image_data: Option<String>,
+    /// Detected programming language, for code-like content only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<Language>,
+    #[serde(default)]
+    pub is_favorite: bool,
+    /// Contains a credential; kept out of search and exports.
+    #[serde(default)]
+    pub is_secret: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Folder {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub notes: Vec<NoteItem>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Project {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub folders: Vec<Folder>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryItem {
+    pub id: String,
+    pub text: String,
+    /// Unix milliseconds (UTC) when it was copied.
+    pub created_at: i64,
+    /// Unix milliseconds (UTC) of the last edit; `created_at` if never edited.
+    pub updated_at: i64,
+    /// Minutes east of UTC on the machine that copied it.
+    pub utc_offset: i32,
+    pub content_type: ContentType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image_data: Option<String>,
+    /// Detected programming language, for code-like content only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<Language>,
+    #[serde(default)]
+    pub is_favorite: bool,
+    /// Contains a credential; kept out of search and exports.
+    #[serde(default)]
+    pub is_secret: bool,
+    /// Unix milliseconds after which the item is deleted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<i64>,
+}
+
+/// Everything the webview needs to render on startup.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppData {
+    pub projects: Vec<Project>,
+    pub history: Vec<HistoryItem>,
+    pub global_tags: Vec<String>,
+}
@@ -0,0 +1,27 @@
+// src-tauri/src/paths.rs
+
+use std::path::{Path, PathBuf};
+
+/// Same folder the webview uses for `saveImageToDisk`.
+pub const IMAGES_DIR: &str = "images";
+
+/// Disposable cache of image previews; safe to delete at any time.
+pub const THUMBS_DIR: &str = "thumbs";
+
+/// Resolved on-disk locations, managed as Tauri state.
+#[derive(Debug, Clone)]
+pub struct AppPaths {
+    pub data_dir: PathBuf,
+    pub images_dir: PathBuf,
+    pub thumbs_dir: PathBuf,
+}
+
+impl AppPaths {
+    pub fn new(data_dir: &Path) -> Self {
+        Self {
+            data_dir: data_dir.to_path_buf(),
+            images_dir: data_dir.join(IMAGES_DIR),
+            thumbs_dir: data_dir.join(THUMBS_DIR),
+        }
+    }
+}
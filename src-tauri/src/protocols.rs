@@ -0,0 +1,51 @@
+// src-tauri/src/protocols.rs
+
+//! Custom URI schemes served straight from the backend.
+
+use tauri::http::{header, Request, Response, StatusCode};
+use tauri::{Manager, Runtime, UriSchemeContext, UriSchemeResponder};
+
+use crate::error::Error;
+use crate::images::ThumbnailCache;
+
+/// `thumb://localhost/<imageData>` (`http://thumb.localhost/<imageData>` on
+/// Windows/Android) returns a small PNG preview of a stored image.
+pub const THUMB_SCHEME: &str = "thumb";
+
+pub fn thumbnail<R: Runtime>(
+    ctx: UriSchemeContext<'_, R>,
+    request: Request<Vec<u8>>,
+    responder: UriSchemeResponder,
+) {
+    let Some(cache) = ctx.app_handle().try_state::<ThumbnailCache>() else {
+        responder.respond(status(StatusCode::SERVICE_UNAVAILABLE));
+        return;
+    };
+    let cache = cache.inner().clone();
+    let file_name = request.uri().path().trim_start_matches('/').to_string();
+
+    // Decoding large screenshots takes a while; keep it off the webview thread.
+    std::thread::spawn(move || {
+        let response = match cache.get(&file_name) {
+            Ok(bytes) => Response::builder()
+                .header(header::CONTENT_TYPE, "image/png")
+                .header(header::CACHE_CONTROL, "max-age=31536000, immutable")
+                .body(bytes)
+                .unwrap_or_else(|_| status(StatusCode::INTERNAL_SERVER_ERROR)),
+            Err(Error::InvalidInput(_)) => status(StatusCode::BAD_REQUEST),
+            Err(Error::Locked) => status(StatusCode::LOCKED),
+            Err(Error::Io(err)) if err.kind() == std::io::ErrorKind::NotFound => status(StatusCode::NOT_FOUND),
+            Err(err) => {
+                eprintln!("[thumbs] failed to render {file_name}: {err}");
+                status(StatusCode::INTERNAL_SERVER_ERROR)
+            }
+        };
+        responder.respond(response);
+    });
+}
+
+fn status(code: StatusCode) -> Response<Vec<u8>> {
+    let mut response = Response::new(Vec::new());
+    *response.status_mut() = code;
+    response
+}
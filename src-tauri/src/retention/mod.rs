@@ -0,0 +1,143 @@
+// src-tauri/src/retention/mod.rs
+
+//! Decides how long history and the trash are kept.
+//!
+//! A `RetentionPolicy` is a handful of optional limits, each of which can be
+//! switched off. `prune` applies them in one transaction; the timer runs it
+//! every few seconds. Favorites and history items that were also saved into
+//! a project are never deleted, whatever the policy says. Imported and
+//! merged items only count towards `max_items`; the age limits would
+//! otherwise delete them as soon as they arrive.
+
+mod timer;
+
+#[cfg(test)]
+mod tests;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::storage::{self, Database, HISTORY_ORDER, PRUNABLE_HISTORY};
+use crate::trash;
+
+pub use timer::RetentionTimer;
+
+/// Emitted after `prune` deleted something.
+pub const PRUNED_EVENT: &str = "clipboard://pruned";
+
+const POLICY_KEY: &str = "retention_policy";
+const DAY_MILLIS: i64 = 24 * 60 * 60 * 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RetentionPolicy {
+    /// Most history items to keep. Favorites and saved items count towards
+    /// the limit but are never the ones removed.
+    pub max_items: Option<usize>,
+    /// Drop items older than this many days.
+    pub max_age_days: Option<u32>,
+    /// Drop images sooner than other items; they are the bulk of the disk use.
+    pub image_max_age_days: Option<u32>,
+    /// How long a secret captured under `SecretPolicy::Expire` lives.
+    pub secret_ttl_secs: Option<u64>,
+    /// Purge deleted projects, folders and notes after this many days.
+    pub trash_max_age_days: Option<u32>,
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self {
+            max_items: Some(10_000),
+            max_age_days: Some(30),
+            image_max_age_days: Some(7),
+            secret_ttl_secs: Some(2 * 60),
+            trash_max_age_days: Some(30),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PruneReason {
+    SecretExpired,
+    ImageTooOld,
+    TooOld,
+    OverLimit,
+    /// A trash entry, not a history item.
+    TrashExpired,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrunedItem {
+    pub id: String,
+    pub reason: PruneReason,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PruneReport {
+    pub removed: Vec<PrunedItem>,
+}
+
+pub fn policy(db: &Database) -> Result<RetentionPolicy> {
+    match db.meta(POLICY_KEY)? {
+        Some(json) => Ok(serde_json::from_str(&json)?),
+        None => Ok(RetentionPolicy::default()),
+    }
+}
+
+pub fn set_policy(db: &Database, policy: &RetentionPolicy) -> Result<()> {
+    if policy.max_items == Some(0) {
+        return Err(Error::InvalidInput("max items must be at least 1".into()));
+    }
+    if [policy.max_age_days, policy.image_max_age_days, policy.trash_max_age_days].contains(&Some(0)) {
+        return Err(Error::InvalidInput("max age must be at least 1 day".into()));
+    }
+    let json = serde_json::to_string(policy)?;
+    db.write(|tx| storage::set_meta(tx, POLICY_KEY, &json))
+}
+
+/// Deletes whatever `policy` no longer allows as of `now_millis`. Each item
+/// is reported once, under the first rule that removed it.
+pub fn prune(db: &Database, policy: &RetentionPolicy, now_millis: i64) -> Result<PruneReport> {
+    db.write(|tx| {
+        let mut removed = Vec::new();
+        let mut delete = |condition: &str, value: i64, reason: PruneReason| -> Result<()> {
+            let sql = format!("DELETE FROM history WHERE {PRUNABLE_HISTORY} AND ({condition}) RETURNING id");
+            let mut stmt = tx.prepare(&sql)?;
+            let ids = stmt.query_map([value], |row| row.get::<_, String>(0))?;
+            for id in ids {
+                removed.push(PrunedItem { id: id?, reason });
+            }
+            Ok(())
+        };
+
+        // Secrets carry the deadline they were captured with, so changing
+        // the TTL later doesn't affect the ones already stored.
+        delete("is_secret AND expires_at <= ?1", now_millis, PruneReason::SecretExpired)?;
+        // Imported items are as old as their copy time the moment they
+        // arrive; only the item limit applies to them.
+        if let Some(days) = policy.image_max_age_days {
+            let cutoff = now_millis - i64::from(days) * DAY_MILLIS;
+            let condition = "content_type = 'image' AND created_at < ?1 AND imported_at IS NULL";
+            delete(condition, cutoff, PruneReason::ImageTooOld)?;
+        }
+        if let Some(days) = policy.max_age_days {
+            let cutoff = now_millis - i64::from(days) * DAY_MILLIS;
+            delete("created_at < ?1 AND imported_at IS NULL", cutoff, PruneReason::TooOld)?;
+        }
+        if let Some(max_items) = policy.max_items {
+            let newest = format!("seq NOT IN (SELECT seq FROM history ORDER BY {HISTORY_ORDER} LIMIT ?1)");
+            delete(&newest, max_items as i64, PruneReason::OverLimit)?;
+        }
+        if let Some(days) = policy.trash_max_age_days {
+            let cutoff = now_millis - i64::from(days) * DAY_MILLIS;
+            for id in trash::purge_before(tx, cutoff)? {
+                removed.push(PrunedItem { id, reason: PruneReason::TrashExpired });
+            }
+        }
+
+        Ok(PruneReport { removed })
+    })
+}
@@ -0,0 +1,156 @@
+// src-tauri/src/retention/tests.rs
+
+//! Policies against an in-memory database.
+
+use super::*;
+use crate::models::{ContentType, HistoryItem, NoteItem};
+
+const NOW: i64 = 1_790_000_000_000;
+const HOUR_MILLIS: i64 = 60 * 60 * 1000;
+
+/// Ids are creation times too, so items of the same age need distinct hours.
+fn item(age_hours: i64, text: &str, content_type: ContentType) -> HistoryItem {
+    let created_at = NOW - age_hours * HOUR_MILLIS;
+    HistoryItem {
+        id: created_at.to_string(),
+        text: text.into(),
+        created_at,
+        updated_at: created_at,
+        utc_offset: 0,
+        content_type,
+        image_data: (content_type == ContentType::Image).then(|| format!("{text}.png")),
+        language: None,
+        is_favorite: false,
+        is_secret: false,
+        expires_at: None,
+    }
+}
+
+fn note(id: &str, text: &str) -> NoteItem {
+    NoteItem {
+        id: id.into(),
+        text: text.into(),
+        created_at: 1_700_000_000_000,
+        updated_at: 1_700_000_000_000,
+        utc_offset: 0,
+        content_type: ContentType::Text,
+        tags: Vec::new(),
+        image_data: None,
+        language: None,
+        is_favorite: false,
+        is_secret: false,
+    }
+}
+
+/// Pushes `items` oldest first, so the last one ends up newest.
+fn db_with(items: &[HistoryItem]) -> Database {
+    let db = Database::open_in_memory().unwrap();
+    for item in items {
+        assert!(db.push_history_item(item, None).unwrap());
+    }
+    db
+}
+
+fn remaining(db: &Database) -> Vec<String> {
+    db.load().unwrap().history.into_iter().map(|h| h.text).collect()
+}
+
+const NO_LIMITS: RetentionPolicy = RetentionPolicy {
+    max_items: None,
+    max_age_days: None,
+    image_max_age_days: None,
+    secret_ttl_secs: None,
+    trash_max_age_days: None,
+};
+
+#[test]
+fn old_items_are_dropped_but_favorites_and_saved_items_stay() {
+    let favorite = item(40 * 24 + 2, "old favorite", ContentType::Text);
+    let db = db_with(&[
+        item(40 * 24 + 3, "old", ContentType::Text),
+        favorite.clone(),
+        item(40 * 24 + 1, "old saved", ContentType::Text),
+        item(24, "recent", ContentType::Text),
+    ]);
+    db.toggle_favorite(&favorite.id).unwrap();
+    db.add_note("f1", &note("n1", "old saved")).unwrap();
+
+    let report = prune(&db, &RetentionPolicy { max_age_days: Some(30), ..NO_LIMITS }, NOW).unwrap();
+
+    assert_eq!(report.removed.iter().map(|r| r.reason).collect::<Vec<_>>(), [PruneReason::TooOld]);
+    assert_eq!(remaining(&db), ["recent", "old saved", "old favorite"]);
+}
+
+#[test]
+fn images_expire_sooner() {
+    let db = db_with(&[item(10 * 24 + 1, "screenshot", ContentType::Image), item(10 * 24, "text", ContentType::Text)]);
+    let policy = RetentionPolicy { max_age_days: Some(30), image_max_age_days: Some(7), ..Default::default() };
+
+    let report = prune(&db, &policy, NOW).unwrap();
+
+    assert_eq!(report.removed[0].reason, PruneReason::ImageTooOld);
+    assert_eq!(remaining(&db), ["text"]);
+}
+
+#[test]
+fn the_default_policy_keeps_a_month_of_text_and_a_week_of_images() {
+    let db = db_with(&[
+        item(31 * 24, "last month", ContentType::Text),
+        item(8 * 24, "last week's screenshot", ContentType::Image),
+        item(10 * 24, "ten days ago", ContentType::Text),
+        item(6 * 24, "this week's screenshot", ContentType::Image),
+    ]);
+
+    let report = prune(&db, &RetentionPolicy::default(), NOW).unwrap();
+
+    let reasons: Vec<_> = report.removed.iter().map(|r| r.reason).collect();
+    assert_eq!(reasons, [PruneReason::ImageTooOld, PruneReason::TooOld]);
+    assert_eq!(remaining(&db), ["this week's screenshot", "ten days ago"]);
+}
+
+#[test]
+fn the_oldest_items_go_first_when_over_the_limit() {
+    let items: Vec<_> = (0..5).map(|i| item(5 - i, &format!("item {i}"), ContentType::Text)).collect();
+    let db = db_with(&items);
+
+    let report = prune(&db, &RetentionPolicy { max_items: Some(3), ..NO_LIMITS }, NOW).unwrap();
+
+    assert!(report.removed.iter().all(|r| r.reason == PruneReason::OverLimit));
+    assert_eq!(remaining(&db), ["item 4", "item 3", "item 2"]);
+}
+
+#[test]
+fn age_comes_from_the_creation_time_not_the_id() {
+    let mut imported = item(3 * 24, "imported", ContentType::Text);
+    imported.id = "legacy-1".into();
+    let db = db_with(&[imported, item(1, "recent", ContentType::Text)]);
+
+    prune(&db, &RetentionPolicy { max_age_days: Some(2), ..Default::default() }, NOW).unwrap();
+    assert_eq!(remaining(&db), ["recent"]);
+}
+
+#[test]
+fn old_trash_is_purged() {
+    let db = Database::open_in_memory().unwrap();
+    db.add_note("f1", &note("n1", "deleted")).unwrap();
+    trash::delete(&db, trash::TrashKind::Note, "n1").unwrap();
+    let policy = RetentionPolicy { trash_max_age_days: Some(30), ..NO_LIMITS };
+    let now = chrono::Utc::now().timestamp_millis();
+
+    assert!(prune(&db, &policy, now + 29 * DAY_MILLIS).unwrap().removed.is_empty());
+    let report = prune(&db, &policy, now + 31 * DAY_MILLIS).unwrap();
+    assert_eq!(report.removed, [PrunedItem { id: "n1".into(), reason: PruneReason::TrashExpired }]);
+    assert!(trash::list(&db).unwrap().is_empty());
+}
+
+#[test]
+fn policy_round_trips_and_rejects_zero_limits() {
+    let db = Database::open_in_memory().unwrap();
+    assert_eq!(policy(&db).unwrap(), RetentionPolicy::default());
+
+    let custom = RetentionPolicy { max_items: Some(500), max_age_days: Some(90), ..Default::default() };
+    set_policy(&db, &custom).unwrap();
+    assert_eq!(policy(&db).unwrap(), custom);
+
+    assert!(set_policy(&db, &RetentionPolicy { max_items: Some(0), ..Default::default() }).is_err());
+}
@@ -0,0 +1,65 @@
+// src-tauri/src/retention/timer.rs
+
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use chrono::Utc;
+
+use super::{policy, prune, PruneReport};
+use crate::error::{Error, Result};
+use crate::storage::Database;
+
+/// Short enough that a two-minute secret doesn't noticeably outlive its TTL.
+const PRUNE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Background thread that applies the stored retention policy. Prunes once
+/// right away, so anything that expired while the app was closed is gone
+/// before the webview loads. Stops when dropped.
+pub struct RetentionTimer {
+    stop: Option<Sender<()>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl RetentionTimer {
+    /// `on_pruned` is called only when something was actually deleted.
+    pub fn spawn<F>(db: Database, on_pruned: F) -> Self
+    where
+        F: Fn(PruneReport) + Send + 'static,
+    {
+        let (stop, stopped) = mpsc::channel::<()>();
+
+        let handle = thread::Builder::new()
+            .name("retention".into())
+            .spawn(move || loop {
+                match run_once(&db) {
+                    Ok(report) if !report.removed.is_empty() => on_pruned(report),
+                    Ok(_) => {}
+                    // Nothing can be deleted until the store is unlocked again.
+                    Err(Error::Locked) => {}
+                    Err(err) => eprintln!("[retention] prune failed: {err}"),
+                }
+                // Dropping the sender wakes us up immediately.
+                if stopped.recv_timeout(PRUNE_INTERVAL) != Err(RecvTimeoutError::Timeout) {
+                    break;
+                }
+            })
+            .expect("failed to spawn retention thread");
+
+        Self { stop: Some(stop), handle: Some(handle) }
+    }
+}
+
+impl Drop for RetentionTimer {
+    fn drop(&mut self) {
+        drop(self.stop.take());
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+fn run_once(db: &Database) -> Result<PruneReport> {
+    // Re-read every time so policy changes apply without a restart.
+    prune(db, &policy(db)?, Utc::now().timestamp_millis())
+}
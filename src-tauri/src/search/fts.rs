@@ -0,0 +1,170 @@
+// src-tauri/src/search/fts.rs
+
+//! Ranked full-text search on the `search_index` FTS5 table.
+//!
+//! The index is kept in sync by triggers (see `storage/schema.rs`), so this
+//! module only has to turn user input into an FTS5 query and decode results.
+
+use rusqlite::Connection;
+use rust_stemmers::{Algorithm, Stemmer};
+
+use super::{Highlight, HitKind, SearchHit, SearchScope};
+use crate::error::Result;
+use crate::storage::Database;
+
+pub const DEFAULT_LIMIT: usize = 50;
+const MAX_LIMIT: usize = 500;
+
+// Control characters never appear in the tokenized text, so they are safe
+// markers for `highlight()`.
+const MARK_START: char = '\u{2}';
+const MARK_END: char = '\u{3}';
+
+/// Ranked hits for `query`, best first. Every word must match; words are
+/// matched by prefix after stemming, so "заметки" also finds "заметка" and
+/// "running" finds "run".
+pub fn search(db: &Database, query: &str, scope: SearchScope, limit: usize) -> Result<Vec<SearchHit>> {
+    let Some(fts_query) = build_query(query) else {
+        return Ok(Vec::new());
+    };
+    let limit = limit.clamp(1, MAX_LIMIT);
+
+    // Kinds are static strings, not user input.
+    let kinds: Vec<_> = scope.kinds().iter().map(|kind| format!("'{}'", kind.as_str())).collect();
+    let sql = format!(
+        "SELECT kind, ref_id,
+                CASE kind
+                    WHEN 'history' THEN (SELECT unseal(text) FROM history WHERE id = ref_id)
+                    WHEN 'note' THEN (SELECT unseal(text) FROM notes WHERE id = ref_id)
+                    WHEN 'folder' THEN (SELECT name FROM folders WHERE id = ref_id)
+                    ELSE ref_id
+                END,
+                highlight(search_index, 2, char(2), char(3)),
+                highlight(search_index, 3, char(2), char(3)),
+                bm25(search_index, 0.0, 0.0, 1.0, 0.5) AS rank,
+                CASE kind
+                    WHEN 'note' THEN (SELECT folder_id FROM notes WHERE id = ref_id)
+                    WHEN 'folder' THEN (SELECT project_id FROM folders WHERE id = ref_id)
+                END
+         FROM search_index
+         WHERE search_index MATCH ?1 AND kind IN ({})
+         ORDER BY rank
+         LIMIT ?2",
+        kinds.join(", ")
+    );
+
+    db.read(|conn| {
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(rusqlite::params![fts_query, limit as i64], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, String>(4)?,
+                row.get::<_, f64>(5)?,
+                row.get::<_, Option<String>>(6)?,
+            ))
+        })?;
+
+        let mut hits = Vec::new();
+        for row in rows.collect::<rusqlite::Result<Vec<_>>>()? {
+            let (kind, id, text, marked_body, marked_tags, rank, parent_id) = row;
+            let Some(kind) = HitKind::parse(&kind) else { continue };
+            let matched_tags = match kind {
+                HitKind::Note => matched_tags(conn, &id, &marked_tags)?,
+                _ => Vec::new(),
+            };
+            hits.push(SearchHit {
+                kind,
+                id,
+                parent_id,
+                text,
+                highlights: highlights(&marked_body),
+                matched_tags,
+                // bm25() is "lower is better"; flip it for the frontend.
+                score: -rank,
+            });
+        }
+        Ok(hits)
+    })
+}
+
+/// Turns free text into an FTS5 expression: each word becomes a quoted
+/// prefix term, so FTS5 operators and punctuation in the input are inert.
+/// Returns `None` when there is nothing to search for.
+fn build_query(input: &str) -> Option<String> {
+    let russian = Stemmer::create(Algorithm::Russian);
+
+    let terms: Vec<String> = input
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let word = fold_yo(&word.to_lowercase());
+            // The porter tokenizer stems English on both sides; Russian has to
+            // be reduced to its stem here and matched as a prefix.
+            let term = if word.chars().any(is_cyrillic) {
+                let stem = russian.stem(&word).into_owned();
+                if stem.is_empty() { word } else { stem }
+            } else {
+                word
+            };
+            format!("\"{term}\"*")
+        })
+        .collect();
+
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
+/// Same folding the index triggers apply.
+fn fold_yo(text: &str) -> String {
+    text.replace('ё', "е").replace('Ё', "Е")
+}
+
+fn is_cyrillic(c: char) -> bool {
+    matches!(c, '\u{0400}'..='\u{04FF}')
+}
+
+/// Decodes `highlight()` output into UTF-16 ranges over the unmarked text.
+fn highlights(marked: &str) -> Vec<Highlight> {
+    let mut ranges = Vec::new();
+    let mut offset = 0;
+    let mut start = None;
+
+    for c in marked.chars() {
+        match c {
+            MARK_START => start = Some(offset),
+            MARK_END => {
+                if let Some(start) = start.take() {
+                    ranges.push(Highlight { start, end: offset });
+                }
+            }
+            _ => offset += c.len_utf16(),
+        }
+    }
+    ranges
+}
+
+/// Note tags are indexed newline-separated and ё-folded; returns the
+/// note's original tags whose indexed form contains a match.
+fn matched_tags(conn: &Connection, note_id: &str, marked: &str) -> Result<Vec<String>> {
+    let matched: Vec<String> = marked
+        .split('\n')
+        .filter(|tag| tag.contains(MARK_START))
+        .map(|tag| tag.replace([MARK_START, MARK_END], ""))
+        .collect();
+    if matched.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut stmt = conn.prepare_cached("SELECT tag FROM note_tags WHERE note_id = ?1 ORDER BY position")?;
+    let tags = stmt.query_map([note_id], |row| row.get::<_, String>(0))?;
+    let mut result = Vec::new();
+    for tag in tags {
+        let tag = tag?;
+        if matched.contains(&fold_yo(&tag)) {
+            result.push(tag);
+        }
+    }
+    Ok(result)
+}
@@ -0,0 +1,249 @@
+// src-tauri/src/search/fuzzy.rs
+
+//! fzf-style fuzzy matching for the command palette.
+//!
+//! Each whitespace-separated query word must appear in the candidate as a
+//! subsequence. Matches are scored with fzf's scheme: a base score per
+//! matched character, bonuses for word starts, camelCase humps and runs of
+//! consecutive characters, and penalties for gaps. So "dckr cmps" ranks
+//! "docker compose up -d" above text where the letters are scattered.
+
+use super::{char_ranges_to_utf16, fold, HitKind, SearchHit};
+use crate::error::Result;
+use crate::storage::Database;
+
+const SCORE_MATCH: i32 = 16;
+const SCORE_GAP_START: i32 = -3;
+const SCORE_GAP_EXTENSION: i32 = -1;
+
+const BONUS_BOUNDARY: i32 = SCORE_MATCH / 2;
+const BONUS_BOUNDARY_WHITE: i32 = BONUS_BOUNDARY + 2;
+const BONUS_BOUNDARY_DELIMITER: i32 = BONUS_BOUNDARY + 1;
+const BONUS_NON_WORD: i32 = SCORE_MATCH / 2;
+const BONUS_CAMEL_123: i32 = BONUS_BOUNDARY + SCORE_GAP_EXTENSION;
+const BONUS_CONSECUTIVE: i32 = -(SCORE_GAP_START + SCORE_GAP_EXTENSION);
+const BONUS_FIRST_CHAR_MULTIPLIER: i32 = 2;
+
+/// Only the start of long notes is scored; the DP is O(query × text).
+pub(super) const MAX_TEXT_CHARS: usize = 8192;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuzzyMatch {
+    pub score: i32,
+    /// Matched character indices (not bytes), ascending.
+    pub positions: Vec<usize>,
+}
+
+/// Scores every history item, note, project and folder against `query`
+/// and returns the best `limit` hits.
+pub fn fuzzy_search(db: &Database, query: &str, limit: usize) -> Result<Vec<SearchHit>> {
+    let words: Vec<&str> = query.split_whitespace().collect();
+    if words.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let candidates = db.read(|conn| {
+        // Newest history first, so ties keep the order users are used to.
+        let mut stmt = conn.prepare(
+            "SELECT 'history', id, NULL, unseal(text) FROM history WHERE NOT is_secret
+             UNION ALL SELECT 'note', n.id, n.folder_id, unseal(n.text) FROM notes n WHERE NOT n.is_secret
+             UNION ALL SELECT 'project', id, NULL, name FROM projects
+             UNION ALL SELECT 'folder', id, project_id, name FROM folders",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Option<String>>(2)?,
+                row.get::<_, String>(3)?,
+            ))
+        })?;
+        Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+    })?;
+
+    let mut scored: Vec<(i32, SearchHit)> = candidates
+        .into_iter()
+        .filter_map(|(kind, id, parent_id, text)| {
+            let kind = HitKind::parse(&kind)?;
+            let found = fuzzy_match_words(&words, &text)?;
+            let hit = SearchHit {
+                kind,
+                id,
+                parent_id,
+                highlights: char_ranges_to_utf16(&text, &found.positions),
+                text,
+                matched_tags: Vec::new(),
+                score: f64::from(found.score),
+            };
+            Some((found.score, hit))
+        })
+        .collect();
+
+    // Stable sort: equal scores prefer shorter text, then candidate order.
+    scored.sort_by(|(a, a_hit), (b, b_hit)| b.cmp(a).then(a_hit.text.len().cmp(&b_hit.text.len())));
+    Ok(scored.into_iter().take(limit).map(|(_, hit)| hit).collect())
+}
+
+/// Every word must match; scores add up and positions are merged.
+fn fuzzy_match_words(words: &[&str], text: &str) -> Option<FuzzyMatch> {
+    let mut total = FuzzyMatch { score: 0, positions: Vec::new() };
+    for word in words {
+        let found = fuzzy_match(word, text)?;
+        total.score += found.score;
+        total.positions.extend(found.positions);
+    }
+    total.positions.sort_unstable();
+    total.positions.dedup();
+    Some(total)
+}
+
+/// Best-scoring alignment of `pattern` as a subsequence of `text`, or `None`
+/// if it doesn't occur. Smart case: case-insensitive unless `pattern` has an
+/// uppercase letter. 'ё' and 'е' are treated as the same letter.
+pub fn fuzzy_match(pattern: &str, text: &str) -> Option<FuzzyMatch> {
+    let case_sensitive = pattern.chars().any(char::is_uppercase);
+    let normalize = |c: char| fold(c, case_sensitive);
+
+    let pattern: Vec<char> = pattern.chars().map(normalize).collect();
+    if pattern.is_empty() {
+        return Some(FuzzyMatch { score: 0, positions: Vec::new() });
+    }
+
+    let original: Vec<char> = text.chars().take(MAX_TEXT_CHARS).collect();
+    let chars: Vec<char> = original.iter().copied().map(normalize).collect();
+
+    // Cheap rejection before the DP: is it a subsequence at all?
+    let mut remaining = pattern.iter().peekable();
+    for &c in &chars {
+        if remaining.peek() == Some(&&c) {
+            remaining.next();
+        }
+    }
+    if remaining.peek().is_some() {
+        return None;
+    }
+
+    let bonuses: Vec<i32> = (0..original.len())
+        .map(|j| bonus_at(if j == 0 { None } else { Some(original[j - 1]) }, original[j]))
+        .collect();
+
+    let (n, m) = (pattern.len(), chars.len());
+    const NONE: i32 = i32::MIN / 2;
+
+    // score[i][j]: best score with pattern[i] matched at text[j].
+    // from[i][j]: text index where pattern[i - 1] was matched.
+    // run_bonus[i][j]: bonus of the first character of the current run.
+    let mut score = vec![vec![NONE; m]; n];
+    let mut from = vec![vec![0usize; m]; n];
+    let mut run_bonus = vec![vec![0i32; m]; n];
+
+    for j in 0..m {
+        if chars[j] == pattern[0] {
+            score[0][j] = SCORE_MATCH + bonuses[j] * BONUS_FIRST_CHAR_MULTIPLIER;
+            run_bonus[0][j] = bonuses[j];
+        }
+    }
+
+    for i in 1..n {
+        // Best `score[i - 1][k] + gap penalty` over k < j - 1, carried along.
+        let mut gapped = NONE;
+        let mut gapped_from = 0;
+
+        for j in i..m {
+            if j >= 2 {
+                let opened = score[i - 1][j - 2] + SCORE_GAP_START;
+                let extended = gapped + SCORE_GAP_EXTENSION;
+                if opened >= extended {
+                    gapped = opened;
+                    gapped_from = j - 2;
+                } else {
+                    gapped = extended;
+                }
+            }
+
+            if chars[j] != pattern[i] {
+                continue;
+            }
+
+            let mut best = NONE;
+            if gapped > NONE {
+                best = gapped + SCORE_MATCH + bonuses[j];
+                from[i][j] = gapped_from;
+                run_bonus[i][j] = bonuses[j];
+            }
+
+            let previous = score[i - 1][j - 1];
+            if previous > NONE {
+                // A run keeps the bonus of its first character, unless this
+                // character starts a stronger word boundary of its own.
+                let mut bonus = run_bonus[i - 1][j - 1].max(BONUS_CONSECUTIVE);
+                let mut started = run_bonus[i - 1][j - 1];
+                if bonuses[j] >= BONUS_BOUNDARY && bonuses[j] > bonus {
+                    bonus = bonuses[j];
+                    started = bonuses[j];
+                }
+                let consecutive = previous + SCORE_MATCH + bonus;
+                if consecutive >= best {
+                    best = consecutive;
+                    from[i][j] = j - 1;
+                    run_bonus[i][j] = started;
+                }
+            }
+
+            score[i][j] = best;
+        }
+    }
+
+    let (mut j, best) = score[n - 1]
+        .iter()
+        .copied()
+        .enumerate()
+        .filter(|&(_, s)| s > NONE)
+        .max_by(|(a_j, a), (b_j, b)| a.cmp(b).then(b_j.cmp(a_j)))?;
+
+    let mut positions = vec![0; n];
+    for i in (0..n).rev() {
+        positions[i] = j;
+        j = from[i][j];
+    }
+    Some(FuzzyMatch { score: best, positions })
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum CharClass {
+    White,
+    Delimiter,
+    NonWord,
+    Lower,
+    Upper,
+    Letter,
+    Number,
+}
+
+fn class_of(c: char) -> CharClass {
+    match c {
+        c if c.is_whitespace() => CharClass::White,
+        '/' | ',' | ':' | ';' | '|' => CharClass::Delimiter,
+        c if c.is_lowercase() => CharClass::Lower,
+        c if c.is_uppercase() => CharClass::Upper,
+        c if c.is_numeric() => CharClass::Number,
+        c if c.is_alphabetic() => CharClass::Letter,
+        _ => CharClass::NonWord,
+    }
+}
+
+/// Bonus for matching `current` given the character before it.
+fn bonus_at(previous: Option<char>, current: char) -> i32 {
+    let previous = previous.map_or(CharClass::White, class_of);
+    let current = class_of(current);
+
+    match (previous, current) {
+        (_, CharClass::White | CharClass::Delimiter | CharClass::NonWord) => BONUS_NON_WORD,
+        (CharClass::White, _) => BONUS_BOUNDARY_WHITE,
+        (CharClass::Delimiter, _) => BONUS_BOUNDARY_DELIMITER,
+        (CharClass::NonWord, _) => BONUS_BOUNDARY,
+        (CharClass::Lower, CharClass::Upper) => BONUS_CAMEL_123,
+        (CharClass::Lower | CharClass::Upper | CharClass::Letter, CharClass::Number) => BONUS_CAMEL_123,
+        _ => 0,
+    }
+}
@@ -0,0 +1,134 @@
+// src-tauri/src/search/mod.rs
+
+//! Search over history, notes, tags, projects and folder names.
+
+mod fts;
+mod fuzzy;
+mod pattern;
+mod query;
+
+#[cfg(test)]
+mod tests;
+
+use serde::{Deserialize, Serialize};
+
+pub use fts::{search, DEFAULT_LIMIT};
+pub use fuzzy::fuzzy_search;
+pub use pattern::{extract_to_note, regex_search, RegexOptions, RegexSearchResult};
+pub use query::{evaluate, parse, ParseError, Query};
+
+/// What a search should cover. Defaults to everything.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SearchScope {
+    #[default]
+    All,
+    History,
+    Notes,
+    Tags,
+    Folders,
+}
+
+impl SearchScope {
+    fn kinds(self) -> &'static [HitKind] {
+        match self {
+            SearchScope::All => &[HitKind::History, HitKind::Note, HitKind::Tag, HitKind::Folder],
+            SearchScope::History => &[HitKind::History],
+            SearchScope::Notes => &[HitKind::Note],
+            SearchScope::Tags => &[HitKind::Tag],
+            SearchScope::Folders => &[HitKind::Folder],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HitKind {
+    History,
+    Note,
+    Tag,
+    Project,
+    Folder,
+}
+
+impl HitKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            HitKind::History => "history",
+            HitKind::Note => "note",
+            HitKind::Tag => "tag",
+            HitKind::Project => "project",
+            HitKind::Folder => "folder",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "history" => Some(HitKind::History),
+            "note" => Some(HitKind::Note),
+            "tag" => Some(HitKind::Tag),
+            "project" => Some(HitKind::Project),
+            "folder" => Some(HitKind::Folder),
+            _ => None,
+        }
+    }
+}
+
+/// Half-open range into the hit's `text`, in UTF-16 code units so the
+/// webview can pass it straight to `String.prototype.slice`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Highlight {
+    pub start: usize,
+    pub end: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchHit {
+    pub kind: HitKind,
+    /// History/note/project/folder id, or the tag name itself.
+    pub id: String,
+    /// Folder of a note, project of a folder.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<String>,
+    /// Item text, folder name or tag name.
+    pub text: String,
+    pub highlights: Vec<Highlight>,
+    /// Tags of a note that matched the query.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub matched_tags: Vec<String>,
+    /// Higher is better. Only comparable within one result list.
+    pub score: f64,
+}
+
+/// Case folding shared by the matchers: lowercases unless `case_sensitive`,
+/// and treats 'ё' as 'е' (it is often typed without the dots).
+fn fold(c: char, case_sensitive: bool) -> char {
+    let c = if case_sensitive { c } else { c.to_lowercase().next().unwrap_or(c) };
+    match c {
+        'ё' => 'е',
+        'Ё' => 'Е',
+        c => c,
+    }
+}
+
+/// Character indices → merged UTF-16 ranges, matching `Highlight`.
+fn char_ranges_to_utf16(text: &str, positions: &[usize]) -> Vec<Highlight> {
+    let mut ranges: Vec<Highlight> = Vec::new();
+    let mut wanted = positions.iter().peekable();
+    let mut offset = 0;
+
+    for (index, c) in text.chars().enumerate() {
+        let Some(&&next) = wanted.peek() else { break };
+        let width = c.len_utf16();
+        if index == next {
+            wanted.next();
+            match ranges.last_mut() {
+                Some(last) if last.end == offset => last.end += width,
+                _ => ranges.push(Highlight { start: offset, end: offset + width }),
+            }
+        }
+        offset += width;
+    }
+    ranges
+}
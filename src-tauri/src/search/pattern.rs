@@ -0,0 +1,213 @@
+// src-tauri/src/search/pattern.rs
+
+//! Regular-expression search over history.
+//!
+//! Uses the `regex` crate, whose engines run in time linear in the input, so
+//! a hostile pattern can't hang the app with backtracking. On top of that
+//! the compiled program size, the wall-clock time and the number of matches
+//! are capped.
+
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+use chrono::Local;
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+
+use crate::classify::classify;
+use crate::error::{Error, Result};
+use crate::ids;
+use crate::models::NoteItem;
+use crate::storage::{Database, HISTORY_ORDER};
+
+const MAX_PATTERN_CHARS: usize = 1_000;
+/// Memory budget for the compiled program and the lazy DFA cache.
+const MAX_COMPILED_SIZE: usize = 1 << 20;
+const TIME_BUDGET: Duration = Duration::from_millis(500);
+const MAX_MATCHES: usize = 10_000;
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RegexOptions {
+    pub case_insensitive: bool,
+    /// `^` and `$` match at line breaks, not only at the ends of the text.
+    pub multi_line: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegexSearchResult {
+    /// Name of every capture group after the whole match (`None` if unnamed).
+    pub group_names: Vec<Option<String>>,
+    /// Matching history items, newest first.
+    pub items: Vec<RegexItem>,
+    pub total_matches: usize,
+    /// `true` if the time or match budget ran out before all of history
+    /// was scanned.
+    pub truncated: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegexItem {
+    pub id: String,
+    pub text: String,
+    pub matches: Vec<RegexMatch>,
+}
+
+/// Offsets are UTF-16 code units into the item's text, like `Highlight`.
+#[derive(Debug, Clone, Serialize)]
+pub struct RegexMatch {
+    pub start: usize,
+    pub end: usize,
+    pub text: String,
+    /// One entry per capture group; `None` when the group didn't take part.
+    pub groups: Vec<Option<CaptureGroup>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CaptureGroup {
+    pub start: usize,
+    pub end: usize,
+    pub text: String,
+}
+
+pub fn regex_search(db: &Database, pattern: &str, options: RegexOptions) -> Result<RegexSearchResult> {
+    let regex = compile(pattern, options)?;
+    let group_names = regex.capture_names().skip(1).map(|name| name.map(str::to_string)).collect();
+
+    let deadline = Instant::now() + TIME_BUDGET;
+    let mut items = Vec::new();
+    let mut total_matches = 0;
+    let mut truncated = false;
+
+    for (id, text) in load_history(db)? {
+        if Instant::now() >= deadline {
+            truncated = true;
+            break;
+        }
+        let (matches, complete) = find_matches(&regex, &text, MAX_MATCHES - total_matches, deadline);
+        if !matches.is_empty() {
+            total_matches += matches.len();
+            items.push(RegexItem { id, text, matches });
+        }
+        if !complete {
+            truncated = true;
+            break;
+        }
+    }
+
+    Ok(RegexSearchResult { group_names, items, total_matches, truncated })
+}
+
+/// Collects every match across history into a new note in `folder_id`,
+/// one per line, newest item first. With `unique` repeated matches are
+/// kept only once. Fails rather than saving a partial result.
+pub fn extract_to_note(
+    db: &Database,
+    pattern: &str,
+    options: RegexOptions,
+    folder_id: &str,
+    unique: bool,
+) -> Result<NoteItem> {
+    let result = regex_search(db, pattern, options)?;
+    if result.truncated {
+        return Err(Error::InvalidInput("too many matches to extract; narrow the pattern".into()));
+    }
+
+    let mut seen = HashSet::new();
+    let mut lines: Vec<String> = Vec::new();
+    for found in result.items.into_iter().flat_map(|item| item.matches) {
+        if !unique || seen.insert(found.text.clone()) {
+            lines.push(found.text);
+        }
+    }
+    if lines.is_empty() {
+        return Err(Error::InvalidInput("nothing matched the pattern".into()));
+    }
+
+    let now = Local::now();
+    let text = lines.join("\n");
+    let classification = classify(&text);
+    let note = NoteItem {
+        id: ids::new_id(),
+        text,
+        created_at: now.timestamp_millis(),
+        updated_at: now.timestamp_millis(),
+        utc_offset: now.offset().local_minus_utc() / 60,
+        content_type: classification.content_type,
+        tags: Vec::new(),
+        image_data: None,
+        language: classification.language,
+        is_favorite: false,
+        is_secret: false,
+    };
+    db.add_note(folder_id, &note)?;
+    Ok(note)
+}
+
+fn compile(pattern: &str, options: RegexOptions) -> Result<Regex> {
+    if pattern.is_empty() {
+        return Err(Error::InvalidInput("empty pattern".into()));
+    }
+    if pattern.chars().count() > MAX_PATTERN_CHARS {
+        return Err(Error::InvalidInput(format!("pattern is longer than {MAX_PATTERN_CHARS} characters")));
+    }
+
+    RegexBuilder::new(pattern)
+        .case_insensitive(options.case_insensitive)
+        .multi_line(options.multi_line)
+        .size_limit(MAX_COMPILED_SIZE)
+        .dfa_size_limit(MAX_COMPILED_SIZE)
+        .build()
+        .map_err(|err| Error::InvalidInput(format!("invalid regex: {err}")))
+}
+
+fn load_history(db: &Database) -> Result<Vec<(String, String)>> {
+    db.read(|conn| {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT id, unseal(text) FROM history WHERE NOT is_secret ORDER BY {HISTORY_ORDER}"
+        ))?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+    })
+}
+
+/// Non-empty matches in `text`, at most `limit` of them. The flag is
+/// `false` if the limit or the deadline cut the scan short; the deadline is
+/// checked between matches, so one huge item can't blow the budget.
+pub(super) fn find_matches(regex: &Regex, text: &str, limit: usize, deadline: Instant) -> (Vec<RegexMatch>, bool) {
+    let mut matches = Vec::new();
+    // Matches come in order, so UTF-16 offsets can be advanced incrementally.
+    let (mut byte, mut utf16) = (0, 0);
+
+    for captures in regex.captures_iter(text) {
+        if matches.len() == limit || Instant::now() >= deadline {
+            return (matches, false);
+        }
+        let whole = captures.get(0).expect("group 0 always participates");
+        if whole.is_empty() {
+            continue;
+        }
+
+        utf16 += text[byte..whole.start()].encode_utf16().count();
+        byte = whole.start();
+        let offset_of = |at: usize| utf16 + text[whole.start()..at].encode_utf16().count();
+
+        let groups = captures
+            .iter()
+            .skip(1)
+            .map(|group| {
+                group.map(|g| CaptureGroup { start: offset_of(g.start()), end: offset_of(g.end()), text: g.as_str().into() })
+            })
+            .collect();
+
+        matches.push(RegexMatch {
+            start: utf16,
+            end: offset_of(whole.end()),
+            text: whole.as_str().to_string(),
+            groups,
+        });
+    }
+    (matches, true)
+}
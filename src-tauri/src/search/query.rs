@@ -0,0 +1,508 @@
+// src-tauri/src/search/query.rs
+
+//! The search box query language.
+//!
+//! ```text
+//! type:url tag:work project:"Личное" before:2026-09-01 fav:true "exact phrase" -excluded
+//! type:code lang:rust
+//! ```
+//!
+//! Clauses are separated by whitespace and all must hold. A leading `-`
+//! negates a clause. Field values may be quoted. A `key:value` token whose
+//! key is not a known field is searched as plain text, so pasted URLs like
+//! `https://…` keep working.
+
+use chrono::{DateTime, Local, NaiveDate};
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{params_from_iter, Connection};
+use serde::Serialize;
+
+use super::{char_ranges_to_utf16, fold, HitKind, SearchHit};
+use crate::error::Result;
+use crate::models::{ContentType, Language};
+use crate::storage::{Database, HISTORY_ORDER};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Query {
+    pub clauses: Vec<Clause>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Clause {
+    pub negated: bool,
+    pub term: Term,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "field", content = "value", rename_all = "camelCase")]
+pub enum Term {
+    /// Bare word: case-insensitive substring of the text (or a note tag).
+    Word(String),
+    /// `"…"`: like `Word`, but may contain spaces.
+    Phrase(String),
+    Type(ContentType),
+    /// Detected programming language.
+    Language(Language),
+    /// Exact tag name, case-insensitive.
+    Tag(String),
+    /// Name of the project a note lives in, case-insensitive.
+    Project(String),
+    /// Name of the folder a note lives in, case-insensitive.
+    Folder(String),
+    /// Created before this local day.
+    Before(NaiveDate),
+    /// Created after this local day.
+    After(NaiveDate),
+    Favorite(bool),
+}
+
+/// Where and why parsing failed. Offsets are UTF-16 code units into the
+/// query string, so the search box can underline the culprit.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, thiserror::Error)]
+#[error("{message}")]
+pub struct ParseError {
+    pub message: String,
+    pub start: usize,
+    pub end: usize,
+}
+
+pub fn parse(input: &str) -> Result<Query, ParseError> {
+    let mut parser = Parser::new(input);
+    let mut clauses = Vec::new();
+    while let Some(clause) = parser.clause()? {
+        clauses.push(clause);
+    }
+    Ok(Query { clauses })
+}
+
+struct Parser {
+    chars: Vec<char>,
+    /// UTF-16 offset of every char index, plus one for the end.
+    offsets: Vec<usize>,
+    pos: usize,
+}
+
+impl Parser {
+    fn new(input: &str) -> Self {
+        let chars: Vec<char> = input.chars().collect();
+        let mut offsets = Vec::with_capacity(chars.len() + 1);
+        let mut offset = 0;
+        for c in &chars {
+            offsets.push(offset);
+            offset += c.len_utf16();
+        }
+        offsets.push(offset);
+        Self { chars, offsets, pos: 0 }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn error(&self, message: impl Into<String>, start: usize, end: usize) -> ParseError {
+        ParseError { message: message.into(), start: self.offsets[start], end: self.offsets[end] }
+    }
+
+    fn clause(&mut self) -> Result<Option<Clause>, ParseError> {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+        let Some(first) = self.peek() else { return Ok(None) };
+
+        let negated = first == '-' && self.chars.get(self.pos + 1).is_some_and(|c| !c.is_whitespace());
+        if negated {
+            self.pos += 1;
+        }
+
+        if self.peek() == Some('"') {
+            let (phrase, start) = self.quoted()?;
+            if phrase.trim().is_empty() {
+                return Err(self.error("empty phrase", start, self.pos));
+            }
+            return Ok(Some(Clause { negated, term: Term::Phrase(phrase) }));
+        }
+
+        let key_start = self.pos;
+        let key = self.take_while(|c| !c.is_whitespace() && c != ':');
+        if self.peek() == Some(':') && is_field(&key) {
+            self.pos += 1;
+            let value_start = self.pos;
+            let value = match self.peek() {
+                Some('"') => self.quoted()?.0,
+                _ => self.take_while(|c| !c.is_whitespace()),
+            };
+            if value.trim().is_empty() {
+                return Err(self.error(format!("`{key}:` needs a value"), key_start, self.pos));
+            }
+            let term = self.field(&key, value, value_start)?;
+            return Ok(Some(Clause { negated, term }));
+        }
+
+        // Not a field after all: the whole token is a plain word.
+        let rest = self.take_while(|c| !c.is_whitespace());
+        Ok(Some(Clause { negated, term: Term::Word(key + &rest) }))
+    }
+
+    fn take_while(&mut self, keep: impl Fn(char) -> bool) -> String {
+        let start = self.pos;
+        while self.peek().is_some_and(&keep) {
+            self.pos += 1;
+        }
+        self.chars[start..self.pos].iter().collect()
+    }
+
+    /// Reads `"…"` starting at the opening quote. Returns the contents and
+    /// the char index of the opening quote.
+    fn quoted(&mut self) -> Result<(String, usize), ParseError> {
+        let start = self.pos;
+        self.pos += 1;
+        let text = self.take_while(|c| c != '"');
+        if self.peek() != Some('"') {
+            return Err(self.error("missing closing quote", start, self.pos));
+        }
+        self.pos += 1;
+        Ok((text, start))
+    }
+
+    fn field(&self, key: &str, value: String, value_start: usize) -> Result<Term, ParseError> {
+        let invalid = |message: String| self.error(message, value_start, self.pos);
+
+        match key.to_lowercase().as_str() {
+            "type" => {
+                let found = ContentType::ALL.into_iter().find(|t| t.as_str().eq_ignore_ascii_case(&value));
+                found.map(Term::Type).ok_or_else(|| {
+                    let known: Vec<_> = ContentType::ALL.iter().map(|t| t.as_str()).collect();
+                    invalid(format!("unknown type `{value}`, expected one of: {}", known.join(", ")))
+                })
+            }
+            "lang" => {
+                let found = Language::ALL.into_iter().find(|l| l.as_str().eq_ignore_ascii_case(&value));
+                found.map(Term::Language).ok_or_else(|| {
+                    let known: Vec<_> = Language::ALL.iter().map(|l| l.as_str()).collect();
+                    invalid(format!("unknown language `{value}`, expected one of: {}", known.join(", ")))
+                })
+            }
+            "tag" => Ok(Term::Tag(value)),
+            "project" => Ok(Term::Project(value)),
+            "folder" => Ok(Term::Folder(value)),
+            "before" | "after" => {
+                let date = NaiveDate::parse_from_str(&value, "%Y-%m-%d")
+                    .map_err(|_| invalid(format!("`{value}` is not a date, expected YYYY-MM-DD")))?;
+                Ok(if key.eq_ignore_ascii_case("before") { Term::Before(date) } else { Term::After(date) })
+            }
+            "fav" => match value.to_lowercase().as_str() {
+                "true" | "yes" | "1" => Ok(Term::Favorite(true)),
+                "false" | "no" | "0" => Ok(Term::Favorite(false)),
+                _ => Err(invalid(format!("`{value}` is not a boolean, expected true or false"))),
+            },
+            _ => unreachable!("checked by is_field"),
+        }
+    }
+}
+
+fn is_field(key: &str) -> bool {
+    matches!(
+        key.to_lowercase().as_str(),
+        "type" | "lang" | "tag" | "project" | "folder" | "before" | "after" | "fav"
+    )
+}
+
+// --- Evaluation ---
+
+/// What a clause is checked against. History items have no tags or place,
+/// and folders have only a name and a place.
+struct Candidate<'a> {
+    text: &'a str,
+    content_type: Option<ContentType>,
+    language: Option<Language>,
+    tags: &'a [String],
+    project: Option<&'a str>,
+    folder: Option<&'a str>,
+    is_favorite: Option<bool>,
+    created: Option<NaiveDate>,
+}
+
+/// History items (newest first), then folders and notes (in project/folder
+/// order) that satisfy every clause. Secret items never match. Hits keep
+/// that order; `score` is always 0.
+///
+/// Clauses that don't look at the text become SQL conditions, so only rows
+/// that pass them are unsealed and scanned for words.
+pub fn evaluate(db: &Database, query: &Query) -> Result<Vec<SearchHit>> {
+    db.read(|conn| {
+        let mut hits = Vec::new();
+
+        if let Some(filter) = Prefilter::build(conn, query, Scope::History)? {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT id, unseal(text), content_type, language, is_favorite, created_at
+                 FROM history h WHERE NOT is_secret{} ORDER BY {HISTORY_ORDER}",
+                filter.sql
+            ))?;
+            let mut rows = stmt.query(params_from_iter(&filter.params))?;
+            while let Some(row) = rows.next()? {
+                let (id, text): (String, String) = (row.get(0)?, row.get(1)?);
+                let candidate = Candidate {
+                    text: &text,
+                    content_type: Some(ContentType::parse(&row.get::<_, String>(2)?)),
+                    language: row.get::<_, Option<String>>(3)?.as_deref().and_then(Language::parse),
+                    tags: &[],
+                    project: None,
+                    folder: None,
+                    is_favorite: Some(row.get(4)?),
+                    created: created_date(row.get(5)?),
+                };
+                hits.extend(check(query, &candidate, HitKind::History, &id, None));
+            }
+        }
+
+        if let Some(filter) = Prefilter::build(conn, query, Scope::Folders)? {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT f.id, f.project_id, f.name, p.name
+                 FROM folders f JOIN projects p ON p.id = f.project_id
+                 WHERE 1{}
+                 ORDER BY p.position, f.position",
+                filter.sql
+            ))?;
+            let mut rows = stmt.query(params_from_iter(&filter.params))?;
+            while let Some(row) = rows.next()? {
+                let (id, project_id, name, project): (String, String, String, String) =
+                    (row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?);
+                let candidate = Candidate {
+                    text: &name,
+                    content_type: None,
+                    language: None,
+                    tags: &[],
+                    project: Some(&project),
+                    folder: Some(&name),
+                    is_favorite: None,
+                    created: None,
+                };
+                hits.extend(check(query, &candidate, HitKind::Folder, &id, Some(&project_id)));
+            }
+        }
+
+        if let Some(filter) = Prefilter::build(conn, query, Scope::Notes)? {
+            let mut tag_stmt = conn.prepare("SELECT tag FROM note_tags WHERE note_id = ?1 ORDER BY position")?;
+            let mut stmt = conn.prepare(&format!(
+                "SELECT n.id, n.folder_id, unseal(n.text), n.content_type, n.language, n.is_favorite, n.created_at,
+                        f.name, p.name
+                 FROM notes n JOIN folders f ON f.id = n.folder_id JOIN projects p ON p.id = f.project_id
+                 WHERE NOT n.is_secret{}
+                 ORDER BY p.position, f.position, n.position",
+                filter.sql
+            ))?;
+            let mut rows = stmt.query(params_from_iter(&filter.params))?;
+            while let Some(row) = rows.next()? {
+                let (id, folder_id, text): (String, String, String) = (row.get(0)?, row.get(1)?, row.get(2)?);
+                let (folder, project): (String, String) = (row.get(7)?, row.get(8)?);
+                let tags: Vec<String> =
+                    tag_stmt.query_map([&id], |row| row.get(0))?.collect::<rusqlite::Result<_>>()?;
+                let candidate = Candidate {
+                    text: &text,
+                    content_type: Some(ContentType::parse(&row.get::<_, String>(3)?)),
+                    language: row.get::<_, Option<String>>(4)?.as_deref().and_then(Language::parse),
+                    tags: &tags,
+                    project: Some(&project),
+                    folder: Some(&folder),
+                    is_favorite: Some(row.get(5)?),
+                    created: created_date(row.get(6)?),
+                };
+                hits.extend(check(query, &candidate, HitKind::Note, &id, Some(&folder_id)));
+            }
+        }
+
+        Ok(hits)
+    })
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Scope {
+    /// Table alias `h`.
+    History,
+    /// Aliases `f` and `p`.
+    Folders,
+    /// Aliases `n`, `f` and `p`.
+    Notes,
+}
+
+/// What a clause means for one kind of row, before looking at the text.
+enum Condition {
+    /// Needs the text; only `check` can tell.
+    Text,
+    /// The rows have nothing to match on, so they never satisfy it.
+    Never,
+    /// Never NULL, so `NOT (…)` negates it exactly.
+    Sql(String, Vec<SqlValue>),
+}
+
+/// `AND …` conditions to append to a WHERE clause, with their parameters.
+struct Prefilter {
+    sql: String,
+    params: Vec<SqlValue>,
+}
+
+impl Prefilter {
+    /// `None` when some clause rules out every row of `scope`.
+    fn build(conn: &Connection, query: &Query, scope: Scope) -> Result<Option<Self>> {
+        let mut filter = Prefilter { sql: String::new(), params: Vec::new() };
+        for clause in &query.clauses {
+            match (condition(conn, &clause.term, scope)?, clause.negated) {
+                (Condition::Text, _) | (Condition::Never, true) => {}
+                (Condition::Never, false) => return Ok(None),
+                (Condition::Sql(sql, params), negated) => {
+                    filter.sql += &if negated { format!(" AND NOT ({sql})") } else { format!(" AND ({sql})") };
+                    filter.params.extend(params);
+                }
+            }
+        }
+        Ok(Some(filter))
+    }
+}
+
+fn condition(conn: &Connection, term: &Term, scope: Scope) -> Result<Condition> {
+    let row = if scope == Scope::History { "h" } else { "n" };
+    let has_meta = scope != Scope::Folders;
+    let has_place = scope != Scope::History;
+
+    Ok(match term {
+        Term::Word(_) | Term::Phrase(_) => Condition::Text,
+        Term::Type(content_type) if has_meta => {
+            Condition::Sql(format!("{row}.content_type = ?"), vec![content_type.as_str().to_string().into()])
+        }
+        Term::Language(language) if has_meta => {
+            Condition::Sql(format!("{row}.language IS ?"), vec![language.as_str().to_string().into()])
+        }
+        Term::Favorite(wanted) if has_meta => Condition::Sql(format!("{row}.is_favorite = ?"), vec![(*wanted).into()]),
+        Term::Before(date) if has_meta => match local_day_start(*date) {
+            Some(start) => Condition::Sql(format!("{row}.created_at < ?"), vec![start.into()]),
+            None => Condition::Text,
+        },
+        Term::After(date) if has_meta => match date.succ_opt().and_then(local_day_start) {
+            Some(start) => Condition::Sql(format!("{row}.created_at >= ?"), vec![start.into()]),
+            None => Condition::Text,
+        },
+        Term::Tag(tag) if scope == Scope::Notes => {
+            let names = matching_names(conn, "SELECT DISTINCT tag FROM note_tags", tag)?;
+            let list = placeholders(&names);
+            Condition::Sql(format!("EXISTS(SELECT 1 FROM note_tags WHERE note_id = n.id AND tag IN ({list}))"), names)
+        }
+        Term::Project(name) if has_place => {
+            let ids = matching_ids(conn, "SELECT id, name FROM projects", name)?;
+            Condition::Sql(format!("p.id IN ({})", placeholders(&ids)), ids)
+        }
+        Term::Folder(name) if has_place => {
+            let ids = matching_ids(conn, "SELECT id, name FROM folders", name)?;
+            Condition::Sql(format!("f.id IN ({})", placeholders(&ids)), ids)
+        }
+        _ => Condition::Never,
+    })
+}
+
+/// Names from `sql` equal to `wanted` once folded. Tag, project and folder
+/// names are few and never sealed, so this is cheap.
+fn matching_names(conn: &Connection, sql: &str, wanted: &str) -> Result<Vec<SqlValue>> {
+    let mut stmt = conn.prepare(sql)?;
+    let names = stmt.query_map([], |row| row.get::<_, String>(0))?;
+    let mut found = Vec::new();
+    for name in names {
+        let name = name?;
+        if eq_folded(&name, wanted) {
+            found.push(name.into());
+        }
+    }
+    Ok(found)
+}
+
+/// Ids from `sql` (`id, name`) whose name equals `wanted` once folded.
+fn matching_ids(conn: &Connection, sql: &str, wanted: &str) -> Result<Vec<SqlValue>> {
+    let mut stmt = conn.prepare(sql)?;
+    let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?;
+    let mut found = Vec::new();
+    for row in rows {
+        let (id, name) = row?;
+        if eq_folded(&name, wanted) {
+            found.push(id.into());
+        }
+    }
+    Ok(found)
+}
+
+/// `?, ?, …` for `values`. SQLite takes an empty `IN ()` as plain false,
+/// which negates cleanly, unlike `IN (NULL)`.
+fn placeholders(values: &[SqlValue]) -> String {
+    vec!["?"; values.len()].join(", ")
+}
+
+/// First instant of `date` in local time, as Unix milliseconds. Midnight
+/// itself may fall into a DST gap.
+fn local_day_start(date: NaiveDate) -> Option<i64> {
+    (0..24).find_map(|hour| {
+        let start = date.and_hms_opt(hour, 0, 0)?.and_local_timezone(Local).earliest()?;
+        Some(start.timestamp_millis())
+    })
+}
+
+fn check(query: &Query, candidate: &Candidate, kind: HitKind, id: &str, parent_id: Option<&str>) -> Option<SearchHit> {
+    let text: Vec<char> = candidate.text.chars().map(|c| fold(c, false)).collect();
+    let mut positions = Vec::new();
+
+    for clause in &query.clauses {
+        let matched = match &clause.term {
+            Term::Word(needle) | Term::Phrase(needle) => {
+                let needle: Vec<char> = needle.chars().map(|c| fold(c, false)).collect();
+                let found = occurrences(&text, &needle);
+                if !clause.negated {
+                    positions.extend(found.iter().flat_map(|&start| start..start + needle.len()));
+                }
+                !found.is_empty() || candidate.tags.iter().any(|tag| contains_folded(tag, &needle))
+            }
+            Term::Type(content_type) => candidate.content_type == Some(*content_type),
+            Term::Language(language) => candidate.language == Some(*language),
+            Term::Tag(tag) => candidate.tags.iter().any(|t| eq_folded(t, tag)),
+            Term::Project(name) => candidate.project.is_some_and(|p| eq_folded(p, name)),
+            Term::Folder(name) => candidate.folder.is_some_and(|f| eq_folded(f, name)),
+            Term::Before(date) => candidate.created.is_some_and(|created| created < *date),
+            Term::After(date) => candidate.created.is_some_and(|created| created > *date),
+            Term::Favorite(wanted) => candidate.is_favorite == Some(*wanted),
+        };
+        if matched == clause.negated {
+            return None;
+        }
+    }
+
+    positions.sort_unstable();
+    positions.dedup();
+    Some(SearchHit {
+        kind,
+        id: id.to_string(),
+        parent_id: parent_id.map(str::to_string),
+        text: candidate.text.to_string(),
+        highlights: char_ranges_to_utf16(candidate.text, &positions),
+        matched_tags: Vec::new(),
+        score: 0.0,
+    })
+}
+
+/// Start indices of every (possibly overlapping) occurrence.
+fn occurrences(haystack: &[char], needle: &[char]) -> Vec<usize> {
+    if needle.is_empty() || needle.len() > haystack.len() {
+        return Vec::new();
+    }
+    haystack.windows(needle.len()).enumerate().filter(|(_, w)| *w == needle).map(|(i, _)| i).collect()
+}
+
+fn contains_folded(haystack: &str, needle: &[char]) -> bool {
+    let haystack: Vec<char> = haystack.chars().map(|c| fold(c, false)).collect();
+    !occurrences(&haystack, needle).is_empty()
+}
+
+fn eq_folded(a: &str, b: &str) -> bool {
+    a.chars().map(|c| fold(c, false)).eq(b.chars().map(|c| fold(c, false)))
+}
+
+/// The local calendar day of a creation instant.
+fn created_date(millis: i64) -> Option<NaiveDate> {
+    let created: DateTime<Local> = DateTime::from_timestamp_millis(millis)?.into();
+    Some(created.date_naive())
+}
@@ -0,0 +1,347 @@
+// src-tauri/src/search/tests.rs
+
+use super::*;
+use crate::models::{ContentType, HistoryItem, NoteItem};
+use crate::storage::Database;
+
+fn history(id: &str, text: &str) -> HistoryItem {
+    HistoryItem {
+        id: id.into(),
+        text: text.into(),
+        created_at: 1_700_000_000_000,
+        updated_at: 1_700_000_000_000,
+        utc_offset: 0,
+        content_type: ContentType::Text,
+        image_data: None,
+        language: None,
+        is_favorite: false,
+        is_secret: false,
+        expires_at: None,
+    }
+}
+
+fn note(id: &str, text: &str, tags: &[&str]) -> NoteItem {
+    NoteItem {
+        id: id.into(),
+        text: text.into(),
+        created_at: 1_700_000_000_000,
+        updated_at: 1_700_000_000_000,
+        utc_offset: 0,
+        content_type: ContentType::Text,
+        tags: tags.iter().map(|tag| tag.to_string()).collect(),
+        image_data: None,
+        language: None,
+        is_favorite: false,
+        is_secret: false,
+    }
+}
+
+/// History items in the order given, newest last.
+fn db_with_history(texts: &[&str]) -> Database {
+    let db = Database::open_in_memory().unwrap();
+    for (i, text) in texts.iter().enumerate() {
+        db.push_history_item(&history(&format!("h{i}"), text), None).unwrap();
+    }
+    db
+}
+
+fn hit_ids(hits: &[SearchHit]) -> Vec<&str> {
+    hits.iter().map(|hit| hit.id.as_str()).collect()
+}
+
+// --- Full-text ---
+
+#[test]
+fn words_match_by_prefix() {
+    let db = db_with_history(&["documentation for the parser", "unrelated"]);
+
+    let hits = search(&db, "docu pars", SearchScope::History, DEFAULT_LIMIT).unwrap();
+    assert_eq!(hit_ids(&hits), ["h0"]);
+    assert_eq!(hits[0].highlights, [Highlight { start: 0, end: 13 }, Highlight { start: 22, end: 28 }]);
+    assert!(search(&db, "documentary", SearchScope::History, DEFAULT_LIMIT).unwrap().is_empty());
+}
+
+#[test]
+fn cyrillic_is_matched_on_its_stem() {
+    let db = Database::open_in_memory().unwrap();
+    db.add_note("f1", &note("n1", "Заметки по проекту", &["ёлка"])).unwrap();
+
+    let hits = search(&db, "заметка проекты", SearchScope::Notes, DEFAULT_LIMIT).unwrap();
+    assert_eq!(hit_ids(&hits), ["n1"]);
+
+    // 'ё' and 'е' are the same letter to the index.
+    let hits = search(&db, "елки", SearchScope::Notes, DEFAULT_LIMIT).unwrap();
+    assert_eq!(hits[0].matched_tags, ["ёлка"]);
+}
+
+#[test]
+fn highlights_are_utf16_offsets() {
+    // The emoji is two UTF-16 units, and so is the bold 𝐀 inside the word.
+    let db = db_with_history(&["😀 hello x𝐀y"]);
+
+    let hits = search(&db, "hello", SearchScope::History, DEFAULT_LIMIT).unwrap();
+    assert_eq!(hits[0].highlights, [Highlight { start: 3, end: 8 }]);
+
+    let hits = search(&db, "x𝐀y", SearchScope::History, DEFAULT_LIMIT).unwrap();
+    assert_eq!(hits[0].highlights, [Highlight { start: 9, end: 13 }]);
+    let utf16: Vec<u16> = hits[0].text.encode_utf16().collect();
+    assert_eq!(String::from_utf16(&utf16[9..13]).unwrap(), "x𝐀y");
+}
+
+#[test]
+fn operators_in_the_input_are_plain_text() {
+    let db = db_with_history(&["NEAR the \"quoted\" OR not"]);
+
+    let hits = search(&db, "\"quoted\" OR NEAR(", SearchScope::History, DEFAULT_LIMIT).unwrap();
+    assert_eq!(hit_ids(&hits), ["h0"]);
+    assert!(search(&db, "*** ()", SearchScope::All, DEFAULT_LIMIT).unwrap().is_empty());
+}
+
+// --- Fuzzy ---
+
+use super::fuzzy::{fuzzy_match, MAX_TEXT_CHARS};
+
+#[test]
+fn abbreviations_match_as_subsequences() {
+    let found = fuzzy_match("dckr", "docker compose").unwrap();
+    assert_eq!(found.positions, [0, 2, 3, 5]);
+
+    let db = db_with_history(&["docker compose up -d", "cd ~/projects"]);
+    let hits = fuzzy_search(&db, "dckr cmps", DEFAULT_LIMIT).unwrap();
+    assert_eq!(hit_ids(&hits), ["h0"]);
+    // Adjacent positions merge into one range: "d", "ck", "r", "c", "mp", "s".
+    assert_eq!(hits[0].highlights.len(), 6);
+    assert!(fuzzy_match("dckrx", "docker compose").is_none());
+}
+
+#[test]
+fn word_starts_and_runs_outrank_scattered_letters() {
+    let score = |text| fuzzy_match("cmps", text).unwrap().score;
+    assert!(score("cold maps") > score("docker compose"));
+    assert!(score("docker compose") > score("acmeparts"));
+
+    let db = db_with_history(&["acmeparts", "docker compose", "cold maps"]);
+    let hits = fuzzy_search(&db, "cmps", DEFAULT_LIMIT).unwrap();
+    assert_eq!(hit_ids(&hits), ["h2", "h1", "h0"]);
+}
+
+#[test]
+fn smart_case_and_yo_folding() {
+    assert!(fuzzy_match("DC", "docker compose").is_none());
+    assert!(fuzzy_match("dc", "Docker Compose").is_some());
+    assert!(fuzzy_match("елка", "Ёлка").is_some());
+}
+
+#[test]
+fn only_the_start_of_long_text_is_scored() {
+    let mut text = "x".repeat(MAX_TEXT_CHARS - 2);
+    text.push_str("ab");
+    assert_eq!(fuzzy_match("ab", &text).unwrap().positions, [MAX_TEXT_CHARS - 2, MAX_TEXT_CHARS - 1]);
+
+    text.push_str("cd");
+    assert!(fuzzy_match("cd", &text).is_none());
+}
+
+// --- Structured queries ---
+
+use super::query::{Clause, Term};
+use crate::models::{ContentType as Type, Language};
+
+fn clauses(input: &str) -> Vec<(bool, Term)> {
+    parse(input).unwrap().clauses.into_iter().map(|Clause { negated, term }| (negated, term)).collect()
+}
+
+fn parse_error(input: &str) -> (String, usize, usize) {
+    let err = parse(input).unwrap_err();
+    (err.message, err.start, err.end)
+}
+
+#[test]
+fn fields_words_and_phrases_parse() {
+    assert_eq!(
+        clauses(r#"type:URL tag:work project:"Личное дело" lang:rust fav:yes "exact phrase" https://x.io"#),
+        [
+            (false, Term::Type(Type::Url)),
+            (false, Term::Tag("work".into())),
+            (false, Term::Project("Личное дело".into())),
+            (false, Term::Language(Language::Rust)),
+            (false, Term::Favorite(true)),
+            (false, Term::Phrase("exact phrase".into())),
+            (false, Term::Word("https://x.io".into())),
+        ]
+    );
+}
+
+#[test]
+fn negation_binds_to_the_next_clause_only() {
+    assert_eq!(
+        clauses(r#"-tag:work draft -"old copy" - -"#),
+        [
+            (true, Term::Tag("work".into())),
+            (false, Term::Word("draft".into())),
+            (true, Term::Phrase("old copy".into())),
+            // A dash on its own is a word, not an operator.
+            (false, Term::Word("-".into())),
+            (false, Term::Word("-".into())),
+        ]
+    );
+    assert_eq!(clauses("--x"), [(true, Term::Word("-x".into()))]);
+}
+
+#[test]
+fn parse_errors_point_at_the_culprit_in_utf16() {
+    // "😀 " is three UTF-16 units, so everything after it shifts by one.
+    assert_eq!(parse_error(r#"😀 project:"Лич"#), ("missing closing quote".into(), 11, 15));
+    let (message, start, end) = parse_error("😀 before:2026-13-01");
+    assert_eq!((message.as_str(), start, end), ("`2026-13-01` is not a date, expected YYYY-MM-DD", 10, 20));
+    let (message, start, end) = parse_error("type:gif");
+    assert!(message.starts_with("unknown type `gif`, expected one of: url, "));
+    assert_eq!((start, end), (5, 8));
+    assert_eq!(parse_error("a tag: b"), ("`tag:` needs a value".into(), 2, 6));
+    assert_eq!(parse_error(r#"x """#), ("empty phrase".into(), 2, 4));
+}
+
+#[test]
+fn every_clause_must_hold_and_negated_ones_must_not() {
+    let db = db_with_history(&["deploy script", "deploy notes draft", "unrelated"]);
+    db.add_note("f1", &note("n1", "deploy checklist", &["work"])).unwrap();
+    db.add_note("f1", &note("n2", "deploy draft", &["work", "draft"])).unwrap();
+
+    let run = |input: &str| {
+        let hits = evaluate(&db, &parse(input).unwrap()).unwrap();
+        hits.into_iter().map(|hit| hit.id).collect::<Vec<_>>()
+    };
+    assert_eq!(run("deploy"), ["h1", "h0", "n1", "n2"]);
+    assert_eq!(run("deploy -draft"), ["h0", "n1"]);
+    assert_eq!(run("deploy tag:work -tag:draft"), ["n1"]);
+    assert_eq!(run("-deploy -входящие"), ["h2"]);
+}
+
+#[test]
+fn folders_match_by_name_and_project() {
+    let db = Database::open_in_memory().unwrap();
+    db.add_project("p2", "Работа").unwrap();
+    db.add_folder("p2", "f2", "Deploy").unwrap();
+
+    let hits = evaluate(&db, &parse("deploy project:работа").unwrap()).unwrap();
+    assert_eq!(hits.len(), 1);
+    assert_eq!((hits[0].kind, hits[0].id.as_str(), hits[0].parent_id.as_deref()), (HitKind::Folder, "f2", Some("p2")));
+    assert_eq!(hits[0].highlights, [Highlight { start: 0, end: 6 }]);
+
+    // Folders have no type or favorite flag to match on.
+    assert!(evaluate(&db, &parse("type:text").unwrap()).unwrap().is_empty());
+    assert!(evaluate(&db, &parse("fav:false").unwrap()).unwrap().is_empty());
+}
+
+#[test]
+fn metadata_clauses_hold_negated_and_for_unknown_names() {
+    use chrono::{Local, TimeZone};
+
+    let at = |day: u32| Local.with_ymd_and_hms(2026, 3, day, 12, 0, 0).unwrap().timestamp_millis();
+    let db = Database::open_in_memory().unwrap();
+    let items = [
+        HistoryItem { created_at: at(9), ..history("h0", "https://example.com") },
+        HistoryItem { created_at: at(10), is_favorite: true, ..history("h1", "fn main() {}") },
+        HistoryItem { created_at: at(11), ..history("h2", "plain") },
+    ];
+    for mut item in items {
+        if item.id == "h0" {
+            item.content_type = ContentType::Url;
+        }
+        if item.id == "h1" {
+            (item.content_type, item.language) = (ContentType::Code, Some(Language::Rust));
+        }
+        db.push_history_item(&item, None).unwrap();
+    }
+    db.add_note("f1", &NoteItem { created_at: at(10), ..note("n1", "checklist", &["Work"]) }).unwrap();
+
+    let run = |input: &str| {
+        let hits = evaluate(&db, &parse(input).unwrap()).unwrap();
+        hits.into_iter().map(|hit| hit.id).collect::<Vec<_>>()
+    };
+    assert_eq!(run("before:2026-03-10"), ["h0"]);
+    assert_eq!(run("after:2026-03-10"), ["h2"]);
+    // Folders have no date, so they are never before or after anything.
+    assert_eq!(run("-before:2026-03-10 -after:2026-03-10"), ["h1", "f1", "n1"]);
+    assert_eq!(run("type:url"), ["h0"]);
+    assert_eq!(run("fav:true lang:rust"), ["h1"]);
+    // Items without a detected language, and folders, aren't Rust either.
+    assert_eq!(run("-lang:rust"), ["h2", "h0", "f1", "n1"]);
+    assert_eq!(run("tag:work"), ["n1"]);
+    assert!(run("project:nowhere").is_empty());
+    assert_eq!(run("-project:nowhere -folder:nowhere -tag:nowhere checklist"), ["n1"]);
+}
+
+// --- Regex ---
+
+use std::time::{Duration, Instant};
+
+use regex::Regex;
+
+use super::pattern::find_matches;
+use crate::error::Error;
+
+fn regex_error(db: &Database, pattern: &str) -> String {
+    match regex_search(db, pattern, RegexOptions::default()) {
+        Err(Error::InvalidInput(message)) => message,
+        other => panic!("expected InvalidInput, got {other:?}"),
+    }
+}
+
+#[test]
+fn oversized_patterns_are_rejected() {
+    let db = db_with_history(&["text"]);
+
+    assert_eq!(regex_error(&db, ""), "empty pattern");
+    assert_eq!(regex_error(&db, &"a".repeat(1_001)), "pattern is longer than 1000 characters");
+    // Short to type, but megabytes once compiled.
+    assert!(regex_error(&db, r"(\w{100}){100}").starts_with("invalid regex:"));
+    assert!(regex_error(&db, "(unclosed").starts_with("invalid regex:"));
+}
+
+#[test]
+fn the_match_budget_truncates_the_scan() {
+    let db = db_with_history(&["older a", &"a".repeat(10_001)]);
+
+    let result = regex_search(&db, "a", RegexOptions::default()).unwrap();
+    assert_eq!(result.total_matches, 10_000);
+    assert!(result.truncated);
+    assert_eq!(result.items.len(), 1);
+}
+
+#[test]
+fn the_deadline_is_checked_between_matches() {
+    let regex = Regex::new("a").unwrap();
+    let text = "a".repeat(100);
+
+    let (matches, complete) = find_matches(&regex, &text, 1_000, Instant::now() + Duration::from_secs(60));
+    assert_eq!((matches.len(), complete), (100, true));
+
+    let (matches, complete) = find_matches(&regex, &text, 1_000, Instant::now());
+    assert_eq!((matches.len(), complete), (0, false));
+}
+
+#[test]
+fn matches_carry_utf16_offsets_and_groups() {
+    let db = db_with_history(&["😀 key=1, other=22"]);
+
+    let result = regex_search(&db, r"(?P<key>\w+)=(\d+)", RegexOptions::default()).unwrap();
+    assert_eq!(result.group_names, [Some("key".to_string()), None]);
+    let matches = &result.items[0].matches;
+    assert_eq!(matches.iter().map(|m| (m.start, m.end)).collect::<Vec<_>>(), [(3, 8), (10, 18)]);
+    let value = matches[1].groups[1].as_ref().unwrap();
+    assert_eq!((value.start, value.end, value.text.as_str()), (16, 18, "22"));
+}
+
+#[test]
+fn extraction_saves_matches_newest_first() {
+    let db = db_with_history(&["#a #b", "#c #a", "nothing"]);
+    let extract = |unique| extract_to_note(&db, r"#\w", RegexOptions::default(), "f1", unique).unwrap().text;
+
+    assert_eq!(extract(false), "#c\n#a\n#a\n#b");
+    assert_eq!(extract(true), "#c\n#a\n#b");
+    assert_eq!(db.load().unwrap().projects[0].folders[0].notes.len(), 2);
+
+    let nothing = extract_to_note(&db, "zzz", RegexOptions::default(), "f1", false);
+    assert!(matches!(nothing, Err(Error::InvalidInput(_))));
+}
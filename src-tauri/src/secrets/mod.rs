@@ -0,0 +1,80 @@
+// src-tauri/src/secrets/mod.rs
+
+//! Keeps credentials out of plaintext history.
+//!
+//! Every captured text is scanned. When it contains a secret, the policy
+//! decides what happens: drop the capture, store it with the secret masked,
+//! or store it as is and let retention delete it shortly after (see
+//! `RetentionPolicy::secret_ttl_secs`). Stored secrets carry `is_secret`,
+//! which keeps them out of the search index and exports.
+
+mod scan;
+
+#[cfg(test)]
+mod tests;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::storage::{self, Database};
+
+pub use scan::{scan, SecretKind, SecretMatch};
+
+const POLICY_KEY: &str = "secret_policy";
+const MASK: &str = "••••••••";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SecretPolicy {
+    /// Don't store the capture at all.
+    Skip,
+    /// Store it with every secret replaced by `••••••••`.
+    Mask,
+    /// Store it unchanged and let retention delete it soon after.
+    #[default]
+    Expire,
+}
+
+impl SecretPolicy {
+    fn as_str(self) -> &'static str {
+        match self {
+            SecretPolicy::Skip => "skip",
+            SecretPolicy::Mask => "mask",
+            SecretPolicy::Expire => "expire",
+        }
+    }
+}
+
+pub fn policy(db: &Database) -> Result<SecretPolicy> {
+    let stored = db.meta(POLICY_KEY)?;
+    Ok(match stored.as_deref() {
+        Some("skip") => SecretPolicy::Skip,
+        Some("mask") => SecretPolicy::Mask,
+        Some("expire") => SecretPolicy::Expire,
+        Some(other) => return Err(Error::InvalidInput(format!("unknown secret policy '{other}'"))),
+        None => SecretPolicy::default(),
+    })
+}
+
+pub fn set_policy(db: &Database, policy: SecretPolicy) -> Result<()> {
+    db.write(|tx| storage::set_meta(tx, POLICY_KEY, policy.as_str()))
+}
+
+/// Replaces each secret with a fixed-width mask, keeping a short prefix
+/// (`ghp_`, `AKIA`) of long tokens so the user can tell which key it was.
+pub fn mask(text: &str, secrets: &[SecretMatch]) -> String {
+    let mut masked = String::with_capacity(text.len());
+    let mut last = 0;
+    for secret in secrets {
+        let value = &text[secret.start..secret.end];
+        let keep = if secret.kind != SecretKind::PrivateKey && value.chars().count() >= 16 { 4 } else { 0 };
+        let prefix_len = value.char_indices().nth(keep).map_or(value.len(), |(at, _)| at);
+
+        masked.push_str(&text[last..secret.start]);
+        masked.push_str(&value[..prefix_len]);
+        masked.push_str(MASK);
+        last = secret.end;
+    }
+    masked.push_str(&text[last..]);
+    masked
+}
@@ -0,0 +1,251 @@
+// src-tauri/src/storage/legacy.rs
+
+//! One-time import of the IndexedDB blobs ('history', 'projects',
+//! 'globalTags') that the webview used before the SQLite store existed.
+//!
+//! Records are validated one by one so a single malformed note is reported
+//! instead of taking the whole project down with it. Existing ids are left
+//! untouched, which makes re-running the import harmless.
+
+use chrono::Local;
+use rusqlite::{OptionalExtension, Transaction};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{exists, get_meta, id_taken, insert_history_item, insert_note, set_meta, Database};
+use crate::classify::language_of;
+use crate::dates::upgrade_record;
+use crate::error::Result;
+use crate::ids;
+use crate::models::{HistoryItem, NoteItem};
+
+const MIGRATION_MARKER: &str = "legacy_indexeddb_migrated_at";
+
+/// Raw payload read from IndexedDB by the webview.
+/// Same shape as the `version: 2` export from `useImportExport.ts`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LegacyPayload {
+    #[serde(default)]
+    pub history: Vec<Value>,
+    #[serde(default)]
+    pub projects: Vec<Value>,
+    #[serde(default)]
+    pub global_tags: Vec<Value>,
+}
+
+/// A `Project` whose folders are validated individually.
+#[derive(Deserialize)]
+struct ProjectShell {
+    id: String,
+    name: String,
+    #[serde(default)]
+    folders: Vec<Value>,
+}
+
+/// A `Folder` whose notes are validated individually.
+#[derive(Deserialize)]
+struct FolderShell {
+    id: String,
+    name: String,
+    #[serde(default)]
+    notes: Vec<Value>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordFailure {
+    /// JSON-pointer-like location, e.g. `projects/0/folders/2/notes/5`.
+    pub path: String,
+    pub id: Option<String>,
+    pub error: String,
+}
+
+#[derive(Debug, Default, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportCounts {
+    pub projects: usize,
+    pub folders: usize,
+    pub notes: usize,
+    pub history: usize,
+    pub tags: usize,
+    /// Trash entries a restore dropped because the backup reuses their ids.
+    pub trash_purged: usize,
+}
+
+#[derive(Debug, Default, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationReport {
+    /// `true` when the marker was already set and nothing was written.
+    pub already_migrated: bool,
+    pub imported: ImportCounts,
+    /// Records whose id already existed in the database.
+    pub skipped: usize,
+    pub failures: Vec<RecordFailure>,
+}
+
+impl Database {
+    pub fn legacy_migration_done(&self) -> Result<bool> {
+        Ok(self.meta(MIGRATION_MARKER)?.is_some())
+    }
+
+    /// Imports `payload` and records the migration marker in the same
+    /// transaction. Does nothing if the marker is already present.
+    pub fn migrate_legacy(&self, payload: &LegacyPayload) -> Result<MigrationReport> {
+        self.write(|tx| {
+            if get_meta(tx, MIGRATION_MARKER)?.is_some() {
+                return Ok(MigrationReport { already_migrated: true, ..Default::default() });
+            }
+
+            let mut report = MigrationReport::default();
+            import_projects(tx, &payload.projects, &mut report)?;
+            import_history(tx, &payload.history, &mut report)?;
+            import_tags(tx, &payload.global_tags, &mut report)?;
+
+            set_meta(tx, MIGRATION_MARKER, &now_millis().to_string())?;
+            Ok(report)
+        })
+    }
+}
+
+fn import_projects(tx: &Transaction, projects: &[Value], report: &mut MigrationReport) -> Result<()> {
+    for (p_index, raw) in projects.iter().enumerate() {
+        let path = format!("projects/{p_index}");
+        let Some(mut project) = validate::<ProjectShell>(raw, "project", &path, report) else { continue };
+
+        // Upsert so a renamed default project ('p1') keeps the user's name.
+        if !exists(tx, "projects", &project.id)? {
+            project.id = free_id(tx, project.id)?;
+            report.imported.projects += 1;
+        }
+        tx.execute(
+            "INSERT INTO projects (id, name, position)
+             VALUES (?1, ?2, (SELECT COALESCE(MAX(position) + 1, 0) FROM projects))
+             ON CONFLICT(id) DO UPDATE SET name = excluded.name",
+            (&project.id, &project.name),
+        )?;
+
+        for (f_index, raw) in project.folders.iter().enumerate() {
+            let path = format!("{path}/folders/{f_index}");
+            let Some(mut folder) = validate::<FolderShell>(raw, "folder", &path, report) else { continue };
+
+            let owner: Option<String> = tx
+                .query_row("SELECT project_id FROM folders WHERE id = ?1", [&folder.id], |row| row.get(0))
+                .optional()?;
+            match owner {
+                Some(owner) if owner != project.id => {
+                    report.failures.push(RecordFailure {
+                        path,
+                        id: Some(folder.id),
+                        error: format!("folder id already belongs to project '{owner}'"),
+                    });
+                    continue;
+                }
+                Some(_) => {
+                    tx.execute("UPDATE folders SET name = ?2 WHERE id = ?1", (&folder.id, &folder.name))?;
+                }
+                None => {
+                    folder.id = free_id(tx, folder.id)?;
+                    tx.execute(
+                        "INSERT INTO folders (id, project_id, name, position)
+                         VALUES (?1, ?2, ?3,
+                                 (SELECT COALESCE(MAX(position) + 1, 0) FROM folders WHERE project_id = ?2))",
+                        (&folder.id, &project.id, &folder.name),
+                    )?;
+                    report.imported.folders += 1;
+                }
+            }
+
+            for (n_index, raw) in folder.notes.iter().enumerate() {
+                let path = format!("{path}/notes/{n_index}");
+                let Some(mut note) = validate::<NoteItem>(raw, "note", &path, report) else { continue };
+                note.language = note.language.or_else(|| language_of(note.content_type, &note.text));
+
+                if exists(tx, "notes", &note.id)? {
+                    report.skipped += 1;
+                    continue;
+                }
+                note.id = free_id(tx, note.id)?;
+                insert_note(tx, &folder.id, &note)?;
+                report.imported.notes += 1;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn import_history(tx: &Transaction, history: &[Value], report: &mut MigrationReport) -> Result<()> {
+    // Legacy arrays are newest-first; insert oldest-first so `seq` keeps the order.
+    for (index, raw) in history.iter().enumerate().rev() {
+        let path = format!("history/{index}");
+        let Some(mut item) = validate::<HistoryItem>(raw, "history item", &path, report) else { continue };
+        item.language = item.language.or_else(|| language_of(item.content_type, &item.text));
+
+        if exists(tx, "history", &item.id)? {
+            report.skipped += 1;
+            continue;
+        }
+        item.id = free_id(tx, item.id)?;
+        insert_history_item(tx, &item)?;
+        report.imported.history += 1;
+    }
+    Ok(())
+}
+
+fn import_tags(tx: &Transaction, tags: &[Value], report: &mut MigrationReport) -> Result<()> {
+    for (index, raw) in tags.iter().enumerate() {
+        match raw.as_str().map(str::trim) {
+            Some(tag) if !tag.is_empty() => {
+                let inserted = tx.execute("INSERT OR IGNORE INTO tags (name) VALUES (?1)", [tag])?;
+                if inserted > 0 {
+                    report.imported.tags += 1;
+                } else {
+                    report.skipped += 1;
+                }
+            }
+            _ => report.failures.push(RecordFailure {
+                path: format!("globalTags/{index}"),
+                id: None,
+                error: "expected a non-empty string".into(),
+            }),
+        }
+    }
+    Ok(())
+}
+
+/// `id`, or a new one if a record of another kind has it: legacy ids are
+/// `Date.now()`, so a folder and a note made in the same millisecond share
+/// one.
+fn free_id(tx: &Transaction, id: String) -> Result<String> {
+    Ok(if id_taken(tx, &id)? { ids::new_id() } else { id })
+}
+
+/// Deserializes `raw` as `T` (with a legacy `date` converted), recording a failure instead of erroring out.
+fn validate<T: DeserializeOwned>(
+    raw: &Value,
+    label: &str,
+    path: &str,
+    report: &mut MigrationReport,
+) -> Option<T> {
+    let mut record = raw.clone();
+    upgrade_record(&mut record, Local::now());
+    match serde_json::from_value::<T>(record) {
+        Ok(value) => Some(value),
+        Err(err) => {
+            report.failures.push(RecordFailure {
+                path: path.to_string(),
+                id: raw.get("id").and_then(Value::as_str).map(str::to_string),
+                error: format!("invalid {label}: {err}"),
+            });
+            None
+        }
+    }
+}
+
+fn now_millis() -> u128 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or_default()
+}
@@ -0,0 +1,590 @@
+// src-tauri/src/storage/mod.rs
+
+//! SQLite-backed store for projects, folders, notes, history and tags.
+//! Every public method runs in its own transaction, so a failed write
+//! never leaves a half-updated tree behind.
+//!
+//! Item text goes in through `seal()` and comes out through `unseal()`
+//! (see `vault`), which are no-ops unless encryption is on.
+
+mod legacy;
+mod schema;
+
+#[cfg(test)]
+mod tests;
+
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension, Row, Transaction};
+
+use crate::error::{Error, Result};
+use crate::models::{AppData, ContentType, Folder, HistoryItem, Language, NoteItem, Project};
+use crate::vault::{self, Keyring};
+
+pub use legacy::{ImportCounts, LegacyPayload, MigrationReport};
+
+pub const DB_FILE_NAME: &str = "clipboard.db";
+
+/// SQL condition for history rows that trimming may delete: favorites and
+/// items also saved as a note (same text, or same image) are kept.
+pub(crate) const PRUNABLE_HISTORY: &str = "NOT is_favorite
+    AND COALESCE(image_data, '') NOT IN (SELECT image_data FROM notes WHERE image_data IS NOT NULL)
+    AND (content_type = 'image' OR unseal(text) NOT IN (SELECT unseal(text) FROM notes))";
+
+/// Newest first. Imported and merged items keep the time they were copied,
+/// so insertion order (`seq`) only breaks ties.
+pub(crate) const HISTORY_ORDER: &str = "created_at DESC, seq DESC";
+
+/// Columns `history_from_row` reads, text already unsealed.
+pub(crate) const HISTORY_COLUMNS: &str = "id, unseal(text) AS text, created_at, updated_at, utc_offset, content_type,
+    image_data, language, is_favorite, is_secret, expires_at";
+
+/// Cheap to clone: all clones share one connection, so the clipboard
+/// watcher and the command handlers see the same data.
+#[derive(Clone)]
+pub struct Database {
+    conn: Arc<Mutex<Connection>>,
+    keyring: Keyring,
+}
+
+impl Database {
+    pub fn open(path: &Path) -> Result<Self> {
+        Self::init(Connection::open(path)?)
+    }
+
+    #[cfg(test)]
+    pub fn open_in_memory() -> Result<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(mut conn: Connection) -> Result<Self> {
+        conn.pragma_update(None, "foreign_keys", true)?;
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        // Zero out deleted content instead of leaving it in free pages.
+        conn.pragma_update(None, "secure_delete", true)?;
+        schema::migrate(&mut conn)?;
+        let keyring = Keyring::load(&conn)?;
+        vault::register_functions(&conn, &keyring)?;
+        Ok(Self { conn: Arc::new(Mutex::new(conn)), keyring })
+    }
+
+    pub(crate) fn keyring(&self) -> &Keyring {
+        &self.keyring
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Connection> {
+        // A panic while holding the lock cannot corrupt SQLite state,
+        // so recovering from poisoning is safe here.
+        self.conn.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Runs `f` inside a transaction that is committed only if `f` succeeds.
+    /// Refused while the store is locked.
+    pub(crate) fn write<T>(&self, f: impl FnOnce(&Transaction) -> Result<T>) -> Result<T> {
+        self.ensure_unlocked()?;
+        self.write_while_locked(f)
+    }
+
+    /// `write` without the lock check, for the inbox, which only ever
+    /// receives bytes sealed to its public key.
+    pub(crate) fn write_while_locked<T>(&self, f: impl FnOnce(&Transaction) -> Result<T>) -> Result<T> {
+        let mut conn = self.lock();
+        let tx = conn.transaction()?;
+        let value = f(&tx)?;
+        tx.commit()?;
+        Ok(value)
+    }
+
+    /// Runs `f` like `write` but always rolls back, so a preview can go
+    /// through exactly the same code as the real thing.
+    pub(crate) fn rehearse<T>(&self, f: impl FnOnce(&Transaction) -> Result<T>) -> Result<T> {
+        self.ensure_unlocked()?;
+        let mut conn = self.lock();
+        let tx = conn.transaction()?;
+        f(&tx)
+    }
+
+    /// Refused while the store is locked, even for data that isn't sealed.
+    pub(crate) fn read<T>(&self, f: impl FnOnce(&Connection) -> Result<T>) -> Result<T> {
+        self.ensure_unlocked()?;
+        f(&self.lock())
+    }
+
+    /// A setting from `meta`. Settings are never sealed, so this works
+    /// while locked too.
+    pub(crate) fn meta(&self, key: &str) -> Result<Option<String>> {
+        get_meta(&self.lock(), key)
+    }
+
+    fn ensure_unlocked(&self) -> Result<()> {
+        if self.keyring.is_locked() {
+            return Err(Error::Locked);
+        }
+        Ok(())
+    }
+
+    /// Rebuilds the file and empties the WAL, so overwritten content
+    /// doesn't linger in either.
+    pub(crate) fn compact(&self) -> Result<()> {
+        self.lock().execute_batch("VACUUM; PRAGMA wal_checkpoint(TRUNCATE);")?;
+        Ok(())
+    }
+
+    // --- Snapshot ---
+
+    pub fn load(&self) -> Result<AppData> {
+        self.read(|conn| {
+            Ok(AppData {
+                projects: load_projects(conn)?,
+                history: load_history(conn)?,
+                global_tags: load_global_tags(conn)?,
+            })
+        })
+    }
+
+    // --- Projects ---
+
+    pub fn add_project(&self, id: &str, name: &str) -> Result<()> {
+        self.write(|tx| {
+            ensure_free(tx, id)?;
+            tx.execute(
+                "INSERT INTO projects (id, name, position)
+                 VALUES (?1, ?2, (SELECT COALESCE(MAX(position) + 1, 0) FROM projects))",
+                params![id, name],
+            )?;
+            Ok(())
+        })
+    }
+
+    pub fn rename_project(&self, id: &str, name: &str) -> Result<()> {
+        self.write(|tx| {
+            let changed = tx.execute("UPDATE projects SET name = ?2 WHERE id = ?1", params![id, name])?;
+            expect_changed(changed, "project", id)
+        })
+    }
+
+    // --- Folders ---
+
+    pub fn add_folder(&self, project_id: &str, id: &str, name: &str) -> Result<()> {
+        self.write(|tx| {
+            ensure_exists(tx, "projects", "project", project_id)?;
+            ensure_free(tx, id)?;
+            tx.execute(
+                "INSERT INTO folders (id, project_id, name, position)
+                 VALUES (?1, ?2, ?3, (SELECT COALESCE(MAX(position) + 1, 0) FROM folders WHERE project_id = ?2))",
+                params![id, project_id, name],
+            )?;
+            Ok(())
+        })
+    }
+
+    pub fn rename_folder(&self, id: &str, name: &str) -> Result<()> {
+        self.write(|tx| {
+            let changed = tx.execute("UPDATE folders SET name = ?2 WHERE id = ?1", params![id, name])?;
+            expect_changed(changed, "folder", id)
+        })
+    }
+
+    /// Puts the project's folders in the order of `ids`.
+    pub fn reorder_folders(&self, project_id: &str, ids: &[String]) -> Result<()> {
+        self.write(|tx| reorder(tx, "folders", "project_id", project_id, ids, "folder"))
+    }
+
+    // --- Notes ---
+
+    pub fn add_note(&self, folder_id: &str, note: &NoteItem) -> Result<()> {
+        self.write(|tx| {
+            ensure_exists(tx, "folders", "folder", folder_id)?;
+            insert_note(tx, folder_id, note)
+        })
+    }
+
+    pub fn edit_note(
+        &self,
+        id: &str,
+        text: &str,
+        content_type: ContentType,
+        language: Option<Language>,
+        tags: Option<&[String]>,
+    ) -> Result<()> {
+        self.write(|tx| {
+            let changed = tx.execute(
+                "UPDATE notes SET text = seal(?2), content_type = ?3, language = ?4, updated_at = ?5 WHERE id = ?1",
+                params![id, text, content_type.as_str(), language.map(Language::as_str), Utc::now().timestamp_millis()],
+            )?;
+            expect_changed(changed, "note", id)?;
+            if let Some(tags) = tags {
+                replace_note_tags(tx, id, tags)?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Puts the folder's notes in the order of `ids`.
+    pub fn reorder_notes(&self, folder_id: &str, ids: &[String]) -> Result<()> {
+        self.write(|tx| reorder(tx, "notes", "folder_id", folder_id, ids, "note"))
+    }
+
+    // --- History ---
+
+    /// Inserts `item` as the newest entry and trims the oldest prunable
+    /// entries past `max_items`. Returns `false` when `item` repeats the
+    /// current newest entry.
+    pub fn push_history_item(&self, item: &HistoryItem, max_items: Option<usize>) -> Result<bool> {
+        self.write(|tx| {
+            let latest = tx
+                .query_row(
+                    &format!("SELECT unseal(text), content_type, image_data FROM history ORDER BY {HISTORY_ORDER} LIMIT 1"),
+                    [],
+                    |row| {
+                        Ok((
+                            row.get::<_, String>(0)?,
+                            row.get::<_, String>(1)?,
+                            row.get::<_, Option<String>>(2)?,
+                        ))
+                    },
+                )
+                .optional()?;
+
+            if let Some((text, content_type, image_data)) = latest {
+                let is_duplicate = match item.content_type {
+                    ContentType::Image => {
+                        content_type == ContentType::Image.as_str() && image_data == item.image_data
+                    }
+                    _ => text == item.text,
+                };
+                if is_duplicate {
+                    return Ok(false);
+                }
+            }
+
+            insert_history_item(tx, item)?;
+            if let Some(max_items) = max_items {
+                tx.execute(
+                    &format!(
+                        "DELETE FROM history WHERE {PRUNABLE_HISTORY}
+                         AND seq NOT IN (SELECT seq FROM history ORDER BY {HISTORY_ORDER} LIMIT ?1)"
+                    ),
+                    [max_items as i64],
+                )?;
+            }
+            Ok(true)
+        })
+    }
+
+    pub fn history_item(&self, id: &str) -> Result<HistoryItem> {
+        self.read(|conn| {
+            conn.query_row(
+                &format!("SELECT {HISTORY_COLUMNS} FROM history WHERE id = ?1"),
+                [id],
+                history_from_row,
+            )
+            .optional()?
+            .ok_or_else(|| Error::not_found("history item", id))
+        })
+    }
+
+    /// Puts a previously deleted item back on top. No-op if the id is present.
+    pub fn restore_history_item(&self, item: &HistoryItem) -> Result<()> {
+        self.write(|tx| {
+            let exists: bool = tx.query_row(
+                "SELECT EXISTS(SELECT 1 FROM history WHERE id = ?1)",
+                [&item.id],
+                |row| row.get(0),
+            )?;
+            if !exists {
+                insert_history_item(tx, item)?;
+            }
+            Ok(())
+        })
+    }
+
+    pub fn delete_history_item(&self, id: &str) -> Result<()> {
+        self.write(|tx| {
+            let changed = tx.execute("DELETE FROM history WHERE id = ?1", [id])?;
+            expect_changed(changed, "history item", id)
+        })
+    }
+
+    pub fn clear_history(&self) -> Result<()> {
+        self.write(|tx| {
+            tx.execute("DELETE FROM history", [])?;
+            Ok(())
+        })
+    }
+
+    /// Flips `is_favorite` and returns the new value.
+    pub fn toggle_favorite(&self, id: &str) -> Result<bool> {
+        self.write(|tx| {
+            let changed = tx.execute("UPDATE history SET is_favorite = NOT is_favorite WHERE id = ?1", [id])?;
+            expect_changed(changed, "history item", id)?;
+            Ok(tx.query_row("SELECT is_favorite FROM history WHERE id = ?1", [id], |row| row.get(0))?)
+        })
+    }
+
+    // --- Global Tags ---
+
+    pub fn add_global_tag(&self, tag: &str) -> Result<()> {
+        self.write(|tx| {
+            tx.execute("INSERT OR IGNORE INTO tags (name) VALUES (?1)", [tag])?;
+            Ok(())
+        })
+    }
+
+    pub fn delete_global_tag(&self, tag: &str) -> Result<()> {
+        self.write(|tx| {
+            tx.execute("DELETE FROM tags WHERE name = ?1", [tag])?;
+            Ok(())
+        })
+    }
+}
+
+// --- Row helpers ---
+
+pub(crate) fn get_meta(conn: &Connection, key: &str) -> Result<Option<String>> {
+    Ok(conn
+        .query_row("SELECT value FROM meta WHERE key = ?1", [key], |row| row.get(0))
+        .optional()?)
+}
+
+pub(crate) fn set_meta(conn: &Connection, key: &str, value: &str) -> Result<()> {
+    conn.execute(
+        "INSERT INTO meta (key, value) VALUES (?1, ?2)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+        params![key, value],
+    )?;
+    Ok(())
+}
+
+fn expect_changed(changed: usize, entity: &'static str, id: &str) -> Result<()> {
+    if changed == 0 {
+        return Err(Error::not_found(entity, id));
+    }
+    Ok(())
+}
+
+pub(crate) fn exists(conn: &Connection, table: &str, id: &str) -> Result<bool> {
+    let sql = format!("SELECT EXISTS(SELECT 1 FROM {table} WHERE id = ?1)");
+    Ok(conn.query_row(&sql, [id], |row| row.get(0))?)
+}
+
+/// Whether any project, folder, note or history item has `id`.
+pub(crate) fn id_taken(conn: &Connection, id: &str) -> Result<bool> {
+    exists(conn, "entity_ids", id)
+}
+
+/// Fails with `InvalidInput` when `id` is taken. `entity_ids` would refuse
+/// it anyway, but only with a bare constraint error.
+pub(crate) fn ensure_free(conn: &Connection, id: &str) -> Result<()> {
+    if id_taken(conn, id)? {
+        return Err(Error::InvalidInput(format!("id '{id}' is already in use")));
+    }
+    Ok(())
+}
+
+fn ensure_exists(conn: &Connection, table: &str, entity: &'static str, id: &str) -> Result<()> {
+    if !exists(conn, table, id)? {
+        return Err(Error::not_found(entity, id));
+    }
+    Ok(())
+}
+
+/// Renumbers `position` to follow `ids`, all of which must belong to `parent_id`.
+fn reorder(
+    tx: &Transaction,
+    table: &str,
+    parent_column: &str,
+    parent_id: &str,
+    ids: &[String],
+    entity: &'static str,
+) -> Result<()> {
+    let sql = format!("UPDATE {table} SET position = ?3 WHERE id = ?1 AND {parent_column} = ?2");
+    let mut stmt = tx.prepare(&sql)?;
+    for (position, id) in ids.iter().enumerate() {
+        let changed = stmt.execute(params![id, parent_id, position as i64])?;
+        expect_changed(changed, entity, id)?;
+    }
+    Ok(())
+}
+
+pub(crate) fn insert_note(tx: &Transaction, folder_id: &str, note: &NoteItem) -> Result<()> {
+    ensure_free(tx, &note.id)?;
+    tx.execute(
+        "INSERT INTO notes
+             (id, folder_id, text, created_at, updated_at, utc_offset, content_type, image_data, language,
+              is_favorite, is_secret, position)
+         VALUES (?1, ?2, seal(?3), ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11,
+                 (SELECT COALESCE(MAX(position) + 1, 0) FROM notes WHERE folder_id = ?2))",
+        params![
+            note.id,
+            folder_id,
+            note.text,
+            note.created_at,
+            note.updated_at,
+            note.utc_offset,
+            note.content_type.as_str(),
+            note.image_data,
+            note.language.map(Language::as_str),
+            note.is_favorite,
+            note.is_secret,
+        ],
+    )?;
+    replace_note_tags(tx, &note.id, &note.tags)
+}
+
+fn replace_note_tags(tx: &Transaction, note_id: &str, tags: &[String]) -> Result<()> {
+    tx.execute("DELETE FROM note_tags WHERE note_id = ?1", [note_id])?;
+    let mut stmt = tx.prepare("INSERT OR IGNORE INTO note_tags (note_id, tag, position) VALUES (?1, ?2, ?3)")?;
+    for (position, tag) in tags.iter().enumerate() {
+        stmt.execute(params![note_id, tag, position as i64])?;
+    }
+    Ok(())
+}
+
+pub(crate) fn insert_history_item(tx: &Transaction, item: &HistoryItem) -> Result<()> {
+    ensure_free(tx, &item.id)?;
+    tx.execute(
+        "INSERT INTO history
+             (id, text, created_at, updated_at, utc_offset, content_type, image_data, language, is_favorite,
+              is_secret, expires_at)
+         VALUES (?1, seal(?2), ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+        params![
+            item.id,
+            item.text,
+            item.created_at,
+            item.updated_at,
+            item.utc_offset,
+            item.content_type.as_str(),
+            item.image_data,
+            item.language.map(Language::as_str),
+            item.is_favorite,
+            item.is_secret,
+            item.expires_at,
+        ],
+    )?;
+    Ok(())
+}
+
+/// Records that the history item `id` came from elsewhere, so retention
+/// doesn't expire it by the age it had before it arrived.
+pub(crate) fn mark_imported(tx: &Transaction, id: &str, imported_at: i64) -> Result<()> {
+    tx.execute("UPDATE history SET imported_at = ?2 WHERE id = ?1", params![id, imported_at])?;
+    Ok(())
+}
+
+pub(crate) fn history_from_row(row: &Row) -> rusqlite::Result<HistoryItem> {
+    Ok(HistoryItem {
+        id: row.get("id")?,
+        text: row.get("text")?,
+        created_at: row.get("created_at")?,
+        updated_at: row.get("updated_at")?,
+        utc_offset: row.get("utc_offset")?,
+        content_type: ContentType::parse(&row.get::<_, String>("content_type")?),
+        image_data: row.get("image_data")?,
+        language: row.get::<_, Option<String>>("language")?.as_deref().and_then(Language::parse),
+        is_favorite: row.get("is_favorite")?,
+        is_secret: row.get("is_secret")?,
+        expires_at: row.get("expires_at")?,
+    })
+}
+
+fn note_from_row(row: &Row) -> rusqlite::Result<NoteItem> {
+    Ok(NoteItem {
+        id: row.get("id")?,
+        text: row.get("text")?,
+        created_at: row.get("created_at")?,
+        updated_at: row.get("updated_at")?,
+        utc_offset: row.get("utc_offset")?,
+        content_type: ContentType::parse(&row.get::<_, String>("content_type")?),
+        tags: Vec::new(),
+        image_data: row.get("image_data")?,
+        language: row.get::<_, Option<String>>("language")?.as_deref().and_then(Language::parse),
+        is_favorite: row.get("is_favorite")?,
+        is_secret: row.get("is_secret")?,
+    })
+}
+
+fn load_history(conn: &Connection) -> Result<Vec<HistoryItem>> {
+    let mut stmt = conn.prepare(&format!("SELECT {HISTORY_COLUMNS} FROM history ORDER BY {HISTORY_ORDER}"))?;
+    let items = stmt.query_map([], history_from_row)?.collect::<rusqlite::Result<_>>()?;
+    Ok(items)
+}
+
+fn load_global_tags(conn: &Connection) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare("SELECT name FROM tags ORDER BY rowid")?;
+    let tags = stmt.query_map([], |row| row.get(0))?.collect::<rusqlite::Result<_>>()?;
+    Ok(tags)
+}
+
+pub(crate) fn load_projects(conn: &Connection) -> Result<Vec<Project>> {
+    let mut project_stmt = conn.prepare("SELECT id, name FROM projects ORDER BY position")?;
+    let mut projects: Vec<Project> = project_stmt
+        .query_map([], |row| {
+            Ok(Project { id: row.get(0)?, name: row.get(1)?, folders: Vec::new() })
+        })?
+        .collect::<rusqlite::Result<_>>()?;
+
+    for project in &mut projects {
+        project.folders = load_folders(conn, &project.id)?;
+    }
+    Ok(projects)
+}
+
+/// One project with everything in it, or `None` if there is no such id.
+pub(crate) fn load_project(conn: &Connection, id: &str) -> Result<Option<Project>> {
+    let project = conn
+        .query_row("SELECT id, name FROM projects WHERE id = ?1", [id], |row| {
+            Ok(Project { id: row.get(0)?, name: row.get(1)?, folders: Vec::new() })
+        })
+        .optional()?;
+    let Some(mut project) = project else { return Ok(None) };
+    project.folders = load_folders(conn, &project.id)?;
+    Ok(Some(project))
+}
+
+pub(crate) fn load_folder(conn: &Connection, id: &str) -> Result<Option<Folder>> {
+    let folder = conn
+        .query_row("SELECT id, name FROM folders WHERE id = ?1", [id], |row| {
+            Ok(Folder { id: row.get(0)?, name: row.get(1)?, notes: Vec::new() })
+        })
+        .optional()?;
+    let Some(mut folder) = folder else { return Ok(None) };
+    folder.notes = load_notes(conn, "folder_id", &folder.id)?;
+    Ok(Some(folder))
+}
+
+pub(crate) fn load_note(conn: &Connection, id: &str) -> Result<Option<NoteItem>> {
+    Ok(load_notes(conn, "id", id)?.pop())
+}
+
+fn load_folders(conn: &Connection, project_id: &str) -> Result<Vec<Folder>> {
+    let mut stmt = conn.prepare_cached("SELECT id, name FROM folders WHERE project_id = ?1 ORDER BY position")?;
+    let mut folders: Vec<Folder> = stmt
+        .query_map([project_id], |row| {
+            Ok(Folder { id: row.get(0)?, name: row.get(1)?, notes: Vec::new() })
+        })?
+        .collect::<rusqlite::Result<_>>()?;
+
+    for folder in &mut folders {
+        folder.notes = load_notes(conn, "folder_id", &folder.id)?;
+    }
+    Ok(folders)
+}
+
+/// Notes whose `column` (`id` or `folder_id`) equals `value`, with tags.
+fn load_notes(conn: &Connection, column: &str, value: &str) -> Result<Vec<NoteItem>> {
+    let mut note_stmt = conn.prepare_cached(&format!(
+        "SELECT id, unseal(text) AS text, created_at, updated_at, utc_offset, content_type, image_data, language,
+                is_favorite, is_secret
+         FROM notes WHERE {column} = ?1 ORDER BY position"
+    ))?;
+    let mut tag_stmt = conn.prepare_cached("SELECT tag FROM note_tags WHERE note_id = ?1 ORDER BY position")?;
+
+    let mut notes: Vec<NoteItem> = note_stmt.query_map([value], note_from_row)?.collect::<rusqlite::Result<_>>()?;
+    for note in &mut notes {
+        note.tags = tag_stmt.query_map([&note.id], |row| row.get(0))?.collect::<rusqlite::Result<_>>()?;
+    }
+    Ok(notes)
+}
@@ -0,0 +1,522 @@
+// src-tauri/src/storage/schema.rs
+
+use rusqlite::{params, Connection, Transaction};
+
+use chrono::Local;
+
+use crate::classify::language_of;
+use crate::dates::from_legacy;
+use crate::error::Result;
+use crate::ids;
+use crate::models::ContentType;
+
+/// Ordered list of schema migrations. The index + 1 is stored in
+/// `PRAGMA user_version`, so entries must only ever be appended.
+const MIGRATIONS: &[&str] = &[
+    // v1: initial layout
+    r#"
+    CREATE TABLE projects (
+        id       TEXT PRIMARY KEY,
+        name     TEXT NOT NULL,
+        position INTEGER NOT NULL
+    );
+
+    CREATE TABLE folders (
+        id         TEXT PRIMARY KEY,
+        project_id TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+        name       TEXT NOT NULL,
+        position   INTEGER NOT NULL
+    );
+    CREATE INDEX idx_folders_project ON folders(project_id, position);
+
+    CREATE TABLE notes (
+        id           TEXT PRIMARY KEY,
+        folder_id    TEXT NOT NULL REFERENCES folders(id) ON DELETE CASCADE,
+        text         TEXT NOT NULL,
+        date         TEXT NOT NULL,
+        content_type TEXT NOT NULL,
+        image_data   TEXT,
+        is_favorite  INTEGER NOT NULL DEFAULT 0,
+        position     INTEGER NOT NULL
+    );
+    CREATE INDEX idx_notes_folder ON notes(folder_id, position);
+
+    CREATE TABLE note_tags (
+        note_id  TEXT NOT NULL REFERENCES notes(id) ON DELETE CASCADE,
+        tag      TEXT NOT NULL,
+        position INTEGER NOT NULL,
+        PRIMARY KEY (note_id, tag)
+    );
+
+    -- `seq` keeps insertion order: the newest item has the highest seq.
+    CREATE TABLE history (
+        seq          INTEGER PRIMARY KEY AUTOINCREMENT,
+        id           TEXT NOT NULL UNIQUE,
+        text         TEXT NOT NULL,
+        date         TEXT NOT NULL,
+        content_type TEXT NOT NULL,
+        image_data   TEXT,
+        is_favorite  INTEGER NOT NULL DEFAULT 0
+    );
+
+    CREATE TABLE tags (
+        name TEXT PRIMARY KEY
+    );
+
+    -- Same default as DEFAULT_PROJECT in src/constants.ts
+    INSERT INTO projects (id, name, position) VALUES ('p1', 'Личное', 0);
+    INSERT INTO folders (id, project_id, name, position) VALUES ('f1', 'p1', 'Входящие', 0);
+    "#,
+    // v2: key/value bookkeeping (migration markers, settings)
+    r#"
+    CREATE TABLE meta (
+        key   TEXT PRIMARY KEY,
+        value TEXT NOT NULL
+    );
+    "#,
+    // v3: content-addressed image blobs. `ref_count` always equals the number
+    // of history items and notes whose `image_data` names the blob.
+    r#"
+    CREATE TABLE images (
+        file_name  TEXT PRIMARY KEY,
+        sha256     TEXT NOT NULL UNIQUE,
+        byte_size  INTEGER NOT NULL,
+        ref_count  INTEGER NOT NULL DEFAULT 0,
+        created_at INTEGER NOT NULL
+    );
+
+    CREATE TRIGGER history_image_ref_insert AFTER INSERT ON history
+    WHEN new.image_data IS NOT NULL BEGIN
+        UPDATE images SET ref_count = ref_count + 1 WHERE file_name = new.image_data;
+    END;
+    CREATE TRIGGER history_image_ref_delete AFTER DELETE ON history
+    WHEN old.image_data IS NOT NULL BEGIN
+        UPDATE images SET ref_count = ref_count - 1 WHERE file_name = old.image_data;
+    END;
+    CREATE TRIGGER history_image_ref_update AFTER UPDATE OF image_data ON history
+    WHEN old.image_data IS NOT new.image_data BEGIN
+        UPDATE images SET ref_count = ref_count - 1 WHERE file_name = old.image_data;
+        UPDATE images SET ref_count = ref_count + 1 WHERE file_name = new.image_data;
+    END;
+
+    CREATE TRIGGER notes_image_ref_insert AFTER INSERT ON notes
+    WHEN new.image_data IS NOT NULL BEGIN
+        UPDATE images SET ref_count = ref_count + 1 WHERE file_name = new.image_data;
+    END;
+    CREATE TRIGGER notes_image_ref_delete AFTER DELETE ON notes
+    WHEN old.image_data IS NOT NULL BEGIN
+        UPDATE images SET ref_count = ref_count - 1 WHERE file_name = old.image_data;
+    END;
+    CREATE TRIGGER notes_image_ref_update AFTER UPDATE OF image_data ON notes
+    WHEN old.image_data IS NOT new.image_data BEGIN
+        UPDATE images SET ref_count = ref_count - 1 WHERE file_name = old.image_data;
+        UPDATE images SET ref_count = ref_count + 1 WHERE file_name = new.image_data;
+    END;
+    "#,
+    // v4: full-text index over history, notes (text + tags), folder names and
+    // global tags. `porter` stems English; Russian is stemmed at query time.
+    // unicode61 does not fold 'ё', so it is replaced with 'е' on the way in
+    // (same length, so highlight offsets still apply to the original text).
+    r#"
+    CREATE VIRTUAL TABLE search_index USING fts5(
+        kind UNINDEXED,
+        ref_id UNINDEXED,
+        body,
+        tags,
+        tokenize = 'porter unicode61 remove_diacritics 2'
+    );
+
+    INSERT INTO search_index (kind, ref_id, body, tags)
+        SELECT 'history', id, replace(replace(text, 'ё', 'е'), 'Ё', 'Е'), '' FROM history;
+    INSERT INTO search_index (kind, ref_id, body, tags)
+        SELECT 'note', id, replace(replace(text, 'ё', 'е'), 'Ё', 'Е'), COALESCE((
+            SELECT replace(replace(group_concat(tag, char(10)), 'ё', 'е'), 'Ё', 'Е')
+            FROM note_tags WHERE note_id = notes.id
+        ), '') FROM notes;
+    INSERT INTO search_index (kind, ref_id, body, tags)
+        SELECT 'folder', id, replace(replace(name, 'ё', 'е'), 'Ё', 'Е'), '' FROM folders;
+    INSERT INTO search_index (kind, ref_id, body, tags)
+        SELECT 'tag', name, replace(replace(name, 'ё', 'е'), 'Ё', 'Е'), '' FROM tags;
+
+    CREATE TRIGGER history_search_insert AFTER INSERT ON history BEGIN
+        INSERT INTO search_index (kind, ref_id, body, tags)
+        VALUES ('history', new.id, replace(replace(new.text, 'ё', 'е'), 'Ё', 'Е'), '');
+    END;
+    CREATE TRIGGER history_search_delete AFTER DELETE ON history BEGIN
+        DELETE FROM search_index WHERE kind = 'history' AND ref_id = old.id;
+    END;
+    CREATE TRIGGER history_search_update AFTER UPDATE OF text ON history BEGIN
+        UPDATE search_index SET body = replace(replace(new.text, 'ё', 'е'), 'Ё', 'Е')
+        WHERE kind = 'history' AND ref_id = new.id;
+    END;
+
+    CREATE TRIGGER notes_search_insert AFTER INSERT ON notes BEGIN
+        INSERT INTO search_index (kind, ref_id, body, tags)
+        VALUES ('note', new.id, replace(replace(new.text, 'ё', 'е'), 'Ё', 'Е'), '');
+    END;
+    CREATE TRIGGER notes_search_delete AFTER DELETE ON notes BEGIN
+        DELETE FROM search_index WHERE kind = 'note' AND ref_id = old.id;
+    END;
+    CREATE TRIGGER notes_search_update AFTER UPDATE OF text ON notes BEGIN
+        UPDATE search_index SET body = replace(replace(new.text, 'ё', 'е'), 'Ё', 'Е')
+        WHERE kind = 'note' AND ref_id = new.id;
+    END;
+
+    -- Note tags are indexed newline-separated in the note's own row.
+    CREATE TRIGGER note_tags_search_insert AFTER INSERT ON note_tags BEGIN
+        UPDATE search_index SET tags = (
+            SELECT replace(replace(group_concat(tag, char(10)), 'ё', 'е'), 'Ё', 'Е')
+            FROM note_tags WHERE note_id = new.note_id
+        ) WHERE kind = 'note' AND ref_id = new.note_id;
+    END;
+    CREATE TRIGGER note_tags_search_delete AFTER DELETE ON note_tags BEGIN
+        UPDATE search_index SET tags = COALESCE((
+            SELECT replace(replace(group_concat(tag, char(10)), 'ё', 'е'), 'Ё', 'Е')
+            FROM note_tags WHERE note_id = old.note_id
+        ), '') WHERE kind = 'note' AND ref_id = old.note_id;
+    END;
+
+    CREATE TRIGGER folders_search_insert AFTER INSERT ON folders BEGIN
+        INSERT INTO search_index (kind, ref_id, body, tags)
+        VALUES ('folder', new.id, replace(replace(new.name, 'ё', 'е'), 'Ё', 'Е'), '');
+    END;
+    CREATE TRIGGER folders_search_delete AFTER DELETE ON folders BEGIN
+        DELETE FROM search_index WHERE kind = 'folder' AND ref_id = old.id;
+    END;
+    CREATE TRIGGER folders_search_update AFTER UPDATE OF name ON folders BEGIN
+        UPDATE search_index SET body = replace(replace(new.name, 'ё', 'е'), 'Ё', 'Е')
+        WHERE kind = 'folder' AND ref_id = new.id;
+    END;
+
+    CREATE TRIGGER tags_search_insert AFTER INSERT ON tags BEGIN
+        INSERT INTO search_index (kind, ref_id, body, tags)
+        VALUES ('tag', new.name, replace(replace(new.name, 'ё', 'е'), 'Ё', 'Е'), '');
+    END;
+    CREATE TRIGGER tags_search_delete AFTER DELETE ON tags BEGIN
+        DELETE FROM search_index WHERE kind = 'tag' AND ref_id = old.name;
+    END;
+    "#,
+    // v5: programming language of code-like items. Existing rows are
+    // filled in by `backfill_languages` right after this runs.
+    r#"
+    ALTER TABLE history ADD COLUMN language TEXT;
+    ALTER TABLE notes ADD COLUMN language TEXT;
+    "#,
+    // v6: items containing credentials. They are never added to the search
+    // index; `expires_at` (unix ms) marks history items to be swept.
+    r#"
+    ALTER TABLE history ADD COLUMN is_secret INTEGER NOT NULL DEFAULT 0;
+    ALTER TABLE history ADD COLUMN expires_at INTEGER;
+    ALTER TABLE notes ADD COLUMN is_secret INTEGER NOT NULL DEFAULT 0;
+    CREATE INDEX idx_history_expires ON history(expires_at) WHERE expires_at IS NOT NULL;
+
+    DROP TRIGGER history_search_insert;
+    CREATE TRIGGER history_search_insert AFTER INSERT ON history
+    WHEN NOT new.is_secret BEGIN
+        INSERT INTO search_index (kind, ref_id, body, tags)
+        VALUES ('history', new.id, replace(replace(new.text, 'ё', 'е'), 'Ё', 'Е'), '');
+    END;
+
+    DROP TRIGGER notes_search_insert;
+    CREATE TRIGGER notes_search_insert AFTER INSERT ON notes
+    WHEN NOT new.is_secret BEGIN
+        INSERT INTO search_index (kind, ref_id, body, tags)
+        VALUES ('note', new.id, replace(replace(new.text, 'ё', 'е'), 'Ё', 'Е'), '');
+    END;
+    "#,
+    // v7: encrypted text is stored as a BLOB and must stay out of the index.
+    // Edits now re-index from scratch, so an edited secret note stays out too.
+    r#"
+    DROP TRIGGER history_search_insert;
+    CREATE TRIGGER history_search_insert AFTER INSERT ON history
+    WHEN NOT new.is_secret AND typeof(new.text) = 'text' BEGIN
+        INSERT INTO search_index (kind, ref_id, body, tags)
+        VALUES ('history', new.id, replace(replace(new.text, 'ё', 'е'), 'Ё', 'Е'), '');
+    END;
+
+    DROP TRIGGER history_search_update;
+    CREATE TRIGGER history_search_update AFTER UPDATE OF text ON history BEGIN
+        DELETE FROM search_index WHERE kind = 'history' AND ref_id = new.id;
+        INSERT INTO search_index (kind, ref_id, body, tags)
+        SELECT 'history', new.id, replace(replace(new.text, 'ё', 'е'), 'Ё', 'Е'), ''
+        WHERE NOT new.is_secret AND typeof(new.text) = 'text';
+    END;
+
+    DROP TRIGGER notes_search_insert;
+    CREATE TRIGGER notes_search_insert AFTER INSERT ON notes
+    WHEN NOT new.is_secret AND typeof(new.text) = 'text' BEGIN
+        INSERT INTO search_index (kind, ref_id, body, tags)
+        VALUES ('note', new.id, replace(replace(new.text, 'ё', 'е'), 'Ё', 'Е'), '');
+    END;
+
+    DROP TRIGGER notes_search_update;
+    CREATE TRIGGER notes_search_update AFTER UPDATE OF text ON notes BEGIN
+        DELETE FROM search_index WHERE kind = 'note' AND ref_id = new.id;
+        INSERT INTO search_index (kind, ref_id, body, tags)
+        SELECT 'note', new.id, replace(replace(new.text, 'ё', 'е'), 'Ё', 'Е'), COALESCE((
+            SELECT replace(replace(group_concat(tag, char(10)), 'ё', 'е'), 'Ё', 'Е')
+            FROM note_tags WHERE note_id = new.id
+        ), '')
+        WHERE NOT new.is_secret AND typeof(new.text) = 'text';
+    END;
+    "#,
+    // v8: captures made while the store is locked, each sealed to the
+    // inbox public key. Moved into `history` on unlock.
+    r#"
+    CREATE TABLE inbox (
+        seq   INTEGER PRIMARY KEY AUTOINCREMENT,
+        item  BLOB NOT NULL,
+        image BLOB
+    );
+    "#,
+    // v9: UTC instants (unix ms) and the offset (minutes) they were made in.
+    // Filled in from the legacy `date` by `backfill_timestamps`.
+    r#"
+    ALTER TABLE history ADD COLUMN created_at INTEGER NOT NULL DEFAULT 0;
+    ALTER TABLE history ADD COLUMN updated_at INTEGER NOT NULL DEFAULT 0;
+    ALTER TABLE history ADD COLUMN utc_offset INTEGER NOT NULL DEFAULT 0;
+    ALTER TABLE notes ADD COLUMN created_at INTEGER NOT NULL DEFAULT 0;
+    ALTER TABLE notes ADD COLUMN updated_at INTEGER NOT NULL DEFAULT 0;
+    ALTER TABLE notes ADD COLUMN utc_offset INTEGER NOT NULL DEFAULT 0;
+    CREATE INDEX idx_history_created ON history(created_at);
+    "#,
+    // v10: `date` (local HH:MM) is superseded by v9's columns.
+    r#"
+    ALTER TABLE history DROP COLUMN date;
+    ALTER TABLE notes DROP COLUMN date;
+    "#,
+    // v11: one id space for projects, folders, notes and history items, so
+    // an id names exactly one thing. Existing ids are registered, and
+    // colliding ones renamed, by `register_ids`.
+    r#"
+    CREATE TABLE entity_ids (
+        id   TEXT PRIMARY KEY,
+        kind TEXT NOT NULL
+    );
+
+    CREATE TRIGGER projects_id_insert AFTER INSERT ON projects BEGIN
+        INSERT INTO entity_ids (id, kind) VALUES (new.id, 'project');
+    END;
+    CREATE TRIGGER projects_id_delete AFTER DELETE ON projects BEGIN
+        DELETE FROM entity_ids WHERE id = old.id AND kind = 'project';
+    END;
+    CREATE TRIGGER projects_id_update AFTER UPDATE OF id ON projects BEGIN
+        UPDATE entity_ids SET id = new.id WHERE id = old.id AND kind = 'project';
+    END;
+
+    CREATE TRIGGER folders_id_insert AFTER INSERT ON folders BEGIN
+        INSERT INTO entity_ids (id, kind) VALUES (new.id, 'folder');
+    END;
+    CREATE TRIGGER folders_id_delete AFTER DELETE ON folders BEGIN
+        DELETE FROM entity_ids WHERE id = old.id AND kind = 'folder';
+    END;
+    CREATE TRIGGER folders_id_update AFTER UPDATE OF id ON folders BEGIN
+        UPDATE entity_ids SET id = new.id WHERE id = old.id AND kind = 'folder';
+    END;
+
+    CREATE TRIGGER notes_id_insert AFTER INSERT ON notes BEGIN
+        INSERT INTO entity_ids (id, kind) VALUES (new.id, 'note');
+    END;
+    CREATE TRIGGER notes_id_delete AFTER DELETE ON notes BEGIN
+        DELETE FROM entity_ids WHERE id = old.id AND kind = 'note';
+    END;
+    CREATE TRIGGER notes_id_update AFTER UPDATE OF id ON notes BEGIN
+        UPDATE entity_ids SET id = new.id WHERE id = old.id AND kind = 'note';
+    END;
+
+    CREATE TRIGGER history_id_insert AFTER INSERT ON history BEGIN
+        INSERT INTO entity_ids (id, kind) VALUES (new.id, 'history');
+    END;
+    CREATE TRIGGER history_id_delete AFTER DELETE ON history BEGIN
+        DELETE FROM entity_ids WHERE id = old.id AND kind = 'history';
+    END;
+    CREATE TRIGGER history_id_update AFTER UPDATE OF id ON history BEGIN
+        UPDATE entity_ids SET id = new.id WHERE id = old.id AND kind = 'history';
+    END;
+    "#,
+    // v12: deleted projects, folders and notes. `payload` is the whole
+    // subtree as JSON; `title` and `payload` are sealed like note text. The
+    // entry keeps its id reserved and its images referenced until purged.
+    r#"
+    CREATE TABLE trash (
+        id         TEXT PRIMARY KEY,
+        kind       TEXT NOT NULL,
+        title      NOT NULL,
+        parent_id  TEXT,
+        position   INTEGER NOT NULL,
+        payload    NOT NULL,
+        deleted_at INTEGER NOT NULL
+    );
+    CREATE INDEX idx_trash_deleted ON trash(deleted_at);
+
+    CREATE TABLE trash_images (
+        trash_id  TEXT NOT NULL REFERENCES trash(id) ON DELETE CASCADE,
+        file_name TEXT NOT NULL
+    );
+    CREATE INDEX idx_trash_images_entry ON trash_images(trash_id);
+
+    CREATE TRIGGER trash_id_insert AFTER INSERT ON trash BEGIN
+        INSERT INTO entity_ids (id, kind) VALUES (new.id, 'trash');
+    END;
+    CREATE TRIGGER trash_id_delete AFTER DELETE ON trash BEGIN
+        DELETE FROM entity_ids WHERE id = old.id AND kind = 'trash';
+    END;
+
+    CREATE TRIGGER trash_image_ref_insert AFTER INSERT ON trash_images BEGIN
+        UPDATE images SET ref_count = ref_count + 1 WHERE file_name = new.file_name;
+    END;
+    CREATE TRIGGER trash_image_ref_delete AFTER DELETE ON trash_images BEGIN
+        UPDATE images SET ref_count = ref_count - 1 WHERE file_name = old.file_name;
+    END;
+    "#,
+    // v13: a trash entry reserves the ids of everything inside it, not just
+    // its own. Plaintext payloads are scanned for the ids of their folders
+    // and notes; sealed ones keep reserving only the entry id.
+    r#"
+    DROP TRIGGER trash_id_insert;
+    DROP TRIGGER trash_id_delete;
+
+    CREATE TABLE trash_ids (
+        id       TEXT PRIMARY KEY,
+        trash_id TEXT NOT NULL REFERENCES trash(id) ON DELETE CASCADE
+    );
+    CREATE INDEX idx_trash_ids_entry ON trash_ids(trash_id);
+
+    INSERT INTO trash_ids (id, trash_id) SELECT id, id FROM trash;
+    INSERT OR IGNORE INTO trash_ids (id, trash_id)
+        SELECT j.value, t.id FROM trash t, json_tree(t.payload) j
+        WHERE typeof(t.payload) = 'text' AND j.key = 'id' AND j.type = 'text'
+          AND j.value NOT IN (SELECT id FROM entity_ids);
+    INSERT INTO entity_ids (id, kind) SELECT id, 'trash' FROM trash_ids WHERE id <> trash_id;
+
+    CREATE TRIGGER trash_ids_insert AFTER INSERT ON trash_ids BEGIN
+        INSERT INTO entity_ids (id, kind) VALUES (new.id, 'trash');
+    END;
+    CREATE TRIGGER trash_ids_delete AFTER DELETE ON trash_ids BEGIN
+        DELETE FROM entity_ids WHERE id = old.id AND kind = 'trash';
+    END;
+    "#,
+    // v14: when an item was brought in from another tool or device (unix
+    // ms), NULL for local copies. Age limits don't apply to those.
+    r#"
+    ALTER TABLE history ADD COLUMN imported_at INTEGER;
+    "#,
+];
+
+/// Schema version that added the `language` columns.
+const LANGUAGE_VERSION: usize = 5;
+/// Schema version that added `created_at`, `updated_at` and `utc_offset`.
+const TIMESTAMP_VERSION: usize = 9;
+/// Schema version that added `entity_ids`.
+const ENTITY_ID_VERSION: usize = 11;
+
+/// A table and the column in it that holds another table's ids.
+type Reference = (&'static str, &'static str);
+
+/// Tables whose ids share `entity_ids`, in the order they claim an id,
+/// with the column referring to each, if any.
+const ENTITY_TABLES: [(&str, &str, Option<Reference>); 4] = [
+    ("projects", "project", Some(("folders", "project_id"))),
+    ("folders", "folder", Some(("notes", "folder_id"))),
+    ("notes", "note", Some(("note_tags", "note_id"))),
+    ("history", "history", None),
+];
+
+pub fn migrate(conn: &mut Connection) -> Result<()> {
+    migrate_to(conn, MIGRATIONS.len())
+}
+
+/// Applies migrations up to and including version `target`.
+pub(super) fn migrate_to(conn: &mut Connection, target: usize) -> Result<()> {
+    let current: usize = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+
+    for (index, sql) in MIGRATIONS.iter().enumerate().take(target).skip(current) {
+        let tx = conn.transaction()?;
+        tx.execute_batch(sql)?;
+        if index + 1 == LANGUAGE_VERSION {
+            backfill_languages(&tx)?;
+        }
+        if index + 1 == TIMESTAMP_VERSION {
+            backfill_timestamps(&tx)?;
+        }
+        if index + 1 == ENTITY_ID_VERSION {
+            register_ids(&tx)?;
+        }
+        tx.pragma_update(None, "user_version", index + 1)?;
+        tx.commit()?;
+    }
+
+    Ok(())
+}
+
+/// Detects the language of everything stored before v5. Detection is Rust
+/// code, so this can't be part of the SQL migration itself.
+fn backfill_languages(tx: &Transaction) -> Result<()> {
+    for table in ["history", "notes"] {
+        let rows: Vec<(String, String, String)> = {
+            let mut stmt = tx.prepare(&format!("SELECT id, text, content_type FROM {table}"))?;
+            let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?;
+            rows.collect::<rusqlite::Result<_>>()?
+        };
+
+        let mut update = tx.prepare(&format!("UPDATE {table} SET language = ?2 WHERE id = ?1"))?;
+        for (id, text, content_type) in rows {
+            if let Some(language) = language_of(ContentType::parse(&content_type), &text) {
+                update.execute(params![id, language.as_str()])?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Turns the local `HH:MM` of everything stored before v9 into instants;
+/// see `dates::from_legacy`.
+fn backfill_timestamps(tx: &Transaction) -> Result<()> {
+    let now = Local::now();
+    for table in ["history", "notes"] {
+        let rows: Vec<(String, String)> = {
+            let mut stmt = tx.prepare(&format!("SELECT id, date FROM {table}"))?;
+            let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+            rows.collect::<rusqlite::Result<_>>()?
+        };
+
+        let mut update =
+            tx.prepare(&format!("UPDATE {table} SET created_at = ?2, updated_at = ?2, utc_offset = ?3 WHERE id = ?1"))?;
+        for (id, date) in rows {
+            let (created_at, utc_offset) = from_legacy(&id, &date, now);
+            update.execute(params![id, created_at, utc_offset])?;
+        }
+    }
+    Ok(())
+}
+
+/// Fills `entity_ids` with everything stored before v11. Ids were
+/// `Date.now()`, so a folder and a note made in the same millisecond could
+/// share one; the first of projects, folders, notes and history keeps it,
+/// the others get a new id.
+fn register_ids(tx: &Transaction) -> Result<()> {
+    // Children are renamed along with their parent below; checking the
+    // references at commit lets the parent go first.
+    tx.pragma_update(None, "defer_foreign_keys", true)?;
+    for (table, kind, reference) in ENTITY_TABLES {
+        let ids: Vec<String> = {
+            let mut stmt = tx.prepare(&format!("SELECT id FROM {table}"))?;
+            let rows = stmt.query_map([], |row| row.get(0))?;
+            rows.collect::<rusqlite::Result<_>>()?
+        };
+        for id in ids {
+            let inserted = tx.execute("INSERT OR IGNORE INTO entity_ids (id, kind) VALUES (?1, ?2)", [&id, kind])?;
+            if inserted > 0 {
+                continue;
+            }
+            let new_id = ids::new_id();
+            tx.execute(&format!("UPDATE {table} SET id = ?2 WHERE id = ?1"), [&id, &new_id])?;
+            tx.execute("INSERT INTO entity_ids (id, kind) VALUES (?1, ?2)", [&new_id, kind])?;
+            if let Some((child_table, column)) = reference {
+                tx.execute(&format!("UPDATE {child_table} SET {column} = ?2 WHERE {column} = ?1"), [&id, &new_id])?;
+            }
+            tx.execute("UPDATE search_index SET ref_id = ?2 WHERE kind = ?3 AND ref_id = ?1", [&id, &new_id, kind])?;
+        }
+    }
+    Ok(())
+}
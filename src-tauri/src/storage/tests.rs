@@ -0,0 +1,257 @@
+// src-tauri/src/storage/tests.rs
+
+use super::*;
+
+fn note(id: &str) -> NoteItem {
+    NoteItem {
+        id: id.into(),
+        text: format!("note {id}"),
+        created_at: 1_700_000_000_000,
+        updated_at: 1_700_000_000_000,
+        utc_offset: 0,
+        content_type: ContentType::Text,
+        tags: Vec::new(),
+        image_data: None,
+        language: None,
+        is_favorite: false,
+        is_secret: false,
+    }
+}
+
+fn item(id: &str, text: &str) -> HistoryItem {
+    HistoryItem {
+        id: id.into(),
+        text: text.into(),
+        created_at: 1_700_000_000_000,
+        updated_at: 1_700_000_000_000,
+        utc_offset: 0,
+        content_type: ContentType::Text,
+        image_data: None,
+        language: None,
+        is_favorite: false,
+        is_secret: false,
+        expires_at: None,
+    }
+}
+
+fn history_ids(db: &Database) -> Vec<String> {
+    db.load().unwrap().history.into_iter().map(|h| h.id).collect()
+}
+
+#[test]
+fn only_a_repeat_of_the_newest_item_is_skipped() {
+    let db = Database::open_in_memory().unwrap();
+    assert!(db.push_history_item(&item("h1", "same"), Some(10)).unwrap());
+    assert!(!db.push_history_item(&item("h2", "same"), Some(10)).unwrap());
+    assert!(db.push_history_item(&item("h3", "other"), Some(10)).unwrap());
+    assert!(db.push_history_item(&item("h4", "same"), Some(10)).unwrap());
+
+    assert_eq!(history_ids(&db), ["h4", "h3", "h1"]);
+}
+
+#[test]
+fn the_oldest_items_are_trimmed_past_max_items() {
+    let db = Database::open_in_memory().unwrap();
+    for i in 0..5 {
+        db.push_history_item(&item(&format!("h{i}"), &format!("item {i}")), Some(3)).unwrap();
+    }
+
+    assert_eq!(history_ids(&db), ["h4", "h3", "h2"]);
+}
+
+#[test]
+fn trimming_spares_favorites_and_items_saved_as_notes() {
+    let db = Database::open_in_memory().unwrap();
+    db.push_history_item(&item("h0", "starred"), None).unwrap();
+    db.toggle_favorite("h0").unwrap();
+    db.push_history_item(&item("h1", "note n1"), None).unwrap();
+    db.add_note("f1", &note("n1")).unwrap();
+    for i in 2..6 {
+        db.push_history_item(&item(&format!("h{i}"), &format!("item {i}")), Some(3)).unwrap();
+    }
+
+    // h2 fell out of the newest three; the two older ones outlived it.
+    assert_eq!(history_ids(&db), ["h5", "h4", "h3", "h1", "h0"]);
+}
+
+#[test]
+fn a_rehearsal_is_rolled_back_even_when_it_succeeds() {
+    let db = Database::open_in_memory().unwrap();
+    let seen = db
+        .rehearse(|tx| {
+            set_meta(tx, "probe", "1")?;
+            insert_history_item(tx, &item("h1", "dry run"))?;
+            get_meta(tx, "probe")
+        })
+        .unwrap();
+
+    assert_eq!(seen.as_deref(), Some("1"));
+    assert_eq!(db.meta("probe").unwrap(), None);
+    assert!(history_ids(&db).is_empty());
+}
+
+#[test]
+fn a_failed_write_leaves_nothing_behind() {
+    let db = Database::open_in_memory().unwrap();
+    let mut note = note("n1");
+    note.tags = vec!["a".into()];
+
+    assert!(matches!(db.add_note("missing", &note), Err(Error::NotFound { .. })));
+    db.add_note("f1", &note).unwrap();
+    assert!(db.add_note("f1", &note).is_err());
+    assert_eq!(db.load().unwrap().projects[0].folders[0].notes, [note]);
+}
+
+#[test]
+fn ids_are_unique_across_kinds_until_freed() {
+    let db = Database::open_in_memory().unwrap();
+    db.add_project("p2", "Work").unwrap();
+    db.add_folder("p2", "f2", "Drafts").unwrap();
+    db.add_note("f2", &note("n1")).unwrap();
+
+    assert!(matches!(db.add_folder("p2", "n1", "Clash"), Err(Error::InvalidInput(_))));
+    assert!(matches!(db.add_note("f2", &note("p2")), Err(Error::InvalidInput(_))));
+
+    // Notes deleted along with their folder give their ids back too.
+    db.write(|tx| Ok(tx.execute("DELETE FROM projects WHERE id = 'p2'", [])?)).unwrap();
+    db.add_project("n1", "Reused").unwrap();
+    db.add_folder("n1", "f2", "Reused").unwrap();
+}
+
+#[test]
+fn colliding_legacy_ids_are_renamed_with_their_references() {
+    let mut conn = Connection::open_in_memory().unwrap();
+    conn.pragma_update(None, "foreign_keys", true).unwrap();
+    schema::migrate_to(&mut conn, 10).unwrap();
+    // A folder, a note and a history item made in the same millisecond.
+    conn.execute_batch(
+        "INSERT INTO folders (id, project_id, name, position) VALUES ('1700000000000', 'p1', 'Drafts', 1);
+         INSERT INTO notes (id, folder_id, text, content_type, position) VALUES
+             ('1700000000001', '1700000000000', 'kept', 'text', 0),
+             ('1700000000000', '1700000000000', 'renamed', 'text', 1);
+         INSERT INTO note_tags (note_id, tag, position) VALUES ('1700000000000', 'work', 0);
+         INSERT INTO history (id, text, content_type) VALUES ('1700000000000', 'copied', 'text');",
+    )
+    .unwrap();
+
+    let db = Database::init(conn).unwrap();
+    let data = db.load().unwrap();
+    let folder = &data.projects[0].folders[1];
+    assert_eq!(folder.id, "1700000000000");
+    assert_eq!(folder.notes[0].id, "1700000000001");
+    let renamed = &folder.notes[1];
+    assert!(renamed.id.len() == 36 && renamed.tags == ["work"], "{renamed:?}");
+    assert_ne!(data.history[0].id, "1700000000000");
+
+    let (registered, violations): (i64, i64) = db
+        .read(|conn| {
+            Ok(conn.query_row(
+                "SELECT (SELECT COUNT(*) FROM entity_ids),
+                        (SELECT COUNT(*) FROM pragma_foreign_key_check)",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )?)
+        })
+        .unwrap();
+    // p1, f1, the folder, two notes and the history item.
+    assert_eq!((registered, violations), (6, 0));
+    let sql = "SELECT COUNT(*) FROM search_index WHERE kind = 'note' AND ref_id = ?1";
+    let indexed: i64 = db.read(|conn| Ok(conn.query_row(sql, [&renamed.id], |row| row.get(0))?)).unwrap();
+    assert_eq!(indexed, 1, "search index still points at the old id");
+}
+
+#[test]
+fn older_trash_entries_reserve_the_ids_inside_them() {
+    let mut conn = Connection::open_in_memory().unwrap();
+    conn.pragma_update(None, "foreign_keys", true).unwrap();
+    schema::migrate_to(&mut conn, 12).unwrap();
+    // 'f1' was reused by a live folder while the entry sat in the trash.
+    conn.execute(
+        "INSERT INTO trash (id, kind, title, parent_id, position, payload, deleted_at)
+         VALUES ('p9', 'project', 'Old', NULL, 1, ?1, 0)",
+        [r#"{"id":"p9","name":"Old","folders":[{"id":"f9","name":"A","notes":[{"id":"n9"}]},{"id":"f1","name":"B"}]}"#],
+    )
+    .unwrap();
+
+    let db = Database::init(conn).unwrap();
+    let reserved: Vec<(String, String)> = db
+        .read(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, kind FROM entity_ids WHERE id IN ('p9', 'f9', 'n9', 'f1') ORDER BY id",
+            )?;
+            let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+            Ok(rows.collect::<rusqlite::Result<_>>()?)
+        })
+        .unwrap();
+    let reserved: Vec<(&str, &str)> = reserved.iter().map(|(id, kind)| (id.as_str(), kind.as_str())).collect();
+    assert_eq!(reserved, [("f1", "folder"), ("f9", "trash"), ("n9", "trash"), ("p9", "trash")]);
+}
+
+#[test]
+fn folders_and_notes_follow_the_given_order() {
+    let db = Database::open_in_memory().unwrap();
+    db.add_folder("p1", "f2", "Drafts").unwrap();
+    db.add_note("f1", &note("n1")).unwrap();
+    db.add_note("f1", &note("n2")).unwrap();
+
+    db.reorder_folders("p1", &["f2".into(), "f1".into()]).unwrap();
+    db.reorder_notes("f1", &["n2".into(), "n1".into()]).unwrap();
+    // A note from another folder is refused and nothing moves.
+    assert!(db.reorder_notes("f2", &["n1".into()]).is_err());
+
+    let project = &db.load().unwrap().projects[0];
+    assert_eq!(project.folders.iter().map(|f| f.id.as_str()).collect::<Vec<_>>(), ["f2", "f1"]);
+    assert_eq!(project.folders[1].notes.iter().map(|n| n.id.as_str()).collect::<Vec<_>>(), ["n2", "n1"]);
+}
+
+fn legacy_payload() -> LegacyPayload {
+    LegacyPayload {
+        history: vec![
+            serde_json::json!({ "id": "1700000000002", "text": "newest", "date": "12:01", "contentType": "text" }),
+            serde_json::json!({ "id": "1700000000001", "text": "oldest", "date": "12:00", "contentType": "text" }),
+        ],
+        projects: vec![serde_json::json!({
+            "id": "p1",
+            "name": "Renamed",
+            "folders": [{
+                "id": "f1",
+                "name": "Inbox",
+                "notes": [
+                    { "id": "1700000000003", "text": "kept", "date": "12:02", "contentType": "text", "tags": ["work"] },
+                    { "id": "1700000000004", "date": "12:03", "contentType": "text" },
+                ],
+            }],
+        })],
+        global_tags: vec![serde_json::json!("work"), serde_json::json!(42)],
+    }
+}
+
+#[test]
+fn legacy_records_that_fail_are_reported_and_the_rest_imported() {
+    let db = Database::open_in_memory().unwrap();
+    let report = db.migrate_legacy(&legacy_payload()).unwrap();
+
+    let failed: Vec<_> = report.failures.iter().map(|f| (f.path.as_str(), f.id.as_deref())).collect();
+    assert_eq!(failed, [("projects/0/folders/0/notes/1", Some("1700000000004")), ("globalTags/1", None)]);
+    assert!(report.failures[0].error.contains("text"), "{:?}", report.failures[0]);
+    assert_eq!((report.imported.notes, report.imported.history, report.imported.tags), (1, 2, 1));
+
+    let data = db.load().unwrap();
+    assert_eq!(data.projects[0].name, "Renamed");
+    assert_eq!(data.projects[0].folders[0].notes[0].tags, ["work"]);
+    assert_eq!(history_ids(&db), ["1700000000002", "1700000000001"]);
+}
+
+#[test]
+fn the_legacy_migration_runs_once() {
+    let db = Database::open_in_memory().unwrap();
+    assert!(!db.legacy_migration_done().unwrap());
+    db.migrate_legacy(&legacy_payload()).unwrap();
+    let before = db.load().unwrap();
+
+    assert!(db.legacy_migration_done().unwrap());
+    let again = db.migrate_legacy(&legacy_payload()).unwrap();
+    assert!(again.already_migrated);
+    assert_eq!(again.imported.history + again.imported.notes + again.skipped, 0);
+    assert_eq!(db.load().unwrap(), before);
+}
@@ -0,0 +1,357 @@
+// src-tauri/src/trash/mod.rs
+
+//! Deleted projects, folders and notes, kept until restored or purged.
+//!
+//! Deleting moves the record and everything under it into `trash` as one
+//! entry: the subtree as JSON, plus the parent and position it had.
+//! `restore` puts it back in that spot, or at the end of another parent when
+//! the original one is gone. Until then the entry keeps every id inside it
+//! reserved, so nothing else can take one and block the restore. The
+//! retention timer purges entries older than
+//! `RetentionPolicy::trash_max_age_days`.
+
+#[cfg(test)]
+mod tests;
+
+use chrono::Utc;
+use rusqlite::{params, OptionalExtension, Row, Transaction};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::images::ImageStore;
+use crate::models::{Folder, NoteItem, Project};
+use crate::storage::{self, Database};
+
+/// Longest note title shown in the list, in characters.
+const TITLE_CHARS: usize = 80;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TrashKind {
+    Project,
+    Folder,
+    Note,
+}
+
+impl TrashKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TrashKind::Project => "project",
+            TrashKind::Folder => "folder",
+            TrashKind::Note => "note",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "project" => Some(TrashKind::Project),
+            "folder" => Some(TrashKind::Folder),
+            "note" => Some(TrashKind::Note),
+            _ => None,
+        }
+    }
+
+    fn table(self) -> &'static str {
+        match self {
+            TrashKind::Project => "projects",
+            TrashKind::Folder => "folders",
+            TrashKind::Note => "notes",
+        }
+    }
+
+    /// The column naming the parent, and what kind of record that is.
+    fn parent(self) -> Option<(&'static str, TrashKind)> {
+        match self {
+            TrashKind::Project => None,
+            TrashKind::Folder => Some(("project_id", TrashKind::Project)),
+            TrashKind::Note => Some(("folder_id", TrashKind::Folder)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashEntry {
+    /// The deleted record's id; `restore` brings it back under the same one.
+    pub id: String,
+    pub kind: TrashKind,
+    /// Project or folder name, or the first line of a note. Empty for
+    /// secret notes.
+    pub title: String,
+    /// Project of a folder, folder of a note.
+    pub parent_id: Option<String>,
+    /// Whether the entry can go back where it was without picking another
+    /// parent. Always true for projects.
+    pub parent_exists: bool,
+    /// Unix milliseconds.
+    pub deleted_at: i64,
+}
+
+/// A deleted record with everything under it. Projects and folders look
+/// alike in JSON, so the entry's kind says which one a payload is.
+enum Subtree {
+    Project(Project),
+    Folder(Folder),
+    Note(NoteItem),
+}
+
+impl Subtree {
+    /// Loads just the record and what's under it, not the whole store.
+    fn load(tx: &Transaction, kind: TrashKind, id: &str) -> Result<Option<Self>> {
+        Ok(match kind {
+            TrashKind::Project => storage::load_project(tx, id)?.map(Subtree::Project),
+            TrashKind::Folder => storage::load_folder(tx, id)?.map(Subtree::Folder),
+            TrashKind::Note => storage::load_note(tx, id)?.map(Subtree::Note),
+        })
+    }
+
+    fn from_json(kind: TrashKind, json: &str) -> Result<Self> {
+        Ok(match kind {
+            TrashKind::Project => Subtree::Project(serde_json::from_str(json)?),
+            TrashKind::Folder => Subtree::Folder(serde_json::from_str(json)?),
+            TrashKind::Note => Subtree::Note(serde_json::from_str(json)?),
+        })
+    }
+
+    fn to_json(&self) -> Result<String> {
+        Ok(match self {
+            Subtree::Project(project) => serde_json::to_string(project)?,
+            Subtree::Folder(folder) => serde_json::to_string(folder)?,
+            Subtree::Note(note) => serde_json::to_string(note)?,
+        })
+    }
+
+    fn title(&self) -> String {
+        match self {
+            Subtree::Project(project) => project.name.clone(),
+            Subtree::Folder(folder) => folder.name.clone(),
+            Subtree::Note(note) if note.is_secret => String::new(),
+            Subtree::Note(note) => {
+                let line = note.text.lines().map(str::trim).find(|line| !line.is_empty());
+                line.unwrap_or_default().chars().take(TITLE_CHARS).collect()
+            }
+        }
+    }
+
+    /// The record's own id first, then those of everything under it.
+    fn ids(&self) -> Vec<&str> {
+        let (mut ids, folders) = match self {
+            Subtree::Project(project) => (vec![project.id.as_str()], project.folders.as_slice()),
+            Subtree::Folder(folder) => (Vec::new(), std::slice::from_ref(folder)),
+            Subtree::Note(_) => (Vec::new(), [].as_slice()),
+        };
+        ids.extend(folders.iter().map(|folder| folder.id.as_str()));
+        ids.extend(self.notes().into_iter().map(|note| note.id.as_str()));
+        ids
+    }
+
+    fn notes(&self) -> Vec<&NoteItem> {
+        match self {
+            Subtree::Project(project) => project.folders.iter().flat_map(|f| &f.notes).collect(),
+            Subtree::Folder(folder) => folder.notes.iter().collect(),
+            Subtree::Note(note) => vec![note],
+        }
+    }
+}
+
+/// Moves a project, folder or note, with everything under it, to the trash.
+pub fn delete(db: &Database, kind: TrashKind, id: &str) -> Result<TrashEntry> {
+    let deleted_at = Utc::now().timestamp_millis();
+    db.write(|tx| {
+        let parent_column = kind.parent().map_or("NULL", |(column, _)| column);
+        let sql = format!("SELECT {parent_column}, position FROM {} WHERE id = ?1", kind.table());
+        let (parent_id, position): (Option<String>, i64) = tx
+            .query_row(&sql, [id], |row| Ok((row.get(0)?, row.get(1)?)))
+            .optional()?
+            .ok_or_else(|| Error::not_found(kind.as_str(), id))?;
+        let subtree = Subtree::load(tx, kind, id)?.ok_or_else(|| Error::not_found(kind.as_str(), id))?;
+
+        tx.execute(&format!("DELETE FROM {} WHERE id = ?1", kind.table()), [id])?;
+        let title = subtree.title();
+        tx.execute(
+            "INSERT INTO trash (id, kind, title, parent_id, position, payload, deleted_at)
+             VALUES (?1, ?2, seal(?3), ?4, ?5, seal(?6), ?7)",
+            params![id, kind.as_str(), title, parent_id, position, subtree.to_json()?, deleted_at],
+        )?;
+        let mut id_stmt = tx.prepare("INSERT INTO trash_ids (id, trash_id) VALUES (?1, ?2)")?;
+        for reserved in subtree.ids() {
+            id_stmt.execute(params![reserved, id])?;
+        }
+        let mut image_stmt = tx.prepare("INSERT INTO trash_images (trash_id, file_name) VALUES (?1, ?2)")?;
+        for file_name in subtree.notes().into_iter().filter_map(|note| note.image_data.as_deref()) {
+            image_stmt.execute(params![id, file_name])?;
+        }
+
+        Ok(TrashEntry { id: id.to_string(), kind, title, parent_id, parent_exists: true, deleted_at })
+    })
+}
+
+/// Newest first.
+pub fn list(db: &Database) -> Result<Vec<TrashEntry>> {
+    db.read(|conn| {
+        let mut stmt = conn.prepare(
+            "SELECT id, kind, unseal(title), parent_id, deleted_at,
+                    CASE kind
+                        WHEN 'folder' THEN EXISTS(SELECT 1 FROM projects WHERE id = trash.parent_id)
+                        WHEN 'note' THEN EXISTS(SELECT 1 FROM folders WHERE id = trash.parent_id)
+                        ELSE 1
+                    END
+             FROM trash ORDER BY deleted_at DESC, rowid DESC",
+        )?;
+        let entries = stmt
+            .query_map([], |row| {
+                Ok(TrashEntry {
+                    id: row.get(0)?,
+                    kind: kind_at(row, 1)?,
+                    title: row.get(2)?,
+                    parent_id: row.get(3)?,
+                    deleted_at: row.get(4)?,
+                    parent_exists: row.get(5)?,
+                })
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(entries)
+    })
+}
+
+/// Puts an entry back where it was deleted from. `parent_id` moves a folder
+/// or note to the end of another project or folder instead, and is needed
+/// when the original one is gone too.
+pub fn restore(db: &Database, id: &str, parent_id: Option<&str>) -> Result<()> {
+    db.write(|tx| {
+        let (kind, original_parent, position, payload): (TrashKind, Option<String>, i64, String) = tx
+            .query_row(
+                "SELECT kind, parent_id, position, unseal(payload) FROM trash WHERE id = ?1",
+                [id],
+                |row| Ok((kind_at(row, 0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+            )
+            .optional()?
+            .ok_or_else(|| Error::not_found("trash entry", id))?;
+        let subtree = Subtree::from_json(kind, &payload)?;
+        // Frees the ids and drops the image references; the inserts below
+        // take them back.
+        tx.execute("DELETE FROM trash WHERE id = ?1", [id])?;
+
+        let (parent, position) = match kind.parent() {
+            None => (None, Some(position)),
+            Some((_, parent_kind)) => {
+                let (parent, position) = match parent_id {
+                    Some(chosen) if Some(chosen) != original_parent.as_deref() => (chosen.to_string(), None),
+                    _ => (original_parent.unwrap_or_default(), Some(position)),
+                };
+                if !storage::exists(tx, parent_kind.table(), &parent)? {
+                    if position.is_none() {
+                        return Err(Error::not_found(parent_kind.as_str(), parent));
+                    }
+                    return Err(Error::InvalidInput(format!(
+                        "the {} this {} was in no longer exists; restore it first or choose another one",
+                        parent_kind.as_str(),
+                        kind.as_str(),
+                    )));
+                }
+                (Some(parent), position)
+            }
+        };
+        let position = make_room(tx, kind, parent.as_deref(), position)?;
+        let parent = parent.as_deref().unwrap_or_default();
+
+        match subtree {
+            Subtree::Project(project) => insert_project(tx, &project, position),
+            Subtree::Folder(folder) => insert_folder(tx, parent, &folder, position),
+            Subtree::Note(note) => {
+                storage::insert_note(tx, parent, &note)?;
+                tx.execute("UPDATE notes SET position = ?2 WHERE id = ?1", params![note.id, position])?;
+                Ok(())
+            }
+        }
+    })
+}
+
+/// Deletes one entry for good, or all of them when `id` is `None`, and
+/// returns how many went. Images only they still referenced are deleted
+/// too.
+pub fn purge(db: &Database, images: &ImageStore, id: Option<&str>) -> Result<usize> {
+    let (purged, file_names) = db.write(|tx| {
+        let file_names: Vec<String> = {
+            let mut stmt =
+                tx.prepare("SELECT DISTINCT file_name FROM trash_images WHERE ?1 IS NULL OR trash_id = ?1")?;
+            let rows = stmt.query_map([id], |row| row.get(0))?;
+            rows.collect::<rusqlite::Result<_>>()?
+        };
+        let purged = tx.execute("DELETE FROM trash WHERE ?1 IS NULL OR id = ?1", [id])?;
+        if let (Some(id), 0) = (id, purged) {
+            return Err(Error::not_found("trash entry", id));
+        }
+        Ok((purged, file_names))
+    })?;
+
+    for file_name in file_names {
+        match images.release(&file_name) {
+            // Notes may name images the store never registered.
+            Ok(_) | Err(Error::NotFound { .. }) => {}
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(purged)
+}
+
+/// Purges entries deleted before `cutoff_millis`; returns their ids. Runs
+/// inside the retention transaction, so their images are left to the
+/// garbage collector, like those of pruned history.
+pub(crate) fn purge_before(tx: &Transaction, cutoff_millis: i64) -> Result<Vec<String>> {
+    let mut stmt = tx.prepare("DELETE FROM trash WHERE deleted_at < ?1 RETURNING id")?;
+    let ids = stmt.query_map([cutoff_millis], |row| row.get(0))?.collect::<rusqlite::Result<_>>()?;
+    Ok(ids)
+}
+
+fn kind_at(row: &Row, index: usize) -> rusqlite::Result<TrashKind> {
+    let kind: String = row.get(index)?;
+    TrashKind::parse(&kind).ok_or_else(|| rusqlite::Error::InvalidColumnType(index, kind, rusqlite::types::Type::Text))
+}
+
+/// Opens a gap at `position` among the siblings under `parent`, or returns
+/// the position after the last of them when there is none to go back to.
+fn make_room(tx: &Transaction, kind: TrashKind, parent: Option<&str>, position: Option<i64>) -> Result<i64> {
+    let siblings = match kind.parent() {
+        Some((column, _)) => format!("{column} = ?1"),
+        None => "?1 IS NULL".to_string(),
+    };
+    let table = kind.table();
+    match position {
+        Some(position) => {
+            tx.execute(
+                &format!("UPDATE {table} SET position = position + 1 WHERE {siblings} AND position >= ?2"),
+                params![parent, position],
+            )?;
+            Ok(position)
+        }
+        None => {
+            let sql = format!("SELECT COALESCE(MAX(position) + 1, 0) FROM {table} WHERE {siblings}");
+            Ok(tx.query_row(&sql, [parent], |row| row.get(0))?)
+        }
+    }
+}
+
+fn insert_project(tx: &Transaction, project: &Project, position: i64) -> Result<()> {
+    storage::ensure_free(tx, &project.id)?;
+    tx.execute(
+        "INSERT INTO projects (id, name, position) VALUES (?1, ?2, ?3)",
+        params![project.id, project.name, position],
+    )?;
+    for (position, folder) in project.folders.iter().enumerate() {
+        insert_folder(tx, &project.id, folder, position as i64)?;
+    }
+    Ok(())
+}
+
+fn insert_folder(tx: &Transaction, project_id: &str, folder: &Folder, position: i64) -> Result<()> {
+    storage::ensure_free(tx, &folder.id)?;
+    tx.execute(
+        "INSERT INTO folders (id, project_id, name, position) VALUES (?1, ?2, ?3, ?4)",
+        params![folder.id, project_id, folder.name, position],
+    )?;
+    for note in &folder.notes {
+        storage::insert_note(tx, &folder.id, note)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,123 @@
+// src-tauri/src/trash/tests.rs
+
+use std::io::Cursor;
+
+use image::{ImageFormat, RgbaImage};
+
+use super::*;
+use crate::backup;
+use crate::models::ContentType;
+
+fn note(id: &str, text: &str) -> NoteItem {
+    NoteItem {
+        id: id.into(),
+        text: text.into(),
+        created_at: 1_700_000_000_000,
+        updated_at: 1_700_000_000_000,
+        utc_offset: 0,
+        content_type: ContentType::Text,
+        tags: vec!["keep".into()],
+        image_data: None,
+        language: None,
+        is_favorite: false,
+        is_secret: false,
+    }
+}
+
+fn folder_ids(db: &Database) -> Vec<String> {
+    db.load().unwrap().projects[0].folders.iter().map(|f| f.id.clone()).collect()
+}
+
+fn png() -> Vec<u8> {
+    let mut out = Cursor::new(Vec::new());
+    RgbaImage::from_pixel(2, 2, image::Rgba([0, 0, 255, 255])).write_to(&mut out, ImageFormat::Png).unwrap();
+    out.into_inner()
+}
+
+/// A project with a folder and a note, all in the trash.
+fn trashed_project(db: &Database, note: NoteItem) {
+    db.add_project("p2", "Screenshots").unwrap();
+    db.add_folder("p2", "f2", "All").unwrap();
+    db.add_note("f2", &note).unwrap();
+    delete(db, TrashKind::Project, "p2").unwrap();
+}
+
+#[test]
+fn a_deleted_folder_comes_back_in_place_with_its_notes() {
+    let db = Database::open_in_memory().unwrap();
+    db.add_folder("p1", "f2", "Drafts").unwrap();
+    db.add_folder("p1", "f3", "Done").unwrap();
+    db.add_note("f2", &note("n1", "\n  first line\nsecond")).unwrap();
+    db.add_note("f2", &note("n2", "another")).unwrap();
+    let before = db.load().unwrap().projects;
+
+    let entry = delete(&db, TrashKind::Folder, "f2").unwrap();
+    assert_eq!(folder_ids(&db), ["f1", "f3"]);
+    assert_eq!(list(&db).unwrap(), [entry]);
+
+    delete(&db, TrashKind::Note, "n1").unwrap_err();
+    restore(&db, "f2", None).unwrap();
+    assert_eq!(db.load().unwrap().projects, before);
+    assert!(list(&db).unwrap().is_empty());
+}
+
+#[test]
+fn a_note_whose_folder_is_gone_needs_another_one() {
+    let db = Database::open_in_memory().unwrap();
+    db.add_folder("p1", "f2", "Other").unwrap();
+    db.add_note("f1", &note("n1", "orphan")).unwrap();
+    db.add_note("f2", &note("n2", "already here")).unwrap();
+    assert_eq!(delete(&db, TrashKind::Note, "n1").unwrap().title, "orphan");
+    delete(&db, TrashKind::Folder, "f1").unwrap();
+
+    let entries = list(&db).unwrap();
+    assert_eq!(entries.iter().map(|e| (e.id.as_str(), e.parent_exists)).collect::<Vec<_>>(), [
+        ("f1", true),
+        ("n1", false)
+    ]);
+    assert!(matches!(restore(&db, "n1", None), Err(Error::InvalidInput(_))));
+    assert!(matches!(restore(&db, "n1", Some("f9")), Err(Error::NotFound { .. })));
+
+    restore(&db, "n1", Some("f2")).unwrap();
+    let notes = &db.load().unwrap().projects[0].folders[0].notes;
+    assert_eq!(notes.iter().map(|n| n.id.as_str()).collect::<Vec<_>>(), ["n2", "n1"]);
+    assert_eq!(notes[1].tags, ["keep"]);
+}
+
+#[test]
+fn trashed_entries_hold_their_ids_and_images_until_purged() {
+    let dir = tempfile::tempdir().unwrap();
+    let db = Database::open_in_memory().unwrap();
+    let images = ImageStore::new(db.clone(), dir.path().join("images"));
+    let stored = images.put(&png()).unwrap();
+    trashed_project(&db, NoteItem { image_data: Some(stored.file_name.clone()), ..note("n1", "") });
+
+    for id in ["p2", "f2", "n1"] {
+        assert!(matches!(db.add_project(id, "Clash"), Err(Error::InvalidInput(_))), "{id} is not reserved");
+    }
+    assert!(images.get(&stored.file_name).is_ok());
+
+    assert_eq!(purge(&db, &images, None).unwrap(), 1);
+    assert!(images.get(&stored.file_name).is_err(), "image outlived its last reference");
+    for id in ["p2", "f2", "n1"] {
+        db.add_project(id, "Reused").unwrap();
+    }
+    assert!(matches!(purge(&db, &images, Some("p2")), Err(Error::NotFound { .. })));
+}
+
+#[test]
+fn a_merge_cannot_take_an_id_from_inside_the_trash() {
+    let db = Database::open_in_memory().unwrap();
+    trashed_project(&db, note("n1", "original"));
+    let laptop = Database::open_in_memory().unwrap();
+    laptop.add_note("f1", &note("n1", "from the laptop")).unwrap();
+
+    let report = backup::merge(&db, &backup::export(&laptop, false).unwrap(), false).unwrap();
+    assert_eq!(report.conflicts.len(), 1);
+    assert_ne!(report.conflicts[0].new_id, "n1");
+
+    restore(&db, "p2", None).unwrap();
+    let projects = db.load().unwrap().projects;
+    assert_eq!(projects[1].folders[0].notes[0].text, "original");
+    assert_eq!(projects[0].folders[0].notes[0].text, "from the laptop");
+}
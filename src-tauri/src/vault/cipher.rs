@@ -0,0 +1,142 @@
+// src-tauri/src/vault/cipher.rs
+
+//! Key derivation and sealing.
+//!
+//! Data is sealed with a random 256-bit data key. The passphrase only
+//! unwraps that key, so changing it never re-encrypts the store. Sealed
+//! bytes are `MAGIC || nonce || ciphertext`; XChaCha20's 192-bit nonces are
+//! random without any risk of reuse.
+
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, KeyInit, OsRng};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
+
+use crate::error::{Error, Result};
+
+/// Marks sealed bytes, so plaintext image files left over from before
+/// encryption was switched on are still recognised as such.
+const MAGIC: &[u8; 4] = b"CMv1";
+const NONCE_LEN: usize = 24;
+const KEY_LEN: usize = 32;
+const SALT_LEN: usize = 16;
+
+/// Argon2id cost. Stored next to the wrapped key so it can be raised later
+/// without locking out existing stores.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KdfParams {
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+}
+
+impl Default for KdfParams {
+    /// About half a second on a laptop: slow to brute-force, quick to unlock.
+    fn default() -> Self {
+        Self { memory_kib: 64 * 1024, iterations: 3, parallelism: 1 }
+    }
+}
+
+/// What the `meta` table holds about encryption. Nothing in here is secret.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyFile {
+    pub kdf: KdfParams,
+    /// Hex.
+    pub salt: String,
+    /// The data key sealed with the passphrase-derived key, hex.
+    pub wrapped_key: String,
+}
+
+/// The key everything is sealed with. Wiped from memory when dropped.
+#[derive(Clone)]
+pub struct DataKey(Zeroizing<[u8; KEY_LEN]>);
+
+impl DataKey {
+    pub fn generate() -> Self {
+        let mut key = Zeroizing::new([0u8; KEY_LEN]);
+        OsRng.fill_bytes(key.as_mut());
+        Self(key)
+    }
+
+    pub fn seal(&self, plaintext: &[u8]) -> Vec<u8> {
+        seal_with(&self.0, plaintext)
+    }
+
+    pub fn open(&self, sealed: &[u8]) -> Result<Vec<u8>> {
+        open_with(&self.0, sealed).ok_or_else(|| Error::Crypto("data could not be decrypted".into()))
+    }
+
+    /// Wraps this key under `passphrase` with fresh salt.
+    pub fn wrap(&self, passphrase: &str, kdf: KdfParams) -> Result<KeyFile> {
+        let mut salt = [0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        let wrapping_key = derive(passphrase, &salt, kdf)?;
+        Ok(KeyFile { kdf, salt: to_hex(&salt), wrapped_key: to_hex(&seal_with(&wrapping_key, self.0.as_ref())) })
+    }
+
+    /// Recovers the key from `file`. A wrong passphrase fails authentication.
+    pub fn unwrap(file: &KeyFile, passphrase: &str) -> Result<Self> {
+        let salt = from_hex(&file.salt)?;
+        let wrapped = from_hex(&file.wrapped_key)?;
+        let wrapping_key = derive(passphrase, &salt, file.kdf)?;
+        let bytes = Zeroizing::new(open_with(&wrapping_key, &wrapped).ok_or(Error::WrongPassphrase)?);
+        let key: [u8; KEY_LEN] =
+            bytes.as_slice().try_into().map_err(|_| Error::Crypto("wrapped key has the wrong length".into()))?;
+        Ok(Self(Zeroizing::new(key)))
+    }
+}
+
+pub fn is_sealed(bytes: &[u8]) -> bool {
+    bytes.starts_with(MAGIC)
+}
+
+fn derive(passphrase: &str, salt: &[u8], kdf: KdfParams) -> Result<Zeroizing<[u8; KEY_LEN]>> {
+    let params = Params::new(kdf.memory_kib, kdf.iterations, kdf.parallelism, Some(KEY_LEN))
+        .map_err(|err| Error::Crypto(format!("invalid key derivation parameters: {err}")))?;
+    let mut key = Zeroizing::new([0u8; KEY_LEN]);
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), salt, key.as_mut())
+        .map_err(|err| Error::Crypto(format!("key derivation failed: {err}")))?;
+    Ok(key)
+}
+
+fn seal_with(key: &[u8; KEY_LEN], plaintext: &[u8]) -> Vec<u8> {
+    let cipher = XChaCha20Poly1305::new(key.into());
+    let mut nonce = [0u8; NONCE_LEN];
+    OsRng.fill_bytes(&mut nonce);
+    let ciphertext = cipher.encrypt(XNonce::from_slice(&nonce), plaintext).expect("in-memory encryption cannot fail");
+
+    let mut sealed = Vec::with_capacity(MAGIC.len() + NONCE_LEN + ciphertext.len());
+    sealed.extend_from_slice(MAGIC);
+    sealed.extend_from_slice(&nonce);
+    sealed.extend_from_slice(&ciphertext);
+    sealed
+}
+
+fn open_with(key: &[u8; KEY_LEN], sealed: &[u8]) -> Option<Vec<u8>> {
+    let body = sealed.strip_prefix(MAGIC)?;
+    if body.len() < NONCE_LEN {
+        return None;
+    }
+    let (nonce, ciphertext) = body.split_at(NONCE_LEN);
+    XChaCha20Poly1305::new(key.into()).decrypt(XNonce::from_slice(nonce), ciphertext).ok()
+}
+
+pub(super) fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+pub(super) fn from_hex(hex: &str) -> Result<Vec<u8>> {
+    let invalid = || Error::Crypto("stored key is corrupt".into());
+    if !hex.len().is_multiple_of(2) {
+        return Err(invalid());
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|at| hex.get(at..at + 2).and_then(|pair| u8::from_str_radix(pair, 16).ok()).ok_or_else(invalid))
+        .collect()
+}
@@ -0,0 +1,69 @@
+// src-tauri/src/vault/idle.rs
+
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use super::{auto_lock_minutes, lock, status, VaultStatus};
+use crate::error::Result;
+use crate::storage::Database;
+
+/// How late an idle lock may come, at worst.
+const CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Background thread that locks the store once nobody has touched the app
+/// for the configured number of minutes. Stops when dropped.
+pub struct IdleLock {
+    stop: Option<Sender<()>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl IdleLock {
+    /// `on_locked` is called each time the store was locked for being idle.
+    pub fn spawn<F>(db: Database, on_locked: F) -> Self
+    where
+        F: Fn() + Send + 'static,
+    {
+        let (stop, stopped) = mpsc::channel::<()>();
+
+        let handle = thread::Builder::new()
+            .name("idle-lock".into())
+            .spawn(move || loop {
+                match lock_if_idle(&db) {
+                    Ok(true) => on_locked(),
+                    Ok(false) => {}
+                    Err(err) => eprintln!("[vault] idle check failed: {err}"),
+                }
+                // Dropping the sender wakes us up immediately.
+                if stopped.recv_timeout(CHECK_INTERVAL) != Err(RecvTimeoutError::Timeout) {
+                    break;
+                }
+            })
+            .expect("failed to spawn idle lock thread");
+
+        Self { stop: Some(stop), handle: Some(handle) }
+    }
+}
+
+impl Drop for IdleLock {
+    fn drop(&mut self) {
+        drop(self.stop.take());
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+pub(super) fn lock_if_idle(db: &Database) -> Result<bool> {
+    if status(db) != VaultStatus::Unlocked {
+        return Ok(false);
+    }
+    let Some(minutes) = auto_lock_minutes(db)? else {
+        return Ok(false);
+    };
+    if db.keyring().idle_for() < Duration::from_secs(u64::from(minutes) * 60) {
+        return Ok(false);
+    }
+    lock(db)?;
+    Ok(true)
+}
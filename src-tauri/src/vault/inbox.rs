@@ -0,0 +1,150 @@
+// src-tauri/src/vault/inbox.rs
+
+//! Where captures go while the store is locked.
+//!
+//! The inbox has its own X25519 key pair. The public key sits in `meta` in
+//! the clear, so anyone can seal an entry to it; the secret key is sealed
+//! with the data key, so entries can only be opened after `unlock`. Entries
+//! are libsodium-style sealed boxes: not even the capture that wrote one can
+//! read it back.
+
+use chacha20poly1305::aead::OsRng;
+use chrono::Local;
+use crypto_box::{PublicKey, SecretKey};
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use zeroize::Zeroizing;
+
+use super::cipher::{from_hex, to_hex, DataKey};
+use crate::dates::upgrade_record;
+use crate::error::{Error, Result};
+use crate::ids;
+use crate::images::ImageStore;
+use crate::models::HistoryItem;
+use crate::retention;
+use crate::storage::{self, Database};
+
+const INBOX_KEY: &str = "inbox_key";
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct InboxKey {
+    /// Hex.
+    public_key: String,
+    /// Sealed with the data key, hex.
+    secret_key: String,
+}
+
+/// Creates the key pair unless there already is one. Stores encrypted
+/// before the inbox existed get theirs on the next unlock.
+pub(super) fn ensure_key(db: &Database, data_key: &DataKey) -> Result<()> {
+    if db.meta(INBOX_KEY)?.is_some() {
+        return Ok(());
+    }
+    let secret = SecretKey::generate(&mut OsRng);
+    let key = InboxKey {
+        public_key: to_hex(secret.public_key().as_bytes()),
+        secret_key: to_hex(&data_key.seal(Zeroizing::new(secret.to_bytes()).as_ref())),
+    };
+    let json = serde_json::to_string(&key)?;
+    db.write(|tx| storage::set_meta(tx, INBOX_KEY, &json))
+}
+
+pub(super) fn delete_key(tx: &rusqlite::Transaction) -> Result<()> {
+    tx.execute("DELETE FROM meta WHERE key = ?1", [INBOX_KEY])?;
+    Ok(())
+}
+
+/// Parks a capture made while locked. `image` holds the PNG bytes of an
+/// image capture, which can't go into the image store yet.
+pub fn push(db: &Database, item: &HistoryItem, image: Option<&[u8]>) -> Result<()> {
+    let key = load_key(db)?;
+    let public_key = PublicKey::from_bytes(to_key_bytes(&from_hex(&key.public_key)?)?);
+    let seal = |bytes: &[u8]| {
+        public_key.seal(&mut OsRng, bytes).map_err(|_| Error::Crypto("could not seal inbox entry".into()))
+    };
+
+    let sealed_item = seal(&serde_json::to_vec(item)?)?;
+    let sealed_image = image.map(seal).transpose()?;
+    db.write_while_locked(|tx| {
+        tx.execute("INSERT INTO inbox (item, image) VALUES (?1, ?2)", params![sealed_item, sealed_image])?;
+        Ok(())
+    })
+}
+
+/// Moves every entry into history, oldest first, and returns what was
+/// added. Entries that fail to open are dropped rather than blocking the
+/// rest forever; an entry whose image can't be stored yet stays for the
+/// next unlock.
+pub(super) fn drain(db: &Database, images: &ImageStore, data_key: &DataKey) -> Result<Vec<HistoryItem>> {
+    let Some(json) = db.meta(INBOX_KEY)? else {
+        return Ok(Vec::new());
+    };
+    let key: InboxKey = serde_json::from_str(&json)?;
+    let secret_bytes = Zeroizing::new(data_key.open(&from_hex(&key.secret_key)?)?);
+    let secret = SecretKey::from_bytes(to_key_bytes(&secret_bytes)?);
+    let open = |sealed: &[u8]| secret.unseal(sealed).map_err(|_| Error::Crypto("inbox entry could not be opened".into()));
+
+    let entries: Vec<(i64, Vec<u8>, Option<Vec<u8>>)> = db.read(|conn| {
+        let mut stmt = conn.prepare("SELECT seq, item, image FROM inbox ORDER BY seq")?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    })?;
+
+    let max_items = retention::policy(db)?.max_items;
+    let mut added = Vec::new();
+    for (seq, sealed_item, sealed_image) in entries {
+        match open_entry(&open, &sealed_item, sealed_image.as_deref()) {
+            Ok((mut item, image)) => {
+                if let Some(image) = image {
+                    match images.put(&image) {
+                        Ok(stored) => item.image_data = Some(stored.file_name),
+                        Err(err) => {
+                            eprintln!("[vault] keeping inbox entry {seq} until its image can be stored: {err}");
+                            continue;
+                        }
+                    }
+                }
+                // Entries sealed before ids were UUIDs may clash with a note or folder.
+                if db.read(|conn| storage::id_taken(conn, &item.id))? {
+                    item.id = ids::new_id();
+                }
+                if db.push_history_item(&item, max_items)? {
+                    added.push(item);
+                }
+            }
+            Err(err) => eprintln!("[vault] dropping inbox entry {seq}: {err}"),
+        }
+        db.write(|tx| {
+            tx.execute("DELETE FROM inbox WHERE seq = ?1", [seq])?;
+            Ok(())
+        })?;
+    }
+    Ok(added)
+}
+
+/// The item and, for image captures, the image bytes.
+fn open_entry(
+    open: &impl Fn(&[u8]) -> Result<Vec<u8>>,
+    sealed_item: &[u8],
+    sealed_image: Option<&[u8]>,
+) -> Result<(HistoryItem, Option<Vec<u8>>)> {
+    let mut item: Value = serde_json::from_slice(&open(sealed_item)?)?;
+    // Sealed before timestamps existed.
+    upgrade_record(&mut item, Local::now());
+    let item: HistoryItem = serde_json::from_value(item)?;
+    let image = sealed_image.map(open).transpose()?;
+    Ok((item, image))
+}
+
+fn load_key(db: &Database) -> Result<InboxKey> {
+    match db.meta(INBOX_KEY)? {
+        Some(json) => Ok(serde_json::from_str(&json)?),
+        None => Err(Error::Crypto("the inbox has no key yet; unlock once to create it".into())),
+    }
+}
+
+fn to_key_bytes(bytes: &[u8]) -> Result<[u8; 32]> {
+    bytes.try_into().map_err(|_| Error::Crypto("stored inbox key has the wrong length".into()))
+}
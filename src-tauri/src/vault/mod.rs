@@ -0,0 +1,279 @@
+// src-tauri/src/vault/mod.rs
+
+//! Optional encryption at rest.
+//!
+//! When on, the text of every history item and note and every image and
+//! thumbnail file is sealed with XChaCha20-Poly1305 under a random data key.
+//! That key is wrapped with an Argon2id hash of the user's passphrase and
+//! kept in `meta`; it is only ever unwrapped into memory.
+//!
+//! While locked the key is gone and `Database` refuses to read or write
+//! anything but settings, so nothing new reaches the disk unencrypted.
+//! Captures keep arriving in the write-only `inbox` and move into history on
+//! the next unlock. The store locks itself after a few idle minutes.
+//! Sealed items are never added to the full-text index; fuzzy and regex
+//! search still see them.
+//!
+//! Not covered: the webview's IndexedDB, where older versions kept
+//! everything. The frontend deletes it after migrating, except when some
+//! records failed to migrate.
+
+mod cipher;
+mod idle;
+pub mod inbox;
+mod sql;
+
+#[cfg(test)]
+mod tests;
+
+use std::borrow::Cow;
+use std::sync::{Arc, Mutex, RwLock, RwLockReadGuard};
+use std::time::{Duration, Instant};
+
+use rusqlite::Connection;
+use serde::Serialize;
+
+use crate::error::{Error, Result};
+use crate::images::{ImageStore, ThumbnailCache};
+use crate::models::HistoryItem;
+use crate::storage::{self, Database};
+
+pub use idle::IdleLock;
+pub(crate) use sql::{register as register_functions, take_error as take_function_error};
+
+use cipher::{is_sealed, DataKey, KdfParams, KeyFile};
+
+/// Emitted when the store locks itself after being idle.
+pub const LOCKED_EVENT: &str = "vault://locked";
+
+const KEY_FILE: &str = "encryption";
+const AUTO_LOCK_KEY: &str = "auto_lock_minutes";
+const DEFAULT_AUTO_LOCK_MINUTES: u32 = 10;
+const MIN_PASSPHRASE_CHARS: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum VaultStatus {
+    /// Encryption is switched off; everything is stored in plaintext.
+    Off,
+    /// Encrypted, and the key is not in memory.
+    Locked,
+    Unlocked,
+}
+
+#[derive(Default)]
+enum KeyState {
+    #[default]
+    Off,
+    Locked,
+    Unlocked(DataKey),
+}
+
+/// The in-memory half of the vault, shared by every `Database` clone and
+/// the SQL functions registered on its connection.
+#[derive(Clone, Default)]
+pub struct Keyring {
+    state: Arc<RwLock<KeyState>>,
+    last_activity: Arc<Mutex<Option<Instant>>>,
+}
+
+impl Keyring {
+    /// Starts locked if the store was encrypted when the app last ran.
+    pub(crate) fn load(conn: &Connection) -> Result<Self> {
+        let state = match storage::get_meta(conn, KEY_FILE)? {
+            Some(_) => KeyState::Locked,
+            None => KeyState::Off,
+        };
+        Ok(Self { state: Arc::new(RwLock::new(state)), last_activity: Arc::default() })
+    }
+
+    pub fn status(&self) -> VaultStatus {
+        match &*self.read() {
+            KeyState::Off => VaultStatus::Off,
+            KeyState::Locked => VaultStatus::Locked,
+            KeyState::Unlocked(_) => VaultStatus::Unlocked,
+        }
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.status() == VaultStatus::Locked
+    }
+
+    /// `bytes` as they should be written to disk right now.
+    pub fn seal<'a>(&self, bytes: &'a [u8]) -> Result<Cow<'a, [u8]>> {
+        match &*self.read() {
+            KeyState::Off => Ok(Cow::Borrowed(bytes)),
+            KeyState::Locked => Err(Error::Locked),
+            KeyState::Unlocked(key) => Ok(Cow::Owned(key.seal(bytes))),
+        }
+    }
+
+    /// Plaintext of `bytes` read from disk, which may predate encryption.
+    pub fn open<'a>(&self, bytes: &'a [u8]) -> Result<Cow<'a, [u8]>> {
+        if !is_sealed(bytes) {
+            return Ok(Cow::Borrowed(bytes));
+        }
+        match &*self.read() {
+            KeyState::Unlocked(key) => Ok(Cow::Owned(key.open(bytes)?)),
+            KeyState::Locked => Err(Error::Locked),
+            KeyState::Off => Err(Error::Crypto("found encrypted data, but encryption is off".into())),
+        }
+    }
+
+    /// Records that the user did something, postponing the idle lock.
+    pub fn touch(&self) {
+        *self.last_activity.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(Instant::now());
+    }
+
+    pub fn idle_for(&self) -> Duration {
+        let last_activity = *self.last_activity.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        last_activity.map_or(Duration::MAX, |at| at.elapsed())
+    }
+
+    /// Unlocking counts as activity.
+    fn set(&self, state: KeyState) {
+        if matches!(state, KeyState::Unlocked(_)) {
+            self.touch();
+        }
+        *self.state.write().unwrap_or_else(|poisoned| poisoned.into_inner()) = state;
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, KeyState> {
+        self.state.read().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+pub fn status(db: &Database) -> VaultStatus {
+    db.keyring().status()
+}
+
+/// Encrypts everything stored so far and everything stored from now on.
+/// The store is left unlocked.
+pub fn enable(db: &Database, images: &ImageStore, thumbs: &ThumbnailCache, passphrase: &str) -> Result<()> {
+    enable_with(db, images, thumbs, passphrase, KdfParams::default())
+}
+
+fn enable_with(
+    db: &Database,
+    images: &ImageStore,
+    thumbs: &ThumbnailCache,
+    passphrase: &str,
+    kdf: KdfParams,
+) -> Result<()> {
+    if status(db) != VaultStatus::Off {
+        return Err(Error::InvalidInput("encryption is already on".into()));
+    }
+    check_passphrase(passphrase)?;
+
+    let key = DataKey::generate();
+    let key_file = serde_json::to_string(&key.wrap(passphrase, kdf)?)?;
+
+    // `seal()` in SQL encrypts only once the key is in place.
+    db.keyring().set(KeyState::Unlocked(key.clone()));
+    let sealed = db.write(|tx| {
+        storage::set_meta(tx, KEY_FILE, &key_file)?;
+        tx.execute_batch(
+            "UPDATE history SET text = seal(text) WHERE typeof(text) = 'text';
+             UPDATE notes SET text = seal(text) WHERE typeof(text) = 'text';
+             UPDATE trash SET title = seal(title), payload = seal(payload) WHERE typeof(payload) = 'text';",
+        )?;
+        // Deleted index rows only drop out of the FTS segments on a merge;
+        // until then every sealed word is still readable there.
+        tx.execute("INSERT INTO search_index(search_index) VALUES('rebuild')", [])?;
+        Ok(())
+    });
+    if let Err(err) = sealed {
+        db.keyring().set(KeyState::Off);
+        return Err(err);
+    }
+    inbox::ensure_key(db, &key)?;
+
+    images.adopt_legacy_files()?;
+    images.rewrite_all(|bytes| Ok((!is_sealed(bytes)).then(|| key.seal(bytes))))?;
+    thumbs.clear()?;
+    // The old plaintext still sits in free pages and the WAL until the
+    // database is rewritten.
+    db.compact()
+}
+
+/// Decrypts everything back, including anything still in the inbox, and
+/// forgets the key.
+pub fn disable(db: &Database, images: &ImageStore, thumbs: &ThumbnailCache, passphrase: &str) -> Result<()> {
+    let key = DataKey::unwrap(&key_file(db)?, passphrase)?;
+    db.keyring().set(KeyState::Unlocked(key.clone()));
+    inbox::drain(db, images, &key)?;
+
+    // Files first: if this fails half-way, the store stays encrypted and
+    // plaintext files are still read fine.
+    images.rewrite_all(|bytes| if is_sealed(bytes) { key.open(bytes).map(Some) } else { Ok(None) })?;
+    db.write(|tx| {
+        tx.execute_batch(
+            "UPDATE history SET text = unseal(text) WHERE typeof(text) = 'blob';
+             UPDATE notes SET text = unseal(text) WHERE typeof(text) = 'blob';
+             UPDATE trash SET title = unseal(title), payload = unseal(payload) WHERE typeof(payload) = 'blob';",
+        )?;
+        tx.execute("DELETE FROM meta WHERE key = ?1", [KEY_FILE])?;
+        inbox::delete_key(tx)
+    })?;
+    db.keyring().set(KeyState::Off);
+    thumbs.clear()
+}
+
+/// Loads the key and moves whatever was captured while locked into
+/// history. Returns the items that were added.
+pub fn unlock(db: &Database, images: &ImageStore, passphrase: &str) -> Result<Vec<HistoryItem>> {
+    let key = DataKey::unwrap(&key_file(db)?, passphrase)?;
+    db.keyring().set(KeyState::Unlocked(key.clone()));
+    inbox::ensure_key(db, &key)?;
+    inbox::drain(db, images, &key)
+}
+
+/// Drops the key from memory. Reading or writing anything but settings
+/// fails with `Error::Locked` until `unlock`.
+pub fn lock(db: &Database) -> Result<()> {
+    if status(db) == VaultStatus::Off {
+        return Err(Error::InvalidInput("encryption is off".into()));
+    }
+    db.keyring().set(KeyState::Locked);
+    Ok(())
+}
+
+/// Re-wraps the data key; nothing else needs re-encrypting.
+pub fn change_passphrase(db: &Database, current: &str, new: &str) -> Result<()> {
+    check_passphrase(new)?;
+    let old_file = key_file(db)?;
+    let key = DataKey::unwrap(&old_file, current)?;
+    let new_file = serde_json::to_string(&key.wrap(new, old_file.kdf)?)?;
+    db.keyring().set(KeyState::Unlocked(key));
+    db.write(|tx| storage::set_meta(tx, KEY_FILE, &new_file))
+}
+
+/// Minutes without activity before the store locks itself; `None` never.
+pub fn auto_lock_minutes(db: &Database) -> Result<Option<u32>> {
+    match db.meta(AUTO_LOCK_KEY)? {
+        Some(json) => Ok(serde_json::from_str(&json)?),
+        None => Ok(Some(DEFAULT_AUTO_LOCK_MINUTES)),
+    }
+}
+
+pub fn set_auto_lock_minutes(db: &Database, minutes: Option<u32>) -> Result<()> {
+    if minutes == Some(0) {
+        return Err(Error::InvalidInput("auto-lock needs at least 1 minute".into()));
+    }
+    let json = serde_json::to_string(&minutes)?;
+    db.write(|tx| storage::set_meta(tx, AUTO_LOCK_KEY, &json))
+}
+
+fn check_passphrase(passphrase: &str) -> Result<()> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_CHARS {
+        return Err(Error::InvalidInput(format!("passphrase must be at least {MIN_PASSPHRASE_CHARS} characters")));
+    }
+    Ok(())
+}
+
+fn key_file(db: &Database) -> Result<KeyFile> {
+    match db.meta(KEY_FILE)? {
+        Some(json) => Ok(serde_json::from_str(&json)?),
+        None => Err(Error::InvalidInput("encryption is off".into())),
+    }
+}
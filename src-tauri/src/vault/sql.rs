@@ -0,0 +1,73 @@
+// src-tauri/src/vault/sql.rs
+
+//! `seal(x)` and `unseal(x)` for SQL, so queries read and write item text
+//! the same way whether encryption is on or not.
+//!
+//! Sealed text is stored as a BLOB in the same `text` column. Plain TEXT
+//! passes through `unseal` untouched, and `seal` leaves TEXT as is while
+//! encryption is off. Both fail with `Error::Locked` while locked.
+
+use std::borrow::Cow;
+use std::cell::RefCell;
+
+use rusqlite::functions::FunctionFlags;
+use rusqlite::types::{Value, ValueRef};
+use rusqlite::Connection;
+
+use super::Keyring;
+use crate::error::Error;
+
+pub fn register(conn: &Connection, keyring: &Keyring) -> rusqlite::Result<()> {
+    // DIRECTONLY keeps them out of triggers and views; neither is deterministic.
+    let flags = FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DIRECTONLY;
+
+    let sealer = keyring.clone();
+    conn.create_scalar_function("seal", 1, flags, move |ctx| match ctx.get_raw(0) {
+        ValueRef::Text(text) => match sealer.seal(text).map_err(user_error)? {
+            Cow::Borrowed(_) => Ok(Value::from(ctx.get_raw(0))),
+            Cow::Owned(sealed) => Ok(Value::Blob(sealed)),
+        },
+        other => Ok(Value::from(other)),
+    })?;
+
+    let opener = keyring.clone();
+    conn.create_scalar_function("unseal", 1, flags, move |ctx| match ctx.get_raw(0) {
+        ValueRef::Blob(sealed) => {
+            let text = opener.open(sealed).map_err(user_error)?.into_owned();
+            let text = String::from_utf8(text).map_err(|_| user_error(Error::Crypto("sealed text is not UTF-8".into())))?;
+            Ok(Value::Text(text))
+        }
+        other => Ok(Value::from(other)),
+    })?;
+
+    Ok(())
+}
+
+thread_local! {
+    /// The error a function failed with on this thread. SQLite hands back
+    /// only its message, so the typed value waits here for `take_error`.
+    static FAILED: RefCell<Option<Error>> = const { RefCell::new(None) };
+}
+
+fn user_error(err: Error) -> rusqlite::Error {
+    let message = err.to_string();
+    FAILED.with(|failed| *failed.borrow_mut() = Some(err));
+    rusqlite::Error::UserFunctionError(message.into())
+}
+
+/// The typed error behind a statement that failed because `seal` or
+/// `unseal` did. Functions run on the thread stepping the statement, so the
+/// failure recorded here is the one that aborted it if the messages agree.
+pub(crate) fn take_error(err: &rusqlite::Error) -> Option<Error> {
+    let rusqlite::Error::SqliteFailure(_, Some(message)) = err else {
+        return None;
+    };
+    FAILED.with(|failed| {
+        let mut failed = failed.borrow_mut();
+        if failed.as_ref().is_some_and(|err| err.to_string() == *message) {
+            failed.take()
+        } else {
+            None
+        }
+    })
+}
@@ -0,0 +1,365 @@
+// src-tauri/src/vault/tests.rs
+
+//! Encryption against a real database file, checking what actually lands
+//! on disk.
+
+use std::fs;
+use std::io::Cursor;
+use std::path::Path;
+
+use image::{ImageFormat, RgbaImage};
+
+use super::*;
+use crate::models::{ContentType, HistoryItem, NoteItem};
+use crate::search::{self, SearchScope};
+use crate::storage::DB_FILE_NAME;
+
+const PASSPHRASE: &str = "correct horse battery";
+const MARKER: &str = "confidential-marker-7f3a";
+
+/// Argon2 at full cost takes seconds in a debug build.
+const CHEAP_KDF: KdfParams = KdfParams { memory_kib: 256, iterations: 1, parallelism: 1 };
+
+struct Store {
+    db: Database,
+    images: ImageStore,
+    thumbs: ThumbnailCache,
+    dir: tempfile::TempDir,
+}
+
+impl Store {
+    fn new() -> Self {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::open(&dir.path().join(DB_FILE_NAME)).unwrap();
+        let images = ImageStore::new(db.clone(), dir.path().join("images"));
+        let thumbs = ThumbnailCache::new(images.clone(), dir.path().join("thumbs"));
+        Self { db, images, thumbs, dir }
+    }
+
+    fn enable(&self) {
+        enable_with(&self.db, &self.images, &self.thumbs, PASSPHRASE, CHEAP_KDF).unwrap();
+    }
+
+    fn push(&self, id: &str, text: &str) {
+        let item = HistoryItem {
+            id: id.into(),
+            text: text.into(),
+            created_at: 1_700_000_000_000,
+            updated_at: 1_700_000_000_000,
+            utc_offset: 0,
+            content_type: ContentType::Text,
+            image_data: None,
+            language: None,
+            is_favorite: false,
+            is_secret: false,
+            expires_at: None,
+        };
+        assert!(self.db.push_history_item(&item, None).unwrap());
+    }
+
+    fn add_note(&self, id: &str, text: &str) {
+        let note = NoteItem {
+            id: id.into(),
+            text: text.into(),
+            created_at: 1_700_000_000_000,
+            updated_at: 1_700_000_000_000,
+            utc_offset: 0,
+            content_type: ContentType::Text,
+            tags: vec!["work".into()],
+            image_data: None,
+            language: None,
+            is_favorite: false,
+            is_secret: false,
+        };
+        self.db.add_note("f1", &note).unwrap();
+    }
+
+    /// Every byte under the data directory: database, WAL, images, thumbs.
+    fn bytes_on_disk(&self) -> Vec<u8> {
+        fn collect(dir: &Path, out: &mut Vec<u8>) {
+            for entry in fs::read_dir(dir).unwrap() {
+                let path = entry.unwrap().path();
+                if path.is_dir() {
+                    collect(&path, out);
+                } else {
+                    out.extend(fs::read(&path).unwrap());
+                }
+            }
+        }
+        let mut out = Vec::new();
+        collect(self.dir.path(), &mut out);
+        out
+    }
+
+    fn mentions(&self, needle: &[u8]) -> bool {
+        self.bytes_on_disk().windows(needle.len()).any(|window| window == needle)
+    }
+}
+
+fn png() -> Vec<u8> {
+    let mut out = Cursor::new(Vec::new());
+    RgbaImage::from_pixel(4, 4, image::Rgba([200, 10, 10, 255])).write_to(&mut out, ImageFormat::Png).unwrap();
+    out.into_inner()
+}
+
+#[test]
+fn enabling_seals_existing_and_new_data() {
+    let store = Store::new();
+    store.push("1", &format!("history {MARKER}"));
+    store.add_note("n1", &format!("note {MARKER}"));
+    let stored = store.images.put(&png()).unwrap();
+    store.thumbs.get(&stored.file_name).unwrap();
+    assert!(store.mentions(MARKER.as_bytes()));
+
+    store.enable();
+    store.push("2", &format!("later {MARKER}"));
+    store.db.compact().unwrap();
+
+    assert!(!store.mentions(MARKER.as_bytes()), "plaintext text left on disk");
+    assert!(!store.mentions(b"\x89PNG"), "plaintext image or thumbnail left on disk");
+
+    let data = store.db.load().unwrap();
+    assert_eq!(data.history.iter().map(|h| h.text.as_str()).collect::<Vec<_>>(), [
+        format!("later {MARKER}"),
+        format!("history {MARKER}")
+    ]);
+    assert_eq!(data.projects[0].folders[0].notes[0].text, format!("note {MARKER}"));
+    assert_eq!(store.images.get(&stored.file_name).unwrap(), png());
+    assert!(store.thumbs.get(&stored.file_name).unwrap().starts_with(b"\x89PNG"));
+}
+
+#[test]
+fn enabling_scrubs_sealed_words_from_the_search_index() {
+    // A single token, so the index would hold it verbatim.
+    const WORD: &str = "quokkaxyz";
+    let store = Store::new();
+    store.push("1", &format!("history {WORD}"));
+    store.add_note("n1", &format!("note {WORD}"));
+    assert!(store.mentions(WORD.as_bytes()));
+
+    store.enable();
+
+    assert!(!store.mentions(WORD.as_bytes()), "sealed word left in the search index");
+}
+
+#[test]
+fn only_registered_images_are_sealed() {
+    let store = Store::new();
+    let stored = store.images.put(&png()).unwrap();
+    let foreign = store.dir.path().join("images").join("readme.txt");
+    fs::write(&foreign, b"not ours").unwrap();
+
+    store.enable();
+
+    assert_eq!(fs::read(&foreign).unwrap(), b"not ours");
+    assert!(cipher::is_sealed(&fs::read(store.images.path_of(&stored.file_name).unwrap()).unwrap()));
+}
+
+#[test]
+fn legacy_images_in_use_are_sealed_too() {
+    let store = Store::new();
+    let legacy = "img_1700000000000_5f0c6a1e-4c2b-4c55-9a43-0a4b1f0e9d11.png";
+    fs::create_dir_all(store.dir.path().join("images")).unwrap();
+    fs::write(store.images.path_of(legacy).unwrap(), png()).unwrap();
+    let item = HistoryItem {
+        id: "1".into(),
+        text: "Image".into(),
+        created_at: 1_700_000_000_000,
+        updated_at: 1_700_000_000_000,
+        utc_offset: 0,
+        content_type: ContentType::Image,
+        image_data: Some(legacy.into()),
+        language: None,
+        is_favorite: false,
+        is_secret: false,
+        expires_at: None,
+    };
+    store.db.push_history_item(&item, None).unwrap();
+
+    store.enable();
+
+    assert!(!store.mentions(b"\x89PNG"), "legacy image left in plaintext");
+    let file_name = store.db.history_item("1").unwrap().image_data.unwrap();
+    assert_eq!(store.images.get(&file_name).unwrap(), png());
+}
+
+#[test]
+fn locked_store_refuses_reads_and_writes() {
+    let store = Store::new();
+    let stored = store.images.put(&png()).unwrap();
+    store.push("1", MARKER);
+    store.enable();
+
+    lock(&store.db).unwrap();
+    assert_eq!(status(&store.db), VaultStatus::Locked);
+    assert!(matches!(store.db.load(), Err(Error::Locked)));
+    assert!(matches!(store.images.get(&stored.file_name), Err(Error::Locked)));
+    assert!(matches!(store.images.put(&png()), Err(Error::Locked)));
+    assert!(matches!(store.db.clear_history(), Err(Error::Locked)));
+
+    assert!(matches!(unlock(&store.db, &store.images, "not the passphrase"), Err(Error::WrongPassphrase)));
+    unlock(&store.db, &store.images, PASSPHRASE).unwrap();
+    assert_eq!(store.db.load().unwrap().history[0].text, MARKER);
+}
+
+#[test]
+fn reopening_an_encrypted_store_starts_locked() {
+    let store = Store::new();
+    store.push("1", MARKER);
+    store.enable();
+
+    let reopened = Database::open(&store.dir.path().join(DB_FILE_NAME)).unwrap();
+    assert_eq!(status(&reopened), VaultStatus::Locked);
+    unlock(&reopened, &store.images, PASSPHRASE).unwrap();
+    assert_eq!(reopened.load().unwrap().history[0].text, MARKER);
+}
+
+#[test]
+fn sealed_items_are_found_by_fuzzy_search_only() {
+    let store = Store::new();
+    store.enable();
+    store.push("1", &format!("deploy {MARKER}"));
+
+    assert!(search::search(&store.db, "deploy", SearchScope::All, 10).unwrap().is_empty());
+    assert_eq!(search::fuzzy_search(&store.db, "deploy", 10).unwrap()[0].id, "1");
+}
+
+#[test]
+fn disabling_restores_plaintext() {
+    let store = Store::new();
+    let stored = store.images.put(&png()).unwrap();
+    store.enable();
+    store.push("1", &format!("deploy {MARKER}"));
+    store.add_note("n1", "sealed note");
+
+    let disable = |passphrase| disable(&store.db, &store.images, &store.thumbs, passphrase);
+    assert!(matches!(disable("not the passphrase"), Err(Error::WrongPassphrase)));
+    disable(PASSPHRASE).unwrap();
+
+    assert_eq!(status(&store.db), VaultStatus::Off);
+    assert_eq!(fs::read(store.images.path_of(&stored.file_name).unwrap()).unwrap(), png());
+    assert_eq!(search::search(&store.db, "deploy", SearchScope::All, 10).unwrap()[0].id, "1");
+    assert_eq!(store.db.load().unwrap().projects[0].folders[0].notes[0].text, "sealed note");
+}
+
+#[test]
+fn changing_the_passphrase_keeps_the_data_key() {
+    let store = Store::new();
+    store.push("1", MARKER);
+    store.enable();
+
+    assert!(matches!(change_passphrase(&store.db, "wrong one!", "new passphrase"), Err(Error::WrongPassphrase)));
+    change_passphrase(&store.db, PASSPHRASE, "new passphrase").unwrap();
+    lock(&store.db).unwrap();
+
+    assert!(matches!(unlock(&store.db, &store.images, PASSPHRASE), Err(Error::WrongPassphrase)));
+    unlock(&store.db, &store.images, "new passphrase").unwrap();
+    assert_eq!(store.db.load().unwrap().history[0].text, MARKER);
+}
+
+#[test]
+fn short_passphrases_are_rejected() {
+    let store = Store::new();
+    let result = enable_with(&store.db, &store.images, &store.thumbs, "short", CHEAP_KDF);
+    assert!(matches!(result, Err(Error::InvalidInput(_))));
+    assert_eq!(status(&store.db), VaultStatus::Off);
+}
+
+#[test]
+fn captures_while_locked_wait_in_the_inbox() {
+    let store = Store::new();
+    store.enable();
+    lock(&store.db).unwrap();
+
+    let text = HistoryItem {
+        id: "1".into(),
+        text: format!("copied {MARKER}"),
+        created_at: 1_700_000_000_000,
+        updated_at: 1_700_000_000_000,
+        utc_offset: 0,
+        content_type: ContentType::Text,
+        image_data: None,
+        language: None,
+        is_favorite: false,
+        is_secret: false,
+        expires_at: None,
+    };
+    let image = HistoryItem { id: "2".into(), text: "Image".into(), content_type: ContentType::Image, ..text.clone() };
+    inbox::push(&store.db, &text, None).unwrap();
+    inbox::push(&store.db, &image, Some(&png())).unwrap();
+    store.db.compact().unwrap();
+
+    assert!(!store.mentions(MARKER.as_bytes()), "inbox entry written in plaintext");
+    assert!(!store.mentions(b"\x89PNG"), "inbox image written in plaintext");
+
+    let added = unlock(&store.db, &store.images, PASSPHRASE).unwrap();
+    assert_eq!(added.iter().map(|h| h.id.as_str()).collect::<Vec<_>>(), ["1", "2"]);
+    let history = store.db.load().unwrap().history;
+    assert_eq!(history[1].text, format!("copied {MARKER}"));
+    assert_eq!(store.images.get(history[0].image_data.as_deref().unwrap()).unwrap(), png());
+
+    // Drained entries are gone.
+    lock(&store.db).unwrap();
+    assert!(unlock(&store.db, &store.images, PASSPHRASE).unwrap().is_empty());
+}
+
+#[test]
+fn inbox_images_wait_until_they_can_be_stored() {
+    let store = Store::new();
+    store.enable();
+    lock(&store.db).unwrap();
+
+    let image = HistoryItem {
+        id: "1".into(),
+        text: "Image".into(),
+        created_at: 1_700_000_000_000,
+        updated_at: 1_700_000_000_000,
+        utc_offset: 0,
+        content_type: ContentType::Image,
+        image_data: None,
+        language: None,
+        is_favorite: false,
+        is_secret: false,
+        expires_at: None,
+    };
+    inbox::push(&store.db, &image, Some(&png())).unwrap();
+
+    // A file where the image directory should be makes every `put` fail.
+    let images_dir = store.dir.path().join("images");
+    fs::write(&images_dir, b"in the way").unwrap();
+    assert!(unlock(&store.db, &store.images, PASSPHRASE).unwrap().is_empty());
+
+    fs::remove_file(&images_dir).unwrap();
+    lock(&store.db).unwrap();
+    let added = unlock(&store.db, &store.images, PASSPHRASE).unwrap();
+    assert_eq!(added.len(), 1);
+    assert_eq!(store.images.get(added[0].image_data.as_deref().unwrap()).unwrap(), png());
+}
+
+#[test]
+fn errors_from_sql_functions_keep_their_type() {
+    let store = Store::new();
+    store.push("1", MARKER);
+    store.enable();
+
+    // Not sealed and not UTF-8 either.
+    store.db.write(|tx| Ok(tx.execute("UPDATE history SET text = X'FF'", [])?)).unwrap();
+    assert!(matches!(store.db.load(), Err(Error::Crypto(_))));
+}
+
+#[test]
+fn idle_store_locks_itself() {
+    let store = Store::new();
+    store.enable();
+
+    assert!(!idle::lock_if_idle(&store.db).unwrap(), "locked right after unlocking");
+
+    store.db.keyring().last_activity.lock().unwrap().take();
+    set_auto_lock_minutes(&store.db, None).unwrap();
+    assert!(!idle::lock_if_idle(&store.db).unwrap(), "locked with auto-lock off");
+
+    set_auto_lock_minutes(&store.db, Some(1)).unwrap();
+    assert!(idle::lock_if_idle(&store.db).unwrap());
+    assert_eq!(status(&store.db), VaultStatus::Locked);
+    assert!(matches!(set_auto_lock_minutes(&store.db, Some(0)), Err(Error::InvalidInput(_))));
+}
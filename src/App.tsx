@@ -11,10 +11,12 @@ import { HistoryView } from './components/HistoryView';
 import { ProjectView } from './components/ProjectView';
 import { ProjectsOverview } from './components/ProjectsOverview';
 import { CommandMenu } from './components/CommandMenu';
+import { LockScreen } from './components/LockScreen';
 
 import { useStore } from './store';
-import { useClipboardMonitor } from './hooks/useClipboardMonitor';
+import { useClipboardEvents } from './hooks/useClipboardEvents';
 import { useAppLogic } from './hooks/useAppLogic';
+import { useAppLock } from './hooks/useAppLock';
 
 export default function App() {
   // --- Core Logic Hook ---
@@ -54,9 +56,11 @@ export default function App() {
   // --- Initialization ---
   const { initData, deleteHistoryItem, restoreHistoryItem, toggleFavorite, history: fullHistory } = useStore();
 
-  // Initialize Data & Monitor Clipboard
-  useEffect(() => { initData(); }, [initData]);
-  useClipboardMonitor();
+  // Initialize Data & Follow Native Capture
+  const { isLocked, unlock } = useAppLock();
+  // A locked store refuses reads, so load again once it's unlocked
+  useEffect(() => { if (!isLocked) initData(); }, [isLocked, initData]);
+  useClipboardEvents();
 
   // Undo delete handler
   const handleDeleteWithUndo = useCallback((id: string) => {
@@ -86,6 +90,9 @@ export default function App() {
   // Better: Pass it to ProjectView, let it scroll, then call onScrolled() callback?
   // Or just pass the ID and rely on a useEffect in ProjectView that fires when ID changes.
 
+  // 🔐 Store is encrypted and locked: nothing to show until unlocked
+  if (isLocked) return <LockScreen onUnlock={unlock} />;
+
   return (
     <div className="flex flex-col h-screen bg-bg text-text-primary overflow-hidden font-sans selection:bg-accent-blue/30">
       {/* New Navigation Header */}
@@ -168,29 +175,12 @@ export default function App() {
               }
             }}
             onOpenCreateFolder={() => setModalConfig({ ...modalConfig, isOpen: true, type: 'createFolder', title: 'Новая папка', placeholder: 'Название папки', initialValue: '', targetId: { projectId: currentProject.id } })}
-            onReorderFolders={(newFolders) => {
-              const newProject = { ...currentProject, folders: newFolders };
-              const newProjects = projects.map(p => p.id === currentProject.id ? newProject : p);
-              useStore.getState().setProjects(newProjects);
-            }}
+            onReorderFolders={(newFolders) => useStore.getState().reorderFolders(currentProject.id, newFolders)}
             onToggleFolder={toggleFolder}
             onCopyFolder={handleCopyFolderContent}
             onRenameFolder={(id, name) => setModalConfig({ ...modalConfig, isOpen: true, type: 'renameFolder', title: 'Переименовать папку', placeholder: 'Новое название', initialValue: name, targetId: { projectId: currentProject.id, folderId: id } })}
             onDeleteFolder={(id, name) => handleDeleteFolder(currentProject.id, id, name)}
-            onReorderNotes={(folderId, newNotes) => {
-              // Update notes order in store
-              const pIndex = projects.findIndex(p => p.id === currentProject.id);
-              if (pIndex === -1) return;
-              // Deep clone to avoid mutation issues if strict
-              // Assuming useStore setup allows direct replacement via map
-              useStore.getState().setProjects(projects.map(p => {
-                if (p.id !== currentProject.id) return p;
-                return {
-                  ...p,
-                  folders: p.folders.map(f => f.id === folderId ? { ...f, notes: newNotes } : f)
-                };
-              }));
-            }}
+            onReorderNotes={(folderId, newNotes) => useStore.getState().reorderNotes(currentProject.id, folderId, newNotes)}
             onDeleteNote={(folderId, noteId) => useStore.getState().deleteNote(currentProject.id, folderId, noteId)}
             onEditNote={(folderId, noteId) => {
               const note = currentProject.folders.find(f => f.id === folderId)?.notes.find(n => n.id === noteId);
@@ -6,10 +6,10 @@ import { AlignJustify, Pencil, Trash2, ExternalLink, Check, Star } from 'lucide-
 // 🛠️ Tauri Plugins
 import clipboard from 'tauri-plugin-clipboard-api';
 import * as opener from '@tauri-apps/plugin-opener';
-import { readFile, BaseDirectory } from '@tauri-apps/plugin-fs';
+import { invoke, convertFileSrc } from '@tauri-apps/api/core';
 
 import { cn, TypeBadge } from './ui-elements';
-import { arrayBufferToBase64 } from '../lib/utils';
+import { arrayBufferToBase64, formatCreated } from '../lib/utils';
 import { ErrorBoundary, CompactErrorFallback } from './ErrorBoundary';
 import { HighlightText } from './HighlightText';
 import { NoteItem } from '../types';
@@ -45,62 +45,24 @@ interface CardProps {
 }
 
 // 🔥 OPTIMIZED: Image Preview
+// Cards show a small backend-rendered thumbnail (thumb:// scheme), never the full image
 const ImagePreview = ({ fileName, compact }: { fileName: string, compact?: boolean }) => {
-  const [src, setSrc] = useState<string>("");
   const [error, setError] = useState(false);
   const [loading, setLoading] = useState(true);
 
-  useEffect(() => {
-    let isActive = true;
-    let currentObjectUrl: string | null = null;
-
-    const loadImage = async () => {
-      // 1. Fast path for old data types
-      if (fileName.startsWith('data:') || fileName.startsWith('http')) {
-        if (isActive) {
-          setSrc(fileName);
-          setLoading(false);
-        }
-        return;
-      }
-
-      try {
-        setLoading(true);
-        // 2. Read file from disk
-        const imageBytes = await readFile(`images/${fileName}`, {
-          baseDir: BaseDirectory.AppLocalData,
-        });
-
-        // 3. Check active status before processing
-        if (!isActive) return;
-
-        const blob = new Blob([imageBytes]);
-        const url = URL.createObjectURL(blob);
-        currentObjectUrl = url;
-
-        if (isActive) {
-          setSrc(url);
-          setLoading(false);
-        } else {
-          URL.revokeObjectURL(url);
-        }
-      } catch {
-        if (isActive) {
-          setError(true);
-          setLoading(false);
-        }
-      }
-    };
+  // Old data types are shown as they are
+  const isInline = fileName.startsWith('data:') || fileName.startsWith('http');
+  const src = isInline ? fileName : convertFileSrc(fileName, 'thumb');
 
-    loadImage();
+  useEffect(() => {
+    setError(false);
+    setLoading(!isInline);
+  }, [fileName, isInline]);
 
-    return () => {
-      isActive = false;
-      if (currentObjectUrl) {
-        URL.revokeObjectURL(currentObjectUrl);
-      }
-    };
-  }, [fileName]);
+  const imageEvents = {
+    onLoad: () => setLoading(false),
+    onError: () => { setError(true); setLoading(false); },
+  };
 
   if (error) {
     if (compact) return <div className="text-[9px] text-red-400">Error</div>;
@@ -115,7 +77,7 @@ const ImagePreview = ({ fileName, compact }: { fileName: string, compact?: boole
   if (compact) {
     return (
       <div className={cn("relative w-full h-full transition-opacity duration-300", loading ? "opacity-50" : "opacity-100")}>
-        <img src={src || undefined} alt="Preview" className="w-full h-full object-cover block" />
+        <img src={src} alt="Preview" className="w-full h-full object-cover block" {...imageEvents} />
       </div>
     );
   }
@@ -124,7 +86,7 @@ const ImagePreview = ({ fileName, compact }: { fileName: string, compact?: boole
   return (
     <div className={cn("relative mt-2 rounded-lg overflow-hidden border border-white/5 bg-black/20 transition-opacity duration-300", loading ? "opacity-50" : "opacity-100")}>
       {loading && <div className="absolute inset-0 flex items-center justify-center text-xs text-text-secondary">Загрузка...</div>}
-      <img src={src || undefined} alt="Preview" className="w-full h-auto max-h-[200px] object-contain block" />
+      <img src={src} alt="Preview" className="w-full h-auto max-h-[200px] object-contain block" {...imageEvents} />
     </div>
   );
 };
@@ -151,10 +113,9 @@ function useCardActions(item: NoteItem) {
           await clipboard.writeImageBase64(base64);
         } else {
           try {
-            const imageBytes = await readFile(`images/${item.imageData}`, {
-              baseDir: BaseDirectory.AppLocalData,
-            });
-            const base64String = arrayBufferToBase64(imageBytes);
+            // Through the backend: with encryption on, the file on disk is sealed
+            const imageBytes = await invoke<ArrayBuffer>('get_image', { fileName: item.imageData });
+            const base64String = arrayBufferToBase64(new Uint8Array(imageBytes));
             await clipboard.writeImageBase64(base64String);
           } catch {
             await clipboard.writeText(`[Image Missing: ${item.imageData}]`);
@@ -291,7 +252,7 @@ const CardHeader = memo(({ item }: { item: NoteItem }) => (
   <div className="flex items-center gap-2 overflow-hidden w-full pr-8">
     <TypeBadge type={item.contentType} text={item.text} />
     <span className="text-[10px] text-text-secondary/60 font-medium truncate">
-      {item.date}
+      {formatCreated(item)}
     </span>
   </div>
 ));
@@ -387,7 +348,7 @@ export const DraggableCard = memo(({ item, onEdit, onContextMenu }: CardProps) =
               </div>
             )}
 
-            <span className="text-[10px] text-text-secondary/40 shrink-0 ml-auto">{item.date}</span>
+            <span className="text-[10px] text-text-secondary/40 shrink-0 ml-auto">{formatCreated(item)}</span>
           </div>
 
           <CardContent item={item} compact={true} />
@@ -4,9 +4,10 @@ import { motion, AnimatePresence } from 'framer-motion';
 import { Search, Clipboard, Briefcase, CornerDownLeft, X, Image as ImageIcon, Folder, Plus, Trash2, Download } from 'lucide-react';
 import { useStore } from '../store';
 import clipboard from 'tauri-plugin-clipboard-api';
-import { readFile, BaseDirectory } from '@tauri-apps/plugin-fs';
+import { invoke } from '@tauri-apps/api/core';
 import { toast } from 'sonner';
-import { cn, arrayBufferToBase64 } from '../lib/utils';
+import { cn, formatCreated } from '../lib/utils';
+import { downloadBackup } from '../hooks/useImportExport';
 import { APP_CONFIG } from '../constants';
 import type { Project, HistoryItem, Folder as FolderType } from '../types';
 
@@ -94,24 +95,9 @@ export function CommandMenu({ onNavigateProject, onNavigateFolder, onClose }: Co
       description: 'Скачать резервную копию',
       icon: 'download',
       action: () => {
-        const state = useStore.getState();
-        const exportData = {
-          version: 1,
-          exportDate: new Date().toISOString(),
-          projects: state.projects,
-          history: state.history,
-          globalTags: state.globalTags
-        };
-        const blob = new Blob([JSON.stringify(exportData, null, 2)], { type: 'application/json' });
-        const url = URL.createObjectURL(blob);
-        const a = document.createElement('a');
-        a.href = url;
-        a.download = `clipka-backup-${new Date().toISOString().split('T')[0]}.json`;
-        document.body.appendChild(a);
-        a.click();
-        document.body.removeChild(a);
-        URL.revokeObjectURL(url);
-        toast.success('Данные экспортированы');
+        downloadBackup()
+          .then(() => toast.success('Данные экспортированы'))
+          .catch(() => toast.error('Ошибка при экспорте данных'));
         setIsOpen(false);
       }
     }
@@ -204,11 +190,8 @@ export function CommandMenu({ onNavigateProject, onNavigateFolder, onClose }: Co
             const base64 = historyItem.imageData.replace(/^data:image\/[a-z]+;base64,/, "");
             await clipboard.writeImageBase64(base64);
           } else {
-            const imageBytes = await readFile(`images/${historyItem.imageData}`, {
-              baseDir: BaseDirectory.AppLocalData,
-            });
-            const base64String = arrayBufferToBase64(imageBytes);
-            await clipboard.writeImageBase64(base64String);
+            // The backend reads (and, if needed, decrypts) the stored image itself
+            await invoke('copy_history_item', { id: historyItem.id });
           }
           toast.success('Изображение скопировано');
         } else {
@@ -254,7 +237,7 @@ export function CommandMenu({ onNavigateProject, onNavigateFolder, onClose }: Co
     if (item.type === 'action') return item.description;
     if (item.type === 'folder') return `Папка в ${item.projectName} • ${item.data.notes.length} заметок`;
     if (item.type === 'project') return `Проект • ${item.data.folders.length} папок`;
-    if (item.type === 'history') return `История • ${item.data.date} • ${item.data.contentType}`;
+    if (item.type === 'history') return `История • ${formatCreated(item.data)} • ${item.data.contentType}`;
     return '';
   };
 
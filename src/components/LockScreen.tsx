@@ -0,0 +1,59 @@
+// src/components/LockScreen.tsx
+import { useState, useEffect, useRef } from 'react';
+import { Lock } from 'lucide-react';
+
+interface LockScreenProps {
+  onUnlock: (passphrase: string) => Promise<string | null>;
+}
+
+export function LockScreen({ onUnlock }: LockScreenProps) {
+  const [passphrase, setPassphrase] = useState('');
+  const [error, setError] = useState<string | null>(null);
+  const [isBusy, setIsBusy] = useState(false);
+  const inputRef = useRef<HTMLInputElement>(null);
+
+  useEffect(() => { inputRef.current?.focus(); }, []);
+
+  const handleSubmit = async (e: React.FormEvent) => {
+    e.preventDefault();
+    if (!passphrase || isBusy) return;
+
+    setIsBusy(true);
+    const message = await onUnlock(passphrase);
+    setIsBusy(false);
+
+    if (message) {
+      setError(message);
+      setPassphrase('');
+      inputRef.current?.focus();
+    }
+  };
+
+  return (
+    <div className="flex flex-col items-center justify-center h-screen bg-bg text-text-primary font-sans">
+      <form onSubmit={handleSubmit} className="flex flex-col items-center gap-4 w-72">
+        <Lock size={32} className="text-accent-blue" />
+        <h1 className="text-lg font-semibold">Хранилище заблокировано</h1>
+        <p className="text-sm text-text-secondary text-center">
+          Новые копирования сохраняются и появятся в истории после разблокировки
+        </p>
+        <input
+          ref={inputRef}
+          type="password"
+          value={passphrase}
+          onChange={e => { setPassphrase(e.target.value); setError(null); }}
+          placeholder="Пароль"
+          className="w-full px-3 py-2 rounded-lg bg-white/5 border border-white/10 outline-none focus:border-accent-blue"
+        />
+        {error && <span className="text-sm text-red-400">{error}</span>}
+        <button
+          type="submit"
+          disabled={!passphrase || isBusy}
+          className="w-full py-2 rounded-lg bg-accent-blue text-white font-medium disabled:opacity-50"
+        >
+          {isBusy ? 'Проверка...' : 'Разблокировать'}
+        </button>
+      </form>
+    </div>
+  );
+}
@@ -5,6 +5,7 @@ import { Pin, PinOff, Clipboard, Folder, ArrowLeft, Settings, Download, Upload }
 import { cn } from './ui-elements';
 import { Project } from '../types';
 import { useStore } from '../store';
+import { downloadBackup, mergeFile } from '../hooks/useImportExport';
 import { toast } from 'sonner';
 
 type ViewType = 'history' | 'project' | 'favorites' | 'images' | 'links' | 'code';
@@ -96,27 +97,13 @@ export function NavigationHeader({
     };
 
     // Export data as JSON
-    const handleExport = () => {
-        const state = useStore.getState();
-        const exportData = {
-            version: 1,
-            exportDate: new Date().toISOString(),
-            projects: state.projects,
-            history: state.history,
-            globalTags: state.globalTags
-        };
-
-        const blob = new Blob([JSON.stringify(exportData, null, 2)], { type: 'application/json' });
-        const url = URL.createObjectURL(blob);
-        const a = document.createElement('a');
-        a.href = url;
-        a.download = `clipka-backup-${new Date().toISOString().split('T')[0]}.json`;
-        document.body.appendChild(a);
-        a.click();
-        document.body.removeChild(a);
-        URL.revokeObjectURL(url);
-
-        toast.success('Данные экспортированы');
+    const handleExport = async () => {
+        try {
+            await downloadBackup();
+            toast.success('Данные экспортированы');
+        } catch {
+            toast.error('Ошибка при экспорте данных');
+        }
         setIsSettingsOpen(false);
     };
 
@@ -125,45 +112,29 @@ export function NavigationHeader({
         fileInputRef.current?.click();
     };
 
+    // Merge a backup into the current data (never replaces anything)
     const handleFileChange = async (e: React.ChangeEvent<HTMLInputElement>) => {
         const file = e.target.files?.[0];
         if (!file) return;
+        e.target.value = '';
 
         try {
-            const text = await file.text();
-            const data = JSON.parse(text);
-
-            if (!data.projects || !data.history) {
-                throw new Error('Invalid backup file');
+            const preview = await mergeFile(file, true);
+            const { added, skipped, conflicts } = preview;
+            const summary =
+                `Будет добавлено: ${added.projects} проектов, ${added.folders} папок, ${added.notes} заметок, ${added.history} записей, ${added.tags} тегов\n` +
+                `Уже есть: ${skipped.notes + skipped.history} записей\n` +
+                `Конфликтов id: ${conflicts.length}`;
+
+            if (confirm(`${summary}\n\nОбъединить?`)) {
+                await mergeFile(file, false);
+                await useStore.getState().reload();
+                toast.success(`Импортировано: ${added.projects} проектов, ${added.notes} заметок, ${added.history} записей`);
             }
-
-            const state = useStore.getState();
-
-            // Merge or replace? Let's merge to avoid data loss
-            const existingProjectIds = new Set(state.projects.map(p => p.id));
-            const newProjects = data.projects.filter((p: Project) => !existingProjectIds.has(p.id));
-
-            const existingHistoryIds = new Set(state.history.map(h => h.id));
-            const newHistory = data.history.filter((h: { id: string }) => !existingHistoryIds.has(h.id));
-
-            if (newProjects.length > 0) {
-                state.setProjects([...state.projects, ...newProjects]);
-            }
-            if (newHistory.length > 0) {
-                state.setHistory([...state.history, ...newHistory]);
-            }
-            if (data.globalTags) {
-                const allTags = [...new Set([...state.globalTags, ...data.globalTags])];
-                state.setGlobalTags(allTags);
-            }
-
-            toast.success(`Импортировано: ${newProjects.length} проектов, ${newHistory.length} записей`);
         } catch {
             toast.error('Ошибка при импорте данных');
         }
 
-        // Reset file input
-        e.target.value = '';
         setIsSettingsOpen(false);
     };
 
@@ -247,7 +218,7 @@ export function NavigationHeader({
                 <input
                     ref={fileInputRef}
                     type="file"
-                    accept=".json"
+                    accept=".json,.zip"
                     onChange={handleFileChange}
                     className="hidden"
                 />
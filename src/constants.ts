@@ -5,8 +5,8 @@ export const APP_CONFIG = {
   DB_NAME: 'ClipboardManagerDB',
   STORE_NAME: 'app_store',
   MAX_HISTORY_ITEMS: 50,
-  CLIPBOARD_POLL_INTERVAL: 1000,
   SAVE_DEBOUNCE_DELAY: 500,
+  SEARCH_DEBOUNCE_DELAY: 150,
   DEFAULT_FOLDER_NAME: 'General',
   KEYBOARD_SHORTCUTS: {
     SEARCH: 'k',
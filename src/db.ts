@@ -1,5 +1,8 @@
 // src/db.ts
+import { invoke } from '@tauri-apps/api/core';
 import { APP_CONFIG } from './constants';
+import { logger } from './lib/logger';
+import type { MigrationReport } from './types';
 
 const DB_CONFIG = {
     DB_NAME: APP_CONFIG.DB_NAME,
@@ -41,46 +44,6 @@ const openDB = (): Promise<IDBDatabase> => {
     return dbPromise;
 };
 
-/**
- * Saves data to IndexedDB.
- * @param key The storage key
- * @param data The data to save
- * @param onError Optional callback for handling errors without try/catch
- */
-export const saveToDB = async (
-    key: string, 
-    data: any, 
-    onError?: (err: any) => void
-): Promise<void> => {
-    try {
-        const db = await openDB();
-        
-        return new Promise<void>((resolve, reject) => {
-            const tx = db.transaction(DB_CONFIG.STORE_NAME, 'readwrite');
-            const store = tx.objectStore(DB_CONFIG.STORE_NAME);
-            const request = store.put(data, key);
-
-            tx.oncomplete = () => resolve();
-            
-            tx.onerror = () => {
-                const err = tx.error;
-                if (onError) onError(err);
-                reject(err);
-            };
-
-            // Capture specific request errors if they don't bubble to tx
-            request.onerror = () => {
-                const err = request.error;
-                if (onError) onError(err);
-                // We don't reject here because tx.onerror will likely fire too
-            };
-        });
-    } catch (err) {
-        if (onError) onError(err);
-        throw err;
-    }
-};
-
 /**
  * Loads data from IndexedDB.
  * @param key The storage key
@@ -104,4 +67,45 @@ export const loadFromDB = async <T = any>(key: string): Promise<T | null> => {
         console.error(`DB Load Error (${key}):`, err);
         return null;
     }
-};
\ No newline at end of file
+};
+
+/**
+ * Deletes the IndexedDB database. It holds everything in plaintext, which
+ * encryption at rest can't reach.
+ */
+const deleteDB = async (): Promise<void> => {
+    if (dbPromise) {
+        (await dbPromise).close();
+        dbPromise = null;
+    }
+    return new Promise((resolve, reject) => {
+        const request = indexedDB.deleteDatabase(DB_CONFIG.DB_NAME);
+        request.onsuccess = () => resolve();
+        request.onerror = () => reject(request.error);
+    });
+};
+
+/**
+ * Hands what older versions kept in IndexedDB over to the backend, once.
+ * The backend remembers that it happened, so later starts skip the read.
+ * The old copy is deleted afterwards unless some records failed to
+ * migrate; those stay in IndexedDB, unencrypted, for manual recovery.
+ */
+export const migrateLegacyData = async (): Promise<void> => {
+    if (await invoke<boolean>('legacy_migration_status')) return;
+
+    const [history, projects, globalTags] = await Promise.all([
+        loadFromDB('history'),
+        loadFromDB('projects'),
+        loadFromDB('globalTags')
+    ]);
+    const payload = { history: history || [], projects: projects || [], globalTags: globalTags || [] };
+    const report = await invoke<MigrationReport>('migrate_legacy_data', { payload });
+
+    for (const failure of report.failures) {
+        logger.warn(`Legacy record ${failure.path} was not migrated:`, failure.error);
+    }
+    if (report.failures.length === 0) {
+        await deleteDB().catch((err) => logger.error("Failed to delete the legacy IndexedDB:", err));
+    }
+};
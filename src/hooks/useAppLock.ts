@@ -0,0 +1,55 @@
+// src/hooks/useAppLock.ts
+import { useState, useEffect, useCallback } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { VaultStatus } from '../types';
+
+// Backend auto-lock only needs to know we're alive, not every keystroke
+const ACTIVITY_THROTTLE_MS = 30_000;
+
+export function useAppLock() {
+  const [status, setStatus] = useState<VaultStatus>('off');
+
+  useEffect(() => {
+    invoke<VaultStatus>('encryption_status').then(setStatus).catch(() => setStatus('off'));
+
+    const unlisten = listen('vault://locked', () => setStatus('locked'));
+    return () => { unlisten.then(fn => fn()); };
+  }, []);
+
+  // 🕒 Report user activity so the store doesn't lock while in use
+  useEffect(() => {
+    if (status !== 'unlocked') return;
+
+    let lastReport = 0;
+    const onActivity = () => {
+      const now = Date.now();
+      if (now - lastReport < ACTIVITY_THROTTLE_MS) return;
+      lastReport = now;
+      invoke('report_activity').catch(() => {});
+    };
+
+    const events = ['keydown', 'mousedown', 'wheel'] as const;
+    events.forEach(e => window.addEventListener(e, onActivity, { passive: true }));
+    return () => events.forEach(e => window.removeEventListener(e, onActivity));
+  }, [status]);
+
+  /** Resolves to an error message, or null on success. */
+  const unlock = useCallback(async (passphrase: string): Promise<string | null> => {
+    try {
+      await invoke('unlock_store', { passphrase });
+      setStatus('unlocked');
+      return null;
+    } catch (err) {
+      const kind = (err as { kind?: string })?.kind;
+      return kind === 'wrongPassphrase' ? 'Неверный пароль' : 'Не удалось разблокировать';
+    }
+  }, []);
+
+  const lock = useCallback(async () => {
+    await invoke('lock_store');
+    setStatus('locked');
+  }, []);
+
+  return { status, isLocked: status === 'locked', unlock, lock };
+}
@@ -1,9 +1,10 @@
 import { useState, useRef, useEffect, useCallback, useMemo } from 'react';
+import { invoke } from '@tauri-apps/api/core';
 import { getCurrentWindow } from '@tauri-apps/api/window';
 import { toast } from 'sonner';
 import clipboard from 'tauri-plugin-clipboard-api';
 import { useStore } from '../store';
-import { Folder } from '../types';
+import { Folder, HistoryItem, QueryError, SearchHit } from '../types';
 import { APP_CONFIG } from '../constants';
 import { logger } from '../lib/logger';
 import { useKeyboardShortcuts } from './useKeyboardShortcuts';
@@ -20,6 +21,24 @@ export type ModalConfig = {
     targetId?: { projectId?: string, folderId?: string, noteId?: string };
 };
 
+/** Ids of everything the current search query matched. */
+type SearchMatches = {
+    history: Set<string>;
+    notes: Set<string>;
+    /** Folders whose own name matched; those with matching notes show up anyway. */
+    folders: Set<string>;
+};
+
+function collectMatches(hits: SearchHit[]): SearchMatches {
+    const matches: SearchMatches = { history: new Set(), notes: new Set(), folders: new Set() };
+    for (const hit of hits) {
+        if (hit.kind === 'history') matches.history.add(hit.id);
+        else if (hit.kind === 'note') matches.notes.add(hit.id);
+        else if (hit.kind === 'folder') matches.folders.add(hit.id);
+    }
+    return matches;
+}
+
 export function useAppLogic() {
     // --- Store ---
     const {
@@ -94,84 +113,82 @@ export function useAppLogic() {
         };
     }, [history]);
 
-    const filteredHistory = useMemo(() => {
-        // If searching, we search GLOBAL history? Or just "Recent"?
-        // Original logic: "if (!search) return history".
-        // Let's keep it simple.
-        if (!search) return history;
-        const lowerSearch = search.toLowerCase();
-        return history.filter(h => h.text.toLowerCase().includes(lowerSearch));
-    }, [history, search]);
+    // The search box speaks the backend query language (`type:url tag:work -draft`).
+    // `filter_items` returns what matches; the lists below keep only those ids.
+    const [matches, setMatches] = useState<SearchMatches | null>(null);
+    const [searchError, setSearchError] = useState<QueryError | null>(null);
 
-    const filteredSmartCollection = useMemo(() => {
-        const lowerSearch = search.toLowerCase();
-        // Return a function or object that gives filtered results based on activeView?
-        // No, better to let the View component handle filtering or do it here if we pass "activeCollection" type.
-        // Actually, let's just return the raw smartCollections and let the UI filter if needed, 
-        // OR return pre-filtered.
-        // If I type "foo", 'images' collection should show images matching "foo".
+    useEffect(() => {
+        if (!search.trim()) {
+            setMatches(null);
+            setSearchError(null);
+            return;
+        }
+        let cancelled = false;
+        const timer = setTimeout(async () => {
+            try {
+                const hits = await invoke<SearchHit[]>('filter_items', { query: search });
+                if (cancelled) return;
+                setMatches(collectMatches(hits));
+                setSearchError(null);
+            } catch (err) {
+                if (cancelled) return;
+                // Usually mid-typing (`tag:`, an open quote): keep the last results.
+                if ((err as QueryError)?.kind === 'invalidQuery') setSearchError(err as QueryError);
+                else logger.error('Search failed:', err);
+            }
+        }, APP_CONFIG.SEARCH_DEBOUNCE_DELAY);
+        return () => {
+            cancelled = true;
+            clearTimeout(timer);
+        };
+    }, [search, history, projects]);
 
-        if (!search) return smartCollections;
+    const filteredHistory = useMemo(() => {
+        if (!matches) return history;
+        return history.filter(h => matches.history.has(h.id));
+    }, [history, matches]);
 
+    const filteredSmartCollection = useMemo(() => {
+        if (!matches) return smartCollections;
+        const keep = (items: HistoryItem[]) => items.filter(h => matches.history.has(h.id));
         return {
-            favorites: smartCollections.favorites.filter(h => h.text.toLowerCase().includes(lowerSearch)),
-            images: smartCollections.images.filter(h => h.text.toLowerCase().includes(lowerSearch)),
-            links: smartCollections.links.filter(h => h.text.toLowerCase().includes(lowerSearch)),
-            code: smartCollections.code.filter(h => h.text.toLowerCase().includes(lowerSearch)),
+            favorites: keep(smartCollections.favorites),
+            images: keep(smartCollections.images),
+            links: keep(smartCollections.links),
+            code: keep(smartCollections.code),
         };
-    }, [smartCollections, search]);
+    }, [smartCollections, matches]);
 
     const filteredFolders = useMemo(() => {
         if (!currentProject) return [];
-        if (!search) return currentProject.folders;
-
-        const lowerSearch = search.toLowerCase();
+        if (!matches) return currentProject.folders;
 
         return currentProject.folders.map(folder => {
-            const filteredNotes = folder.notes.filter(note => {
-                const matchText = note.text.toLowerCase().includes(lowerSearch);
-                const matchTag = (note.tags || []).some(tag => tag.toLowerCase().includes(lowerSearch));
-                return matchText || matchTag;
-            });
-
-            if (filteredNotes.length > 0 || folder.name.toLowerCase().includes(lowerSearch)) {
-                return { ...folder, notes: filteredNotes };
+            const notes = folder.notes.filter(note => matches.notes.has(note.id));
+            if (notes.length > 0 || matches.folders.has(folder.id)) {
+                return { ...folder, notes };
             }
             return null;
         }).filter((f): f is Folder => f !== null);
-    }, [currentProject, search]);
+    }, [currentProject, matches]);
 
     // Auto-expand on search
     useEffect(() => {
-        if (!search || !currentProject) return;
-
-        const lowerSearch = search.toLowerCase();
-        const idsToExpand = new Set<string>();
-
-        currentProject.folders.forEach(folder => {
-            const hasMatchingNotes = folder.notes.some(note =>
-                note.text.toLowerCase().includes(lowerSearch) ||
-                (note.tags || []).some(t => t.toLowerCase().includes(lowerSearch))
-            );
-            if (hasMatchingNotes || folder.name.toLowerCase().includes(lowerSearch)) {
-                idsToExpand.add(folder.id);
-            }
-        });
+        if (!matches || filteredFolders.length === 0) return;
 
-        if (idsToExpand.size > 0) {
-            setExpandedFolders(prev => {
-                const next = new Set(prev);
-                let changed = false;
-                idsToExpand.forEach(id => {
-                    if (!next.has(id)) {
-                        next.add(id);
-                        changed = true;
-                    }
-                });
-                return changed ? next : prev;
+        setExpandedFolders(prev => {
+            const next = new Set(prev);
+            let changed = false;
+            filteredFolders.forEach(folder => {
+                if (!next.has(folder.id)) {
+                    next.add(folder.id);
+                    changed = true;
+                }
             });
-        }
-    }, [search, currentProject]);
+            return changed ? next : prev;
+        });
+    }, [matches, filteredFolders]);
 
     const toggleFolder = useCallback((folderId: string) => {
         setExpandedFolders(prev => {
@@ -208,7 +225,7 @@ export function useAppLogic() {
         });
     }, []);
 
-    const handleDropItem = useCallback((itemId: string, targetProjectId: string, targetFolderId?: string) => {
+    const handleDropItem = useCallback(async (itemId: string, targetProjectId: string, targetFolderId?: string) => {
         // 1. Try to find in History
         let itemText = history.find(i => i.id === itemId)?.text;
 
@@ -243,16 +260,15 @@ export function useAppLogic() {
             // Default to "General" or create it
             targetFolder = targetProject.folders.find(f => f.name === APP_CONFIG.DEFAULT_FOLDER_NAME);
             if (!targetFolder) {
-                useStore.getState().addFolder(targetProjectId, APP_CONFIG.DEFAULT_FOLDER_NAME);
-                const updatedProjects = useStore.getState().projects;
-                const updatedProject = updatedProjects.find(p => p.id === targetProjectId);
-                targetFolder = updatedProject?.folders.find(f => f.name === APP_CONFIG.DEFAULT_FOLDER_NAME);
+                const folderId = await useStore.getState().addFolder(targetProjectId, APP_CONFIG.DEFAULT_FOLDER_NAME);
+                const updatedProject = useStore.getState().projects.find(p => p.id === targetProjectId);
+                targetFolder = updatedProject?.folders.find(f => f.id === folderId);
             }
         }
 
         if (!targetFolder) return;
 
-        useStore.getState().addNote(targetProjectId, targetFolder.id, itemText, []);
+        await useStore.getState().addNote(targetProjectId, targetFolder.id, itemText, []);
 
         toast.success('Заметка скопирована', {
             description: `Добавлено в ${targetProject.name} / ${targetFolder.name}`
@@ -272,8 +288,9 @@ export function useAppLogic() {
                 break;
             case 'createFolder':
                 if (targetId?.projectId) {
-                    const newId = addFolder(targetId.projectId, value);
-                    setExpandedFolders(prev => new Set(prev).add(newId));
+                    addFolder(targetId.projectId, value).then(newId => {
+                        if (newId) setExpandedFolders(prev => new Set(prev).add(newId));
+                    });
                 }
                 break;
             case 'createNote':
@@ -336,7 +353,7 @@ export function useAppLogic() {
         // State
         activeView, setActiveView,
         selectedProjectId, setSelectedProjectId,
-        search, setSearch,
+        search, setSearch, searchError,
         focusMode, setFocusMode,
         isPinned, togglePin,
         expandedFolders, setExpandedFolders, toggleFolder,
@@ -0,0 +1,25 @@
+// src/hooks/useClipboardEvents.ts
+import { useEffect } from 'react';
+import { listen } from '@tauri-apps/api/event';
+import { useStore } from '../store';
+import { logger } from '../lib/logger';
+
+/** Backend events after which the history on screen is out of date. */
+const HISTORY_EVENTS = ['clipboard://changed', 'clipboard://pruned'] as const;
+
+/**
+ * Capture runs natively and retention prunes in the background, so the
+ * webview never reads the clipboard itself: it reloads when told to.
+ */
+export function useClipboardEvents() {
+  const reload = useStore((state) => state.reload);
+
+  useEffect(() => {
+    const unlisteners = HISTORY_EVENTS.map((event) =>
+      listen(event, () => {
+        reload().catch((err) => logger.error(`Reload after ${event} failed:`, err));
+      })
+    );
+    return () => { unlisteners.forEach((unlisten) => unlisten.then((fn) => fn())); };
+  }, [reload]);
+}
@@ -1,65 +1,82 @@
 // src/hooks/useImportExport.ts
+import { invoke } from '@tauri-apps/api/core';
 import { useStore } from '../store';
 import { logger } from '../lib/logger';
+import { BackupError, ImportCounts, MergeReport } from '../types';
 
 interface UseImportExportReturn {
     importData: (e: React.ChangeEvent<HTMLInputElement>) => void;
-    exportData: () => void;
+    exportData: (includeSecrets?: boolean) => void;
+}
+
+/**
+ * Asks the backend for a backup bundle (data + images) and saves it as a
+ * `.zip` download. Items flagged as containing credentials are left out
+ * unless asked for.
+ */
+export async function downloadBackup(includeSecrets = false): Promise<void> {
+    const zip = await invoke<ArrayBuffer>('export_bundle', { includeSecrets });
+
+    const blob = new Blob([zip], { type: 'application/zip' });
+    const url = URL.createObjectURL(blob);
+    const a = document.createElement('a');
+    a.href = url;
+    a.download = `clipka-backup-${new Date().toISOString().slice(0, 10)}.zip`;
+    document.body.appendChild(a);
+    a.click();
+    document.body.removeChild(a);
+    URL.revokeObjectURL(url);
+}
+
+/** Restores a `.zip` bundle or a plain JSON backup from older versions. */
+async function restoreFile(file: File): Promise<ImportCounts> {
+    const bytes = new Uint8Array(await file.arrayBuffer());
+    const isZip = bytes[0] === 0x50 && bytes[1] === 0x4b; // "PK"
+    return isZip
+        ? invoke<ImportCounts>('restore_bundle', bytes)
+        : invoke<ImportCounts>('restore_backup', { json: new TextDecoder().decode(bytes) });
+}
+
+/**
+ * Merges a backup file into the current data. With `dryRun` only reports
+ * what would be added, skipped and renamed.
+ */
+export async function mergeFile(file: File, dryRun: boolean): Promise<MergeReport> {
+    const bytes = new Uint8Array(await file.arrayBuffer());
+    const isZip = bytes[0] === 0x50 && bytes[1] === 0x4b; // "PK"
+    if (isZip) {
+        return invoke<MergeReport>(dryRun ? 'preview_merge_bundle' : 'merge_bundle', bytes);
+    }
+    return invoke<MergeReport>('merge_backup', { json: new TextDecoder().decode(bytes), dryRun });
 }
 
 /**
  * Handles import/export functionality for backup and restore.
+ * Validation happens in Rust: a broken file never replaces anything.
  */
 export function useImportExport(): UseImportExportReturn {
-    const { history, projects, globalTags, setHistory, setProjects, setGlobalTags } = useStore();
+    const reload = useStore((state) => state.reload);
 
-    const importData = (e: React.ChangeEvent<HTMLInputElement>) => {
+    const importData = async (e: React.ChangeEvent<HTMLInputElement>) => {
         const file = e.target.files?.[0];
         if (!file) return;
-
-        const reader = new FileReader();
-        reader.onload = async (ev) => {
-            try {
-                const data = JSON.parse(ev.target?.result as string);
-                if (!data || !Array.isArray(data.history) || !Array.isArray(data.projects)) {
-                    throw new Error('Invalid format');
-                }
-                if (data.history) setHistory(data.history);
-                if (data.projects) setProjects(data.projects);
-                if (Array.isArray(data.globalTags)) setGlobalTags(data.globalTags);
-                alert('✅ Данные восстановлены!');
-            } catch (err) {
-                logger.error('Import failed:', err);
-                alert('❌ Ошибка файла');
-            }
-        };
-        reader.readAsText(file);
         e.target.value = '';
+
+        try {
+            const counts = await restoreFile(file);
+            await reload();
+            const purged = counts.trashPurged > 0 ? `\nУдалено из корзины: ${counts.trashPurged}` : '';
+            alert(`✅ Данные восстановлены: ${counts.projects} проектов, ${counts.notes} заметок, ${counts.history} записей${purged}`);
+        } catch (err) {
+            logger.error('Import failed:', err);
+            const problems = (err as BackupError)?.errors;
+            const details = problems?.slice(0, 5).map(p => `• ${p.path}: ${p.message}`).join('\n');
+            alert(details ? `❌ Ошибка файла:\n${details}` : '❌ Ошибка файла');
+        }
     };
 
-    const exportData = () => {
-        const blob = new Blob(
-            [
-                JSON.stringify(
-                    {
-                        history,
-                        projects,
-                        globalTags,
-                        version: 2,
-                        date: new Date().toISOString(),
-                    },
-                    null,
-                    2
-                ),
-            ],
-            { type: 'application/json' }
-        );
-        const url = URL.createObjectURL(blob);
-        const a = document.createElement('a');
-        a.href = url;
-        a.download = `backup-${new Date().toISOString().slice(0, 10)}.json`;
-        a.click();
-        URL.revokeObjectURL(url);
+    const exportData = (includeSecrets = false) => {
+        downloadBackup(includeSecrets).catch(err => logger.error('Export failed:', err));
     };
 
     return { importData, exportData };
@@ -8,6 +8,22 @@ export function cn(...inputs: ClassValue[]) {
   return twMerge(clsx(inputs));
 }
 
+// --- Date Utils ---
+/** Creation fields for an item made right now. */
+export function timestamps() {
+  const now = Date.now();
+  return { createdAt: now, updatedAt: now, utcOffset: -new Date(now).getTimezoneOffset() };
+}
+
+/** Time of day for today's items, date and time for older ones. */
+export function formatCreated(item: { createdAt?: number; date?: string }): string {
+  if (!item.createdAt) return item.date ?? '';
+  const created = new Date(item.createdAt);
+  const time = created.toLocaleTimeString([], { hour: '2-digit', minute: '2-digit' });
+  if (created.toDateString() === new Date().toDateString()) return time;
+  return `${created.toLocaleDateString([], { day: 'numeric', month: 'short' })} ${time}`;
+}
+
 // --- Binary Utils ---
 /**
  * Converts a Uint8Array to a Base64 string.
@@ -1,10 +1,11 @@
 // src/store.ts
 import { create } from 'zustand';
-import { DEFAULT_PROJECT, APP_CONFIG } from './constants';
-import { detectContentType } from './lib/utils';
+import { invoke, type InvokeArgs } from '@tauri-apps/api/core';
+import { DEFAULT_PROJECT } from './constants';
+import { detectContentType, timestamps } from './lib/utils';
 import { logger } from './lib/logger';
-import { saveToDB, loadFromDB } from './db';
-import type { Project, HistoryItem, ClipboardContent } from './types';
+import { migrateLegacyData } from './db';
+import type { Project, HistoryItem, AppData, Folder, NoteItem } from './types';
 
 interface AppState {
     projects: Project[];
@@ -13,260 +14,140 @@ interface AppState {
     isDbLoaded: boolean;
 
     initData: () => Promise<void>;
-
-    // Setters (Internal/Low-level)
-    setProjects: (projects: Project[]) => void;
-    setHistory: (history: HistoryItem[]) => void;
-    setGlobalTags: (tags: string[]) => void;
+    /** Re-reads everything from the backend, e.g. after a restore or merge. */
+    reload: () => Promise<void>;
 
     // Actions
-    addProject: (name: string) => void;
-    deleteProject: (id: string) => void;
-    renameProject: (id: string, newName: string) => void;
-
-    addFolder: (projectId: string, name: string) => string;
-    deleteFolder: (projectId: string, folderId: string) => void;
-    renameFolder: (projectId: string, folderId: string, newName: string) => void;
+    addProject: (name: string) => Promise<void>;
+    deleteProject: (id: string) => Promise<void>;
+    renameProject: (id: string, newName: string) => Promise<void>;
 
-    addNote: (projectId: string, folderId: string, text: string, tags?: string[]) => void;
-    editNote: (projectId: string, folderId: string, noteId: string, text: string, tags?: string[]) => void;
-    deleteNote: (projectId: string, folderId: string, noteId: string) => void;
+    addFolder: (projectId: string, name: string) => Promise<string | undefined>;
+    deleteFolder: (projectId: string, folderId: string) => Promise<void>;
+    renameFolder: (projectId: string, folderId: string, newName: string) => Promise<void>;
+    reorderFolders: (projectId: string, folders: Folder[]) => Promise<void>;
 
-    processClipboardContent: (content: ClipboardContent) => void;
+    addNote: (projectId: string, folderId: string, text: string, tags?: string[]) => Promise<void>;
+    editNote: (projectId: string, folderId: string, noteId: string, text: string, tags?: string[]) => Promise<void>;
+    deleteNote: (projectId: string, folderId: string, noteId: string) => Promise<void>;
+    reorderNotes: (projectId: string, folderId: string, notes: NoteItem[]) => Promise<void>;
 
-    deleteHistoryItem: (id: string) => void;
-    restoreHistoryItem: (item: HistoryItem) => void;
-    clearHistory: () => void;
+    deleteHistoryItem: (id: string) => Promise<void>;
+    restoreHistoryItem: (item: HistoryItem) => Promise<void>;
+    clearHistory: () => Promise<void>;
 
-    addGlobalTag: (tag: string) => void;
-    deleteGlobalTag: (tag: string) => void;
+    addGlobalTag: (tag: string) => Promise<void>;
+    deleteGlobalTag: (tag: string) => Promise<void>;
 
-    toggleFavorite: (id: string) => void;
+    toggleFavorite: (id: string) => Promise<void>;
 }
 
-// 🛠️ Improved Debounce Logic
-const createDebouncedSaver = () => {
-    const timeouts = new Map<string, ReturnType<typeof setTimeout>>();
-
-    return (key: string, data: any) => {
-        if (timeouts.has(key)) {
-            clearTimeout(timeouts.get(key));
-        }
-
-        const timeout = setTimeout(() => {
-            saveToDB(key, data, (err) => {
-                if (err) logger.error(`Failed to save ${key}:`, err);
-            });
-            timeouts.delete(key);
-        }, APP_CONFIG.SAVE_DEBOUNCE_DELAY);
-
-        timeouts.set(key, timeout);
-    };
-};
-const debouncedSave = createDebouncedSaver();
-
-export const useStore = create<AppState>((set, get) => ({
-    projects: [DEFAULT_PROJECT],
-    history: [],
-    globalTags: [],
-    isDbLoaded: false,
-
-    initData: async () => {
+// 💾 SQLite in the backend is the only copy; the store just mirrors it
+export const useStore = create<AppState>((set, get) => {
+    /** Runs a command, then reloads so the UI shows what was actually stored. */
+    const mutate = async <T>(command: string, args?: InvokeArgs): Promise<T | undefined> => {
         try {
-            const [dbHistory, dbProjects, dbGlobalTags] = await Promise.all([
-                loadFromDB('history'),
-                loadFromDB('projects'),
-                loadFromDB('globalTags')
-            ]);
-
-            set({
-                history: dbHistory || [],
-                projects: dbProjects || [DEFAULT_PROJECT],
-                globalTags: dbGlobalTags || [],
-                isDbLoaded: true
-            });
+            const result = await invoke<T>(command, args);
+            await get().reload();
+            return result;
         } catch (err) {
-            logger.error("Failed to initialize DB:", err);
+            logger.error(`${command} failed:`, err);
+            return undefined;
         }
-    },
-
-    setProjects: (projects) => {
-        set({ projects });
-        debouncedSave('projects', projects);
-    },
-
-    setHistory: (history) => {
-        set({ history });
-        debouncedSave('history', history);
-    },
-
-    setGlobalTags: (globalTags) => {
-        set({ globalTags });
-        debouncedSave('globalTags', globalTags);
-    },
-
-    // --- Project Actions ---
-    addProject: (name) => {
-        const newProjects = [...get().projects, { id: Date.now().toString(), name, folders: [] }];
-        get().setProjects(newProjects);
-    },
-    deleteProject: (id) => {
-        get().setProjects(get().projects.filter((p) => p.id !== id));
-    },
-    renameProject: (id, newName) => {
-        get().setProjects(get().projects.map((p) => p.id === id ? { ...p, name: newName } : p));
-    },
+    };
 
-    // --- Folder Actions ---
-    addFolder: (projectId, name) => {
-        const newFolderId = Date.now().toString();
-        const store = get();
-        const newProjects = store.projects.map((p) =>
-            p.id !== projectId ? p : {
-                ...p,
-                folders: [...p.folders, { id: newFolderId, name, notes: [] }]
-            }
-        );
-        store.setProjects(newProjects);
-        return newFolderId;
-    },
-    deleteFolder: (projectId, folderId) => {
-        const store = get();
-        store.setProjects(store.projects.map((p) =>
-            p.id !== projectId ? p : {
-                ...p,
-                folders: p.folders.filter((f) => f.id !== folderId)
-            }
-        ));
-    },
-    renameFolder: (projectId, folderId, newName) => {
-        const store = get();
-        store.setProjects(store.projects.map((p) =>
-            p.id !== projectId ? p : {
-                ...p,
-                folders: p.folders.map((f) => f.id === folderId ? { ...f, name: newName } : f)
-            }
-        ));
-    },
+    const updateProject = (projectId: string, update: (p: Project) => Project) => {
+        set({ projects: get().projects.map((p) => p.id === projectId ? update(p) : p) });
+    };
 
-    // --- Note Actions ---
-    addNote: (projectId, folderId, text, tags = []) => {
-        const store = get();
-        store.setProjects(store.projects.map((p) =>
-            p.id !== projectId ? p : {
-                ...p,
-                folders: p.folders.map((f) =>
-                    f.id !== folderId ? f : {
-                        ...f,
-                        notes: [...f.notes, {
-                            id: Date.now().toString(),
-                            text,
-                            date: new Date().toLocaleTimeString([], { hour: '2-digit', minute: '2-digit' }),
-                            contentType: detectContentType(text),
-                            tags
-                        }]
-                    }
-                )
-            }
-        ));
-    },
-    editNote: (projectId, folderId, noteId, text, tags) => {
-        const store = get();
-        store.setProjects(store.projects.map((p) =>
-            p.id !== projectId ? p : {
-                ...p,
-                folders: p.folders.map((f) =>
-                    f.id !== folderId ? f : {
-                        ...f,
-                        notes: f.notes.map((n) =>
-                            n.id === noteId ? {
-                                ...n,
-                                text,
-                                contentType: detectContentType(text),
-                                tags: tags !== undefined ? tags : n.tags
-                            } : n
-                        )
-                    }
-                )
+    return {
+        projects: [DEFAULT_PROJECT],
+        history: [],
+        globalTags: [],
+        isDbLoaded: false,
+
+        initData: async () => {
+            try {
+                await migrateLegacyData();
+                await get().reload();
+                set({ isDbLoaded: true });
+            } catch (err) {
+                logger.error("Failed to initialize DB:", err);
             }
-        ));
-    },
-    deleteNote: (projectId, folderId, noteId) => {
-        const store = get();
-        store.setProjects(store.projects.map((p) =>
-            p.id !== projectId ? p : {
+        },
+
+        reload: async () => {
+            const data = await invoke<AppData>('load_app_data');
+            set({ history: data.history, projects: data.projects, globalTags: data.globalTags });
+        },
+
+        // --- Project Actions ---
+        addProject: async (name) => {
+            await mutate('add_project', { name });
+        },
+        deleteProject: async (id) => {
+            await mutate('delete_project', { id });
+        },
+        renameProject: async (id, newName) => {
+            await mutate('rename_project', { id, name: newName });
+        },
+
+        // --- Folder Actions ---
+        addFolder: (projectId, name) => mutate<string>('add_folder', { projectId, name }),
+        deleteFolder: async (_projectId, folderId) => {
+            await mutate('delete_folder', { id: folderId });
+        },
+        renameFolder: async (_projectId, folderId, newName) => {
+            await mutate('rename_folder', { id: folderId, name: newName });
+        },
+        reorderFolders: async (projectId, folders) => {
+            // Show the new order right away; dragging shouldn't wait for a round trip
+            updateProject(projectId, (p) => ({ ...p, folders }));
+            await mutate('reorder_folders', { projectId, ids: folders.map((f) => f.id) });
+        },
+
+        // --- Note Actions ---
+        addNote: async (_projectId, folderId, text, tags = []) => {
+            const note = { id: '', text, ...timestamps(), contentType: detectContentType(text), tags };
+            await mutate('add_note', { folderId, note });
+        },
+        editNote: async (_projectId, _folderId, noteId, text, tags) => {
+            await mutate('edit_note', { id: noteId, text, contentType: detectContentType(text), tags });
+        },
+        deleteNote: async (_projectId, _folderId, noteId) => {
+            await mutate('delete_note', { id: noteId });
+        },
+        reorderNotes: async (projectId, folderId, notes) => {
+            updateProject(projectId, (p) => ({
                 ...p,
-                folders: p.folders.map((f) =>
-                    f.id !== folderId ? f : {
-                        ...f,
-                        notes: f.notes.filter((n) => n.id !== noteId)
-                    }
-                )
-            }
-        ));
-    },
-
-    // --- Clipboard Logic ---
-    processClipboardContent: (content) => {
-        const state = get();
-        const history = state.history;
-
-        // 1. Strict Duplicate Check
-        if (history.length > 0) {
-            const lastItem = history[0];
-
-            // Text Comparison
-            if (content.type === 'text' && lastItem.text === content.value) return;
-
-            // Image Comparison (Filename based)
-            if (content.type === 'image' &&
-                lastItem.contentType === 'image' &&
-                lastItem.imageData === content.value) {
-                return;
-            }
-        }
-
-        const newItem: HistoryItem = {
-            id: Date.now().toString(),
-            date: new Date().toLocaleTimeString([], { hour: '2-digit', minute: '2-digit' }),
-            text: content.type === 'text' ? content.value : 'Image',
-            contentType: content.type === 'image' ? 'image' : detectContentType(content.value),
-            imageData: content.type === 'image' ? content.value : undefined,
-        };
-
-        const newHistory = [newItem, ...state.history].slice(0, APP_CONFIG.MAX_HISTORY_ITEMS);
-        get().setHistory(newHistory);
-    },
-
-    deleteHistoryItem: (id) => {
-        get().setHistory(get().history.filter((i) => i.id !== id));
-    },
-    restoreHistoryItem: (item) => {
-        // Restore at the beginning (most recent)
-        const currentHistory = get().history;
-        // Avoid duplicates
-        if (currentHistory.some(h => h.id === item.id)) return;
-        get().setHistory([item, ...currentHistory]);
-    },
-    clearHistory: () => {
-        get().setHistory([]);
-    },
-
-    // --- Tag Actions ---
-    addGlobalTag: (tag) => {
-        if (!get().globalTags.includes(tag)) {
-            get().setGlobalTags([...get().globalTags, tag]);
-        }
-    },
-    deleteGlobalTag: (tag) => {
-        get().setGlobalTags(get().globalTags.filter(t => t !== tag));
-    },
-
-    // --- Favorite Toggle ---
-    toggleFavorite: (id) => {
-        const newHistory = get().history.map((item) =>
-            item.id === id ? { ...item, isFavorite: !item.isFavorite } : item
-        );
-        get().setHistory(newHistory);
-    },
-}));
\ No newline at end of file
+                folders: p.folders.map((f) => f.id === folderId ? { ...f, notes } : f)
+            }));
+            await mutate('reorder_notes', { folderId, ids: notes.map((n) => n.id) });
+        },
+
+        // --- History Actions ---
+        // New items come from the native watcher (see useClipboardEvents)
+        deleteHistoryItem: async (id) => {
+            await mutate('delete_history_item', { id });
+        },
+        restoreHistoryItem: async (item) => {
+            await mutate('restore_history_item', { item });
+        },
+        clearHistory: async () => {
+            await mutate('clear_history');
+        },
+
+        // --- Tag Actions ---
+        addGlobalTag: async (tag) => {
+            await mutate('add_global_tag', { tag });
+        },
+        deleteGlobalTag: async (tag) => {
+            await mutate('delete_global_tag', { tag });
+        },
+
+        // --- Favorite Toggle ---
+        toggleFavorite: async (id) => {
+            await mutate('toggle_favorite', { id });
+        },
+    };
+});
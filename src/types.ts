@@ -1,19 +1,25 @@
 // src/types.ts
 
-export type ContentType = 'url' | 'color' | 'code' | 'text' | 'image';
+export type ContentType =
+  | 'url' | 'color' | 'code' | 'text' | 'image'
+  | 'email' | 'phone' | 'path' | 'json' | 'yaml' | 'sql' | 'shell' | 'stackTrace'
+  | 'uuid' | 'ip' | 'date' | 'number';
 
-/**
- * Represents the payload coming from the Clipboard Monitor.
- * Used to normalize data before it hits the Store.
- */
-export type ClipboardContent =
-  | { type: 'text'; value: string }
-  | { type: 'image'; value: string }; // value is the Filename (e.g. "img_uuid.png")
+/** Language of code-like clips; the names are syntax highlighter ids. */
+export type Language =
+  | 'rust' | 'typescript' | 'python' | 'go' | 'sql' | 'bash'
+  | 'html' | 'css' | 'json' | 'yaml' | 'php' | 'c' | 'cpp';
 
 export interface NoteItem {
   id: string;
   text: string;
-  date: string;
+  /** Unix ms, UTC. */
+  createdAt: number;
+  updatedAt: number;
+  /** Minutes east of UTC where the item was made. */
+  utcOffset: number;
+  /** Local "HH:MM" on records saved before `createdAt` existed. */
+  date?: string;
   contentType: ContentType;
   tags?: string[];
   /**
@@ -21,7 +27,10 @@ export interface NoteItem {
    * The actual image data is loaded asynchronously by the component.
    */
   imageData?: string;
+  language?: Language;
   isFavorite?: boolean;
+  /** Contains a credential; left out of search and exports. */
+  isSecret?: boolean;
 }
 
 export interface Folder {
@@ -39,12 +48,112 @@ export interface Project {
 export interface HistoryItem {
   id: string;
   text: string;
-  date: string;
+  /** Unix ms, UTC. */
+  createdAt: number;
+  updatedAt: number;
+  /** Minutes east of UTC where the item was made. */
+  utcOffset: number;
+  /** Local "HH:MM" on records saved before `createdAt` existed. */
+  date?: string;
   contentType: ContentType;
   /**
    * For images: Stores the filename located in `AppLocalData/images/`.
    * Legacy support: May contain "data:image..." base64 strings (rare).
    */
   imageData?: string;
+  language?: Language;
   isFavorite?: boolean;
-}
\ No newline at end of file
+  /** Contains a credential; left out of search and exports. */
+  isSecret?: boolean;
+  /** Unix ms after which the backend deletes the item. */
+  expiresAt?: number;
+}
+
+/** Relative day buckets returned by `history_by_period`. */
+export type Period = 'today' | 'yesterday' | 'lastWeek' | 'lastMonth' | 'older';
+
+export interface HistoryGroup {
+  period: Period;
+  items: HistoryItem[];
+}
+
+/** Encryption-at-rest state reported by the backend. */
+export type VaultStatus = 'off' | 'locked' | 'unlocked';
+
+/** Snapshot returned by `load_app_data`. */
+export interface AppData {
+  projects: Project[];
+  history: HistoryItem[];
+  globalTags: string[];
+}
+
+/** Records brought in by a restore or import. */
+export interface ImportCounts {
+  projects: number;
+  folders: number;
+  notes: number;
+  history: number;
+  tags: number;
+  /** Trash entries a restore dropped because the backup reuses their ids. */
+  trashPurged: number;
+}
+
+/** Outcome of the one-time move from IndexedDB into the backend store. */
+export interface MigrationReport {
+  alreadyMigrated: boolean;
+  imported: ImportCounts;
+  skipped: number;
+  /** Records the backend couldn't read; they stay behind in IndexedDB. */
+  failures: { path: string; id: string | null; error: string }[];
+}
+
+/** Rejected backup: every problem found, with the path of the field. */
+export interface BackupError {
+  kind: 'invalidBackup';
+  message: string;
+  errors: { path: string; message: string }[];
+}
+
+/** Outcome (or preview, with `dryRun`) of merging a backup into the store. */
+export interface MergeReport {
+  dryRun: boolean;
+  added: ImportCounts;
+  /** Already present, or merged into an existing project/folder. */
+  skipped: ImportCounts;
+  /** Records whose id was taken by something else and got a new one. */
+  conflicts: { path: string; id: string; newId: string }[];
+}
+
+export type TrashKind = 'project' | 'folder' | 'note';
+
+/** A deleted project, folder or note, restorable until it is purged. */
+export interface TrashEntry {
+  id: string;
+  kind: TrashKind;
+  /** Empty for secret notes. */
+  title: string;
+  parentId: string | null;
+  /** False when the original project/folder is gone and another must be chosen. */
+  parentExists: boolean;
+  deletedAt: number;
+}
+
+/** One match of `filter_items`; offsets are UTF-16, like JS strings. */
+export interface SearchHit {
+  kind: 'history' | 'note' | 'tag' | 'project' | 'folder';
+  id: string;
+  /** Folder of a note, project of a folder. */
+  parentId?: string;
+  text: string;
+  highlights: { start: number; end: number }[];
+  matchedTags?: string[];
+  score: number;
+}
+
+/** A search box query the backend couldn't parse, with the culprit's span. */
+export interface QueryError {
+  kind: 'invalidQuery';
+  message: string;
+  start: number;
+  end: number;
+}